@@ -0,0 +1,53 @@
+-- Redemption voucher: idempotensi dan kuota dijaga database, bukan lock per proses,
+-- supaya tetap benar dengan lebih dari satu instance API.
+
+create unique index if not exists voucher_redemption_redemption_key_key
+    on voucher_redemption (redemption_key);
+
+create index if not exists voucher_redemption_voucher_store_idx
+    on voucher_redemption (voucher_code, store_id);
+
+-- Cek kuota + insert dalam satu transaksi. Advisory lock per voucher menserialisasi
+-- redeem voucher yang sama lintas instance; unique index di atas menangkap
+-- redemption_key yang sama untuk voucher berbeda.
+create or replace function redeem_voucher(
+    p_voucher_code text,
+    p_promo_tenor_id uuid,
+    p_store_id uuid,
+    p_redemption_key text,
+    p_amount bigint,
+    p_max_usage integer,
+    p_max_usage_per_store integer
+) returns jsonb
+language plpgsql
+as $$
+declare
+    v_row voucher_redemption;
+begin
+    perform pg_advisory_xact_lock(hashtext('voucher_redemption:' || p_voucher_code));
+
+    select * into v_row from voucher_redemption where redemption_key = p_redemption_key;
+    if found then
+        return jsonb_build_object('outcome', 'replayed', 'redemption', to_jsonb(v_row));
+    end if;
+
+    if p_max_usage is not null
+        and (select count(*) from voucher_redemption where voucher_code = p_voucher_code) >= p_max_usage
+    then
+        return jsonb_build_object('outcome', 'global_limit_reached');
+    end if;
+
+    if p_max_usage_per_store is not null
+        and (select count(*) from voucher_redemption
+             where voucher_code = p_voucher_code and store_id = p_store_id) >= p_max_usage_per_store
+    then
+        return jsonb_build_object('outcome', 'store_limit_reached');
+    end if;
+
+    insert into voucher_redemption (voucher_code, promo_tenor_id, store_id, redemption_key, amount)
+    values (p_voucher_code, p_promo_tenor_id, p_store_id, p_redemption_key, p_amount)
+    returning * into v_row;
+
+    return jsonb_build_object('outcome', 'redeemed', 'redemption', to_jsonb(v_row));
+end;
+$$;
@@ -0,0 +1,82 @@
+-- Ledger transaksi pembiayaan: idempotensi `transaction_ref` dan batas anggaran dijaga
+-- database, bukan lock per proses, supaya tetap benar dengan lebih dari satu instance API.
+
+create unique index if not exists financed_transaction_transaction_ref_key
+    on financed_transaction (transaction_ref);
+
+create index if not exists financed_transaction_promo_store_idx
+    on financed_transaction (promo_id, store_id);
+
+-- Cek batas anggaran promo/store + insert dalam satu transaksi. Advisory lock per promo
+-- menserialisasi pencatatan promo yang sama lintas instance; pemakaian dihitung dengan
+-- agregat sehingga API tidak perlu memuat seluruh ledger. Batas `null` = tanpa batas.
+create or replace function record_financed_transaction(
+    p_row jsonb,
+    p_promo_max_subsidy numeric,
+    p_promo_max_transactions integer,
+    p_store_max_subsidy numeric,
+    p_store_max_transactions integer
+) returns jsonb
+language plpgsql
+as $$
+declare
+    v_new financed_transaction;
+    v_row financed_transaction;
+    v_promo_count bigint;
+    v_promo_subsidy bigint;
+    v_store_count bigint;
+    v_store_subsidy bigint;
+begin
+    v_new := jsonb_populate_record(null::financed_transaction, p_row);
+    perform pg_advisory_xact_lock(hashtext('financed_transaction:' || v_new.promo_id::text));
+
+    select * into v_row from financed_transaction where transaction_ref = v_new.transaction_ref;
+    if found then
+        return jsonb_build_object('outcome', 'replayed', 'transaction', to_jsonb(v_row));
+    end if;
+
+    select count(*), coalesce(sum(subsidy), 0) into v_promo_count, v_promo_subsidy
+    from financed_transaction where promo_id = v_new.promo_id;
+    select count(*), coalesce(sum(subsidy), 0) into v_store_count, v_store_subsidy
+    from financed_transaction where promo_id = v_new.promo_id and store_id = v_new.store_id;
+
+    if (p_promo_max_transactions is not null and v_promo_count + 1 > greatest(p_promo_max_transactions, 0))
+        or (p_promo_max_subsidy is not null and v_promo_subsidy + v_new.subsidy > p_promo_max_subsidy)
+    then
+        return jsonb_build_object(
+            'outcome', 'promo_budget_exceeded',
+            'promo_usage', jsonb_build_object('transactions', v_promo_count, 'subsidy', v_promo_subsidy)
+        );
+    end if;
+
+    if (p_store_max_transactions is not null and v_store_count + 1 > greatest(p_store_max_transactions, 0))
+        or (p_store_max_subsidy is not null and v_store_subsidy + v_new.subsidy > p_store_max_subsidy)
+    then
+        return jsonb_build_object(
+            'outcome', 'store_budget_exceeded',
+            'store_usage', jsonb_build_object('transactions', v_store_count, 'subsidy', v_store_subsidy)
+        );
+    end if;
+
+    insert into financed_transaction (
+        transaction_ref, promo_id, promo_tenor_id, store_id, tenor, amount, discount,
+        financed_amount, admin_fee, total_interest, subsidy, free_installment_value,
+        interest_rate, subsidi_rate, created_by
+    )
+    values (
+        v_new.transaction_ref, v_new.promo_id, v_new.promo_tenor_id, v_new.store_id, v_new.tenor,
+        v_new.amount, v_new.discount, v_new.financed_amount, v_new.admin_fee, v_new.total_interest,
+        v_new.subsidy, v_new.free_installment_value, v_new.interest_rate, v_new.subsidi_rate,
+        v_new.created_by
+    )
+    returning * into v_row;
+
+    -- Pemakaian setelah transaksi ini, dipakai API untuk menandai `budget_exhausted`
+    return jsonb_build_object(
+        'outcome', 'recorded',
+        'transaction', to_jsonb(v_row),
+        'promo_usage', jsonb_build_object('transactions', v_promo_count + 1, 'subsidy', v_promo_subsidy + v_new.subsidy),
+        'store_usage', jsonb_build_object('transactions', v_store_count + 1, 'subsidy', v_store_subsidy + v_new.subsidy)
+    );
+end;
+$$;
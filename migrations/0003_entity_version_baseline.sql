@@ -0,0 +1,26 @@
+-- Baseline riwayat versi untuk baris yang sudah ada sebelum `entity_version` dipakai:
+-- satu versi `create` berisi isi baris saat ini, bertanggal `created_at` baris tersebut.
+-- Hanya dibuat untuk entity yang belum punya versi, jadi aman dijalankan ulang.
+
+insert into entity_version (entity_type, entity_id, promo_id, store_id, operation, snapshot, created_at)
+select 'promo', p.id_promo::text, p.id_promo, null, 'create', to_jsonb(p), coalesce(p.created_at, now())
+from promo p
+where not exists (
+    select 1 from entity_version v where v.entity_type = 'promo' and v.entity_id = p.id_promo::text
+);
+
+insert into entity_version (entity_type, entity_id, promo_id, store_id, operation, snapshot, created_at)
+select 'promo_tenor', t.id::text, t.promo_id, null, 'create', to_jsonb(t), coalesce(t.created_at, now())
+from promo_tenor t
+where not exists (
+    select 1 from entity_version v where v.entity_type = 'promo_tenor' and v.entity_id = t.id::text
+);
+
+insert into entity_version (entity_type, entity_id, promo_id, store_id, operation, snapshot, created_at)
+select 'promo_store', ps.promo_id::text || '-' || ps.store_id::text, ps.promo_id, ps.store_id, 'create',
+       to_jsonb(ps), coalesce(ps.created_at, now())
+from promo_store ps
+where not exists (
+    select 1 from entity_version v
+    where v.entity_type = 'promo_store' and v.entity_id = ps.promo_id::text || '-' || ps.store_id::text
+);
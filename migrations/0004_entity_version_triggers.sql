@@ -0,0 +1,71 @@
+-- Riwayat versi dicatat oleh trigger, bukan oleh API: snapshot masuk dalam transaksi
+-- yang sama dengan insert/update/delete-nya, jadi tidak ada perubahan yang lolos tanpa
+-- versi (termasuk perubahan langsung dari SQL editor). `entity_version` sendiri
+-- dikunci dari update/delete supaya riwayat tidak bisa diubah. Aman dijalankan ulang.
+
+create or replace function record_entity_version()
+returns trigger
+language plpgsql
+security definer
+set search_path = public
+as $$
+declare
+    v_row jsonb;
+    v_entity_id text;
+    v_promo_id uuid;
+    v_store_id uuid;
+begin
+    if tg_op = 'DELETE' then
+        v_row := to_jsonb(old);
+    else
+        v_row := to_jsonb(new);
+    end if;
+
+    if tg_table_name = 'promo' then
+        v_entity_id := v_row ->> 'id_promo';
+        v_promo_id := (v_row ->> 'id_promo')::uuid;
+    elsif tg_table_name = 'promo_tenor' then
+        v_entity_id := v_row ->> 'id';
+        v_promo_id := (v_row ->> 'promo_id')::uuid;
+    else
+        -- Key `{promo_id}-{store_id}` supaya riwayat tetap satu meski relasi dibuat ulang
+        v_entity_id := (v_row ->> 'promo_id') || '-' || (v_row ->> 'store_id');
+        v_promo_id := (v_row ->> 'promo_id')::uuid;
+        v_store_id := (v_row ->> 'store_id')::uuid;
+    end if;
+
+    insert into entity_version (entity_type, entity_id, promo_id, store_id, operation, snapshot, created_at)
+    values (tg_table_name, v_entity_id, v_promo_id, v_store_id, lower(tg_op), v_row, clock_timestamp());
+
+    return null;
+end;
+$$;
+
+drop trigger if exists promo_entity_version on promo;
+create trigger promo_entity_version
+    after insert or update or delete on promo
+    for each row execute function record_entity_version();
+
+drop trigger if exists promo_tenor_entity_version on promo_tenor;
+create trigger promo_tenor_entity_version
+    after insert or update or delete on promo_tenor
+    for each row execute function record_entity_version();
+
+drop trigger if exists promo_store_entity_version on promo_store;
+create trigger promo_store_entity_version
+    after insert or update or delete on promo_store
+    for each row execute function record_entity_version();
+
+create or replace function reject_entity_version_change()
+returns trigger
+language plpgsql
+as $$
+begin
+    raise exception 'entity_version is append-only';
+end;
+$$;
+
+drop trigger if exists entity_version_immutable on entity_version;
+create trigger entity_version_immutable
+    before update or delete on entity_version
+    for each row execute function reject_entity_version_change();
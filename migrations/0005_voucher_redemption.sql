@@ -0,0 +1,19 @@
+-- Voucher: kuota dan expiry per tenor, ledger redemption. Idempotent.
+
+create extension if not exists pgcrypto;
+
+alter table promo_tenor add column if not exists voucher_max_usage integer;
+alter table promo_tenor add column if not exists voucher_max_usage_per_store integer;
+alter table promo_tenor add column if not exists voucher_expires_at text;
+
+-- Sengaja tanpa foreign key ke promo/store: baris ledger harus tetap ada setelah promo
+-- atau store dihapus.
+create table if not exists voucher_redemption (
+    id uuid primary key default gen_random_uuid(),
+    voucher_code text not null,
+    promo_tenor_id uuid not null,
+    store_id uuid not null,
+    redemption_key text not null,
+    amount bigint not null check (amount > 0),
+    created_at timestamptz not null default now()
+);
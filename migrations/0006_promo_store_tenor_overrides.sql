@@ -0,0 +1,4 @@
+-- Override term tenor per store. Idempotent.
+
+-- `tenor_overrides`: array `{ tenor_id, subsidi?, admin?, discount?, max_discount?, min_transaction? }`
+alter table promo_store add column if not exists tenor_overrides jsonb;
@@ -0,0 +1,4 @@
+-- Tag bebas untuk targeting bulk promo-store (`selector.type = tag`), disimpan huruf kecil.
+-- Idempotent.
+
+alter table store add column if not exists tags text[] not null default '{}';
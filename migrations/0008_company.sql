@@ -0,0 +1,14 @@
+-- Company sebagai entity sendiri; `store.company` (teks) dipertahankan untuk store lama.
+-- Idempotent.
+
+create table if not exists company (
+    id uuid primary key default gen_random_uuid(),
+    name text not null,
+    normalized_name text not null,
+    created_at timestamptz not null default now(),
+    updated_at timestamptz not null default now()
+);
+
+create unique index if not exists company_normalized_name_key on company (normalized_name);
+
+alter table store add column if not exists company_id uuid references company (id);
@@ -0,0 +1,9 @@
+-- Money dan rate disimpan sebagai numeric supaya pecahan tidak dibulatkan float.
+-- Mengubah tipe ke tipe yang sama tidak mengubah data, jadi aman dijalankan ulang.
+
+alter table promo alter column interest_rate type numeric using interest_rate::numeric;
+alter table promo_tenor alter column min_transaction type numeric using min_transaction::numeric;
+alter table promo_tenor alter column subsidi type numeric using subsidi::numeric;
+alter table promo_tenor alter column admin type numeric using admin::numeric;
+alter table promo_tenor alter column discount type numeric using discount::numeric;
+alter table promo_tenor alter column max_discount type numeric using max_discount::numeric;
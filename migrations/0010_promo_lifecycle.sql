@@ -0,0 +1,20 @@
+-- Lifecycle promo dan log transisinya. Idempotent.
+
+-- Baris lama dianggap sudah published, promo baru mulai dari draft
+alter table promo add column if not exists lifecycle text not null default 'published';
+alter table promo alter column lifecycle set default 'draft';
+alter table promo drop constraint if exists promo_lifecycle_check;
+alter table promo add constraint promo_lifecycle_check
+    check (lifecycle in ('draft', 'pending_review', 'approved', 'published', 'archived'));
+
+create table if not exists promo_transition (
+    id uuid primary key default gen_random_uuid(),
+    promo_id uuid not null,
+    from_state text,
+    to_state text not null,
+    actor text not null,
+    note text,
+    created_at timestamptz not null default now()
+);
+
+create index if not exists promo_transition_promo_idx on promo_transition (promo_id, created_at);
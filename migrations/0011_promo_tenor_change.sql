@@ -0,0 +1,18 @@
+-- Perubahan PromoTenor terjadwal. Idempotent.
+
+create table if not exists promo_tenor_change (
+    id uuid primary key default gen_random_uuid(),
+    promo_tenor_id uuid not null references promo_tenor (id) on delete cascade,
+    changes jsonb not null,
+    effective_at timestamptz not null,
+    status text not null default 'pending'
+        check (status in ('pending', 'applying', 'applied', 'cancelled', 'failed')),
+    created_by text,
+    created_at timestamptz not null default now(),
+    applied_at timestamptz,
+    cancelled_by text,
+    cancelled_at timestamptz,
+    error text
+);
+
+create index if not exists promo_tenor_change_status_idx on promo_tenor_change (status, effective_at);
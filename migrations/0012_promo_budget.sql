@@ -0,0 +1,34 @@
+-- Batas anggaran promo / promo-store dan ledger transaksi yang dibiayai. Idempotent.
+
+alter table promo add column if not exists budget_max_subsidy numeric check (budget_max_subsidy >= 0);
+alter table promo add column if not exists budget_max_transactions integer check (budget_max_transactions >= 0);
+alter table promo add column if not exists budget_exhausted boolean not null default false;
+
+alter table promo_store add column if not exists budget_max_subsidy numeric check (budget_max_subsidy >= 0);
+alter table promo_store add column if not exists budget_max_transactions integer check (budget_max_transactions >= 0);
+alter table promo_store add column if not exists budget_exhausted boolean not null default false;
+
+-- Sengaja tanpa foreign key ke promo/store: baris ledger harus tetap ada setelah promo
+-- atau store dihapus.
+create table if not exists financed_transaction (
+    id uuid primary key default gen_random_uuid(),
+    transaction_ref text not null,
+    promo_id uuid not null,
+    promo_tenor_id uuid not null,
+    store_id uuid not null,
+    tenor integer not null,
+    amount bigint not null,
+    discount bigint not null,
+    financed_amount bigint not null,
+    admin_fee bigint not null,
+    total_interest bigint not null,
+    subsidy bigint not null,
+    free_installment_value bigint not null,
+    interest_rate numeric not null,
+    subsidi_rate numeric not null,
+    created_by text,
+    created_at timestamptz not null default now()
+);
+
+create index if not exists financed_transaction_store_created_idx
+    on financed_transaction (store_id, created_at);
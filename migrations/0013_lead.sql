@@ -0,0 +1,27 @@
+-- Lead dari calon customer. Idempotent.
+
+-- Sengaja tanpa foreign key ke promo/store: lead harus tetap ada setelah promo atau store
+-- dihapus.
+create table if not exists lead (
+    id uuid primary key default gen_random_uuid(),
+    store_id uuid not null,
+    promo_id uuid not null,
+    promo_tenor_id uuid not null,
+    tenor integer not null,
+    amount bigint not null,
+    monthly_installment bigint not null,
+    customer_name text not null,
+    phone text not null,
+    email text,
+    message text,
+    status text not null default 'new'
+        check (status in ('new', 'contacted', 'qualified', 'converted', 'lost')),
+    assigned_to text,
+    assigned_at timestamptz,
+    status_note text,
+    updated_by text,
+    created_at timestamptz not null default now(),
+    updated_at timestamptz not null default now()
+);
+
+create index if not exists lead_store_created_idx on lead (store_id, created_at);
@@ -0,0 +1,16 @@
+-- Riwayat versi append-only Promo, PromoTenor dan PromoStore. Idempotent.
+
+create table if not exists entity_version (
+    id uuid primary key default gen_random_uuid(),
+    entity_type text not null check (entity_type in ('promo', 'promo_tenor', 'promo_store')),
+    entity_id text not null,
+    promo_id uuid,
+    store_id uuid,
+    operation text not null check (operation in ('create', 'update', 'delete')),
+    snapshot jsonb not null,
+    created_at timestamptz not null default now()
+);
+
+create index if not exists entity_version_entity_idx on entity_version (entity_type, entity_id, created_at);
+create index if not exists entity_version_promo_idx on entity_version (entity_type, promo_id);
+create index if not exists entity_version_store_idx on entity_version (entity_type, store_id);
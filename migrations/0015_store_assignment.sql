@@ -0,0 +1,13 @@
+-- Penugasan operator ke store atau seluruh store milik company (tepat satu diisi). Idempotent.
+
+create table if not exists store_assignment (
+    id uuid primary key default gen_random_uuid(),
+    user_id text not null,
+    store_id uuid references store (id) on delete cascade,
+    company_id uuid references company (id) on delete cascade,
+    created_by text,
+    created_at timestamptz not null default now(),
+    check ((store_id is null) <> (company_id is null))
+);
+
+create index if not exists store_assignment_user_idx on store_assignment (user_id);
@@ -0,0 +1,18 @@
+-- API key service; hanya hash SHA-256 yang disimpan. Idempotent.
+
+create table if not exists api_key (
+    id uuid primary key default gen_random_uuid(),
+    name text not null,
+    key_prefix text not null,
+    key_hash text not null,
+    roles text[] not null default '{}',
+    routes text[] not null default '{}',
+    rate_limit_requests integer check (rate_limit_requests > 0),
+    expires_at text,
+    revoked_at timestamptz,
+    revoked_by text,
+    created_by text,
+    created_at timestamptz not null default now()
+);
+
+create unique index if not exists api_key_key_hash_key on api_key (key_hash);
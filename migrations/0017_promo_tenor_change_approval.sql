@@ -0,0 +1,8 @@
+-- Approval admin untuk perubahan tenor terjadwal pada promo yang bukan draft. Idempotent.
+
+alter table promo_tenor_change add column if not exists approved_by text;
+alter table promo_tenor_change add column if not exists approved_at timestamptz;
+
+alter table promo_tenor_change drop constraint if exists promo_tenor_change_status_check;
+alter table promo_tenor_change add constraint promo_tenor_change_status_check
+    check (status in ('pending_approval', 'pending', 'applying', 'applied', 'cancelled', 'failed'));
@@ -0,0 +1,3 @@
+-- Waktu klaim scheduler, supaya baris `applying` yang ditinggal instance mati bisa diantrikan ulang. Idempotent.
+
+alter table promo_tenor_change add column if not exists claimed_at timestamptz;
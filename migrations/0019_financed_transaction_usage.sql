@@ -0,0 +1,27 @@
+-- Pemakaian anggaran promo (total dan per store) sebagai agregat, supaya API tidak membaca
+-- seluruh ledger yang terpotong `max-rows` PostgREST. Aman dijalankan ulang.
+
+create or replace function financed_transaction_usage(p_promo_id uuid)
+returns jsonb
+language sql
+stable
+as $$
+    select jsonb_build_object(
+        'transactions', coalesce(sum(per_store.transactions), 0),
+        'subsidy', coalesce(sum(per_store.subsidy), 0),
+        'stores', coalesce(
+            jsonb_agg(jsonb_build_object(
+                'store_id', per_store.store_id,
+                'transactions', per_store.transactions,
+                'subsidy', per_store.subsidy
+            )) filter (where per_store.store_id is not null),
+            '[]'::jsonb
+        )
+    )
+    from (
+        select store_id, count(*) as transactions, coalesce(sum(subsidy), 0) as subsidy
+        from financed_transaction
+        where promo_id = p_promo_id
+        group by store_id
+    ) per_store;
+$$;
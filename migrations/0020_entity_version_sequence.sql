@@ -0,0 +1,69 @@
+-- Nomor versi per entity disimpan saat dicatat, bukan dihitung API dari urutan baris yang
+-- dibaca (yang bergeser jika ada baris terpotong atau gagal dibaca). Aman dijalankan ulang.
+
+begin;
+
+alter table entity_version add column if not exists version integer;
+
+-- Advisory lock per entity supaya dua perubahan bersamaan tidak mendapat nomor yang sama
+create or replace function record_entity_version()
+returns trigger
+language plpgsql
+security definer
+set search_path = public
+as $$
+declare
+    v_row jsonb;
+    v_entity_id text;
+    v_promo_id uuid;
+    v_store_id uuid;
+    v_version integer;
+begin
+    if tg_op = 'DELETE' then
+        v_row := to_jsonb(old);
+    else
+        v_row := to_jsonb(new);
+    end if;
+
+    if tg_table_name = 'promo' then
+        v_entity_id := v_row ->> 'id_promo';
+        v_promo_id := (v_row ->> 'id_promo')::uuid;
+    elsif tg_table_name = 'promo_tenor' then
+        v_entity_id := v_row ->> 'id';
+        v_promo_id := (v_row ->> 'promo_id')::uuid;
+    else
+        -- Key `{promo_id}-{store_id}` supaya riwayat tetap satu meski relasi dibuat ulang
+        v_entity_id := (v_row ->> 'promo_id') || '-' || (v_row ->> 'store_id');
+        v_promo_id := (v_row ->> 'promo_id')::uuid;
+        v_store_id := (v_row ->> 'store_id')::uuid;
+    end if;
+
+    perform pg_advisory_xact_lock(hashtext('entity_version:' || tg_table_name || ':' || v_entity_id));
+    select coalesce(max(version), 0) + 1 into v_version
+    from entity_version
+    where entity_type = tg_table_name and entity_id = v_entity_id;
+
+    insert into entity_version (entity_type, entity_id, version, promo_id, store_id, operation, snapshot, created_at)
+    values (tg_table_name, v_entity_id, v_version, v_promo_id, v_store_id, lower(tg_op), v_row, clock_timestamp());
+
+    return null;
+end;
+$$;
+
+-- Baris lama dinomori dengan urutan yang sama seperti sebelumnya (`created_at`, lalu `id`)
+alter table entity_version disable trigger entity_version_immutable;
+update entity_version v
+set version = numbered.version
+from (
+    select id, row_number() over (partition by entity_type, entity_id order by created_at, id) as version
+    from entity_version
+) numbered
+where v.id = numbered.id and v.version is null;
+alter table entity_version enable trigger entity_version_immutable;
+
+alter table entity_version alter column version set not null;
+
+create unique index if not exists entity_version_entity_version_key
+    on entity_version (entity_type, entity_id, version);
+
+commit;
@@ -0,0 +1,115 @@
+use axum::{
+    Router,
+    extract::{MatchedPath, Request, State},
+    http::Extensions,
+    middleware::Next,
+    response::Response,
+    routing::MethodRouter,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::app_state::AppState;
+
+/// Siapa yang boleh memanggil sebuah route. Dideklarasikan per route di `main.rs`
+/// lewat `PolicyRouter`, lalu dibaca oleh `middleware::auth` dan `RateLimiter::middleware`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessPolicy {
+    /// Tanpa JWT dan tanpa rate limit global
+    Public,
+    /// JWT valid
+    Authenticated,
+    /// JWT valid dan memiliki salah satu role ini
+    Roles(&'static [&'static str]),
+}
+
+impl AccessPolicy {
+    /// Policy yang dipasang `attach`; route tanpa deklarasi diperlakukan `Authenticated`
+    pub fn of(extensions: &Extensions) -> AccessPolicy {
+        extensions
+            .get::<AccessPolicy>()
+            .copied()
+            .unwrap_or(AccessPolicy::Authenticated)
+    }
+
+    pub fn is_public(self) -> bool {
+        self == AccessPolicy::Public
+    }
+}
+
+/// Policy per template path route (mis. `/get-promo/{id_promo}`), dicocokkan lewat `MatchedPath`
+pub type RoutePolicies = Arc<HashMap<&'static str, AccessPolicy>>;
+
+/// `Router` yang mewajibkan setiap route mendeklarasikan `AccessPolicy`-nya
+pub struct PolicyRouter {
+    router: Router<Arc<AppState>>,
+    policies: HashMap<&'static str, AccessPolicy>,
+}
+
+impl PolicyRouter {
+    pub fn new() -> Self {
+        Self {
+            router: Router::new(),
+            policies: HashMap::new(),
+        }
+    }
+
+    pub fn route(
+        mut self,
+        path: &'static str,
+        policy: AccessPolicy,
+        method_router: MethodRouter<Arc<AppState>>,
+    ) -> Self {
+        if let Some(existing) = self.policies.insert(path, policy) {
+            panic!(
+                "Route '{}' declared twice with policies {:?} and {:?}",
+                path, existing, policy
+            );
+        }
+        self.router = self.router.route(path, method_router);
+        self
+    }
+
+    pub fn merge(mut self, other: PolicyRouter) -> Self {
+        for (path, policy) in other.policies {
+            if let Some(existing) = self.policies.insert(path, policy) {
+                panic!(
+                    "Route '{}' declared twice with policies {:?} and {:?}",
+                    path, existing, policy
+                );
+            }
+        }
+        self.router = self.router.merge(other.router);
+        self
+    }
+
+    /// Middleware khusus sekelompok route (mis. limiter `/submit-lead`)
+    pub fn map_router(mut self, f: impl FnOnce(Router<Arc<AppState>>) -> Router<Arc<AppState>>) -> Self {
+        self.router = f(self.router);
+        self
+    }
+
+    pub fn into_parts(self) -> (Router<Arc<AppState>>, RoutePolicies) {
+        let count = |wanted: fn(&AccessPolicy) -> bool| self.policies.values().filter(|p| wanted(p)).count();
+        info!(
+            "Route policies: {} public, {} authenticated, {} role-restricted",
+            count(|p| p.is_public()),
+            count(|p| *p == AccessPolicy::Authenticated),
+            count(|p| matches!(p, AccessPolicy::Roles(_)))
+        );
+        (self.router, Arc::new(self.policies))
+    }
+}
+
+/// Route layer terluar: pasang policy route yang cocok sebagai extension request
+pub async fn attach(State(policies): State<RoutePolicies>, mut request: Request, next: Next) -> Response {
+    let policy = request
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|path| policies.get(path.as_str()))
+        .copied()
+        .unwrap_or(AccessPolicy::Authenticated);
+    request.extensions_mut().insert(policy);
+    next.run(request).await
+}
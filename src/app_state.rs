@@ -1,15 +1,39 @@
 use std::sync::Arc;
 
+use crate::jwt_verifier::JwtVerifier;
 use crate::repositories::cache_repository::CacheRepository;
+use crate::services::api_key_service::ApiKeyService;
+use crate::services::budget_service::BudgetService;
+use crate::services::company_service::CompanyService;
+use crate::services::conflict_service::ConflictService;
+use crate::services::delete_service::DeleteService;
+use crate::services::installment_service::InstallmentService;
+use crate::services::lead_service::LeadService;
 use crate::services::promo_service::PromoService;
 use crate::services::promo_store_service::PromoStoreService;
 use crate::services::promo_tenor_service::PromoTenorService;
+use crate::services::settlement_service::SettlementService;
+use crate::services::store_assignment_service::StoreAssignmentService;
 use crate::services::store_service::StoreService;
+use crate::services::version_service::VersionService;
+use crate::services::voucher_service::VoucherService;
 
 pub struct AppState {
     pub cache_repository: Arc<CacheRepository>,
+    pub jwt_verifier: JwtVerifier,
     pub promo_service: PromoService,
     pub promo_tenor_service: PromoTenorService,
     pub store_service: StoreService,
     pub promo_store_service: PromoStoreService,
+    pub installment_service: InstallmentService,
+    pub voucher_service: VoucherService,
+    pub conflict_service: ConflictService,
+    pub company_service: CompanyService,
+    pub budget_service: BudgetService,
+    pub settlement_service: SettlementService,
+    pub lead_service: LeadService,
+    pub version_service: VersionService,
+    pub delete_service: DeleteService,
+    pub store_assignment_service: StoreAssignmentService,
+    pub api_key_service: ApiKeyService,
 }
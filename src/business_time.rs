@@ -0,0 +1,67 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+const DEFAULT_BUSINESS_TIMEZONE: &str = "Asia/Jakarta";
+
+static BUSINESS_TIMEZONE: OnceLock<Tz> = OnceLock::new();
+
+/// Timezone bisnis untuk tanggal promo/voucher, dari env `BUSINESS_TIMEZONE`
+/// (default Asia/Jakarta).
+pub fn business_timezone() -> Tz {
+    *BUSINESS_TIMEZONE.get_or_init(|| {
+        let name = std::env::var("BUSINESS_TIMEZONE")
+            .unwrap_or_else(|_| DEFAULT_BUSINESS_TIMEZONE.to_string());
+        match name.parse::<Tz>() {
+            Ok(tz) => {
+                info!("Business timezone configured: {}", tz);
+                tz
+            }
+            Err(_) => {
+                warn!(
+                    "Invalid BUSINESS_TIMEZONE '{}', falling back to {}",
+                    name, DEFAULT_BUSINESS_TIMEZONE
+                );
+                chrono_tz::Asia::Jakarta
+            }
+        }
+    })
+}
+
+/// Parse tanggal promo/voucher menjadi UTC.
+///
+/// Format yang diterima: RFC3339 (offset dihormati), `YYYY-MM-DDTHH:MM:SS` /
+/// `YYYY-MM-DD HH:MM:SS` (waktu lokal bisnis), dan `YYYY-MM-DD`.
+/// Tanggal tanpa jam dianggap berlaku sepanjang hari tersebut jika `end_of_day`.
+pub fn parse_business_datetime(raw: &str, end_of_day: bool) -> Option<DateTime<Utc>> {
+    let raw = raw.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    let naive = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+        .or_else(|| {
+            let date = NaiveDate::parse_from_str(raw, "%Y-%m-%d").ok()?;
+            if end_of_day {
+                date.and_hms_opt(23, 59, 59)
+            } else {
+                date.and_hms_opt(0, 0, 0)
+            }
+        })?;
+
+    business_timezone()
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Awal hari (00:00 waktu bisnis) dalam UTC, untuk batas periode laporan
+pub fn business_day_start(date: NaiveDate) -> Option<DateTime<Utc>> {
+    business_timezone()
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
@@ -1,10 +1,15 @@
-// Public endpoints that bypass JWT authentication
-pub const PUBLIC_ENDPOINTS: &[&str] = &[
-    "/health",
-    "/ready",
-    "/metrics",
-    "/get-store",
-    "/get-promo",
-    "/get-promo-tenor",
-    "/get-promo-tenor-by-store",
-];
+// Role pemanggil (dibaca dari claim `JWT_ROLE_CLAIM`, default `app_metadata.role` / `app_metadata.roles`)
+pub const ROLE_ADMIN: &str = "admin";
+pub const ROLE_PROMO_MANAGER: &str = "promo-manager";
+pub const ROLE_STORE_OPERATOR: &str = "store-operator";
+pub const ROLE_VIEWER: &str = "viewer";
+
+// Kelompok role untuk `AccessPolicy::Roles` per route
+pub const ANY_ROLE: &[&str] = &[ROLE_ADMIN, ROLE_PROMO_MANAGER, ROLE_STORE_OPERATOR, ROLE_VIEWER];
+pub const PROMO_EDITORS: &[&str] = &[ROLE_ADMIN, ROLE_PROMO_MANAGER];
+pub const STORE_STAFF: &[&str] = &[ROLE_ADMIN, ROLE_STORE_OPERATOR];
+pub const PROMO_STORE_EDITORS: &[&str] = &[ROLE_ADMIN, ROLE_PROMO_MANAGER, ROLE_STORE_OPERATOR];
+pub const ADMIN_ONLY: &[&str] = &[ROLE_ADMIN];
+
+// Header API key untuk klien mesin (lihat `/create-api-key`)
+pub const API_KEY_HEADER: &str = "x-api-key";
@@ -0,0 +1,203 @@
+use crate::model::disclosure_model::TenorDisclosure;
+use crate::model::installment_model::TenorInstallment;
+use crate::model::money_model::Rate;
+use crate::model::promo_model::Promo;
+use crate::model::promo_tenor_model::PromoTenor;
+use crate::services::installment_service::InstallmentService;
+use std::sync::OnceLock;
+use tracing::info;
+
+const DEFAULT_REFERENCE_AMOUNT: i64 = 10_000_000;
+
+static REFERENCE_AMOUNT: OnceLock<i64> = OnceLock::new();
+
+/// Nominal acuan disclosure di listing tenor, dari env `DISCLOSURE_REFERENCE_AMOUNT`
+/// (default Rp 10.000.000). Tenor dengan `min_transaction` lebih besar memakai nilai itu.
+pub fn reference_amount() -> i64 {
+    *REFERENCE_AMOUNT.get_or_init(|| {
+        let amount = std::env::var("DISCLOSURE_REFERENCE_AMOUNT")
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|amount| *amount > 0)
+            .unwrap_or(DEFAULT_REFERENCE_AMOUNT);
+        info!("Disclosure reference amount configured: {}", amount);
+        amount
+    })
+}
+
+/// Disclosure tenor pada nominal acuan; `None` jika term tenor membuat perhitungan overflow
+pub fn disclose_tenor(promo: &Promo, tenor: &PromoTenor) -> Option<TenorDisclosure> {
+    let amount = reference_amount().max(tenor.min_transaction.ceil_to_int());
+    InstallmentService::calculate_installment(promo, tenor, amount)
+        .ok()
+        .map(|installment| disclose(&installment, amount))
+}
+
+/// Disclosure dari hasil kalkulasi cicilan; satu sumber angka untuk semua endpoint
+pub fn disclose(installment: &TenorInstallment, amount: i64) -> TenorDisclosure {
+    let months = installment.tenor.max(1);
+    let free_installment = installment.free_installment;
+    let paid_installments = months - free_installment;
+    // Nilai cicilan yang dibebaskan = total utang customer dikurangi yang benar-benar dibayar
+    let customer_interest = installment.total_interest - installment.subsidy;
+    let free_installment_value = (installment.financed_amount + customer_interest)
+        - (installment.total_payable - installment.admin_fee);
+
+    // Admin dibayar di muka, jadi dana bersih yang diterima customer berkurang
+    let net_received = installment.financed_amount - installment.admin_fee;
+    let monthly_rate = solve_monthly_rate(
+        net_received,
+        installment.monthly_installment,
+        paid_installments,
+    );
+
+    TenorDisclosure {
+        reference_amount: amount,
+        financed_amount: installment.financed_amount,
+        monthly_installment: installment.monthly_installment,
+        paid_installments,
+        free_installment,
+        total_interest: installment.total_interest,
+        customer_interest,
+        total_fees: installment.admin_fee,
+        merchant_subsidy: installment.subsidy,
+        free_installment_value,
+        total_payable: installment.total_payable,
+        customer_cost: installment.total_payable - installment.financed_amount,
+        merchant_cost: installment.subsidy + free_installment_value,
+        annual_percentage_rate: monthly_rate
+            .and_then(|r| Rate::from_f64(r * 12.0 * 100.0))
+            .and_then(|rate| rate.round_dp(2)),
+        effective_annual_rate: monthly_rate
+            .and_then(|r| Rate::from_f64(((1.0 + r).powi(12) - 1.0) * 100.0))
+            .and_then(|rate| rate.round_dp(2)),
+    }
+}
+
+/// Cari bunga bulanan r dengan NPV = 0 (iteratif, satu-satunya bagian yang memakai `f64`):
+/// `net_received = Σ payment / (1 + r)^k` untuk k = 1..=payments (bisection).
+fn solve_monthly_rate(net_received: i64, payment: i64, payments: i32) -> Option<f64> {
+    if payments <= 0 || net_received <= 0 || payment <= 0 {
+        return None;
+    }
+
+    let npv = |r: f64| -> f64 {
+        (1..=payments)
+            .map(|k| payment as f64 / (1.0 + r).powi(k))
+            .sum::<f64>()
+            - net_received as f64
+    };
+
+    // NPV turun monoton terhadap r
+    let (mut low, mut high) = (-0.99_f64, 10.0_f64);
+    if npv(low) < 0.0 || npv(high) > 0.0 {
+        return None;
+    }
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        if npv(mid) > 0.0 {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some((low + high) / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn installment(
+        financed_amount: i64,
+        tenor: i32,
+        total_interest: i64,
+        subsidy: i64,
+        admin_fee: i64,
+        monthly_installment: i64,
+        free_installment: i32,
+    ) -> TenorInstallment {
+        let paid = (tenor - free_installment) as i64;
+        let total_payable = InstallmentService::split_installments(
+            financed_amount + total_interest - subsidy,
+            monthly_installment,
+            tenor,
+        )
+        .into_iter()
+        .take(paid as usize)
+        .sum::<i64>()
+            + admin_fee;
+        TenorInstallment {
+            promo_id: Uuid::nil(),
+            title_promo: "Test".to_string(),
+            promo_tenor_id: Uuid::nil(),
+            tenor,
+            voucher_code: None,
+            interest_rate: Rate::ZERO,
+            discount: 0,
+            financed_amount,
+            admin_fee,
+            total_interest,
+            subsidy,
+            monthly_installment,
+            free_installment,
+            total_payable,
+            term_sources: None,
+            disclosure: None,
+        }
+    }
+
+    fn rate(raw: &str) -> Option<Rate> {
+        Some(raw.parse().unwrap())
+    }
+
+    #[test]
+    fn zero_interest_without_fees_has_zero_apr() {
+        let disclosure = disclose(&installment(12_000_000, 12, 0, 0, 0, 1_000_000, 0), 12_000_000);
+        assert_eq!(disclosure.annual_percentage_rate, rate("0"));
+        assert_eq!(disclosure.effective_annual_rate, rate("0"));
+        assert_eq!(disclosure.customer_cost, 0);
+        assert_eq!(disclosure.merchant_cost, 0);
+    }
+
+    #[test]
+    fn admin_fee_raises_apr_on_zero_interest() {
+        let disclosure = disclose(&installment(12_000_000, 12, 0, 0, 120_000, 1_000_000, 0), 12_000_000);
+        assert_eq!(disclosure.annual_percentage_rate, rate("1.86"));
+        assert_eq!(disclosure.total_fees, 120_000);
+        assert_eq!(disclosure.customer_cost, 120_000);
+    }
+
+    #[test]
+    fn flat_rate_apr_and_effective_rate() {
+        let disclosure = disclose(&installment(10_000_000, 12, 1_800_000, 0, 0, 983_334, 0), 10_000_000);
+        assert_eq!(disclosure.annual_percentage_rate, rate("31.72"));
+        assert_eq!(disclosure.effective_annual_rate, rate("36.76"));
+        assert_eq!(disclosure.total_payable, 11_800_000);
+        assert_eq!(disclosure.customer_cost, 1_800_000);
+    }
+
+    #[test]
+    fn total_cost_splits_customer_and_merchant_share() {
+        let disclosure = disclose(
+            &installment(10_000_000, 12, 1_800_000, 200_000, 100_000, 966_667, 2),
+            10_000_000,
+        );
+        assert_eq!(disclosure.paid_installments, 10);
+        assert_eq!(disclosure.customer_interest, 1_600_000);
+        assert_eq!(disclosure.total_payable, 9_766_670);
+        assert_eq!(disclosure.free_installment_value, 1_933_330);
+        assert_eq!(disclosure.customer_cost, -233_330);
+        assert_eq!(disclosure.merchant_cost, 2_133_330);
+    }
+
+    #[test]
+    fn all_free_installments_have_no_apr() {
+        let disclosure = disclose(&installment(6_000_000, 6, 0, 0, 50_000, 1_000_000, 6), 6_000_000);
+        assert_eq!(disclosure.paid_installments, 0);
+        assert_eq!(disclosure.total_payable, 50_000);
+        assert_eq!(disclosure.annual_percentage_rate, None);
+        assert_eq!(disclosure.effective_annual_rate, None);
+    }
+}
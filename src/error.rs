@@ -2,6 +2,8 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
+use crate::model::delete_model::DependentConflict;
+use axum::Json;
 use serde::Serialize;
 use std::fmt;
 
@@ -15,6 +17,7 @@ pub enum StoreError {
     AlreadyExists(String),
     InvalidRoute(String),
     InvalidPayload(String),
+    InUse(DependentConflict),
     DatabaseError(String),
 }
 
@@ -24,6 +27,8 @@ pub enum PromoError {
     AlreadyExists(String),
     InvalidId(String),
     InvalidPayload(String),
+    Conflict(String),
+    InUse(DependentConflict),
     DatabaseError(String),
 }
 
@@ -36,6 +41,8 @@ pub enum PromoTenorError {
     InvalidInterestRate(String),
     InvalidVoucher(String),
     InvalidPayload(String),
+    Conflict(String),
+    InUse(DependentConflict),
     DatabaseError(String),
 }
 
@@ -45,6 +52,68 @@ pub enum PromoStoreError {
     AlreadyExists(String),
     InvalidKey(String),
     InvalidPayload(String),
+    Conflict(String),
+    DatabaseError(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum VoucherError {
+    NotFound(String),
+    NotApplicable(String),
+    Expired(String),
+    UsageLimitReached(String),
+    InvalidPayload(String),
+    Conflict(String),
+    DatabaseError(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum CompanyError {
+    NotFound(String),
+    AlreadyExists(String),
+    InvalidPayload(String),
+    InUse(String),
+    DatabaseError(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum TransactionError {
+    NotFound(String),
+    InvalidPayload(String),
+    NotApplicable(String),
+    BudgetExceeded(String),
+    DatabaseError(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum HistoryError {
+    NotFound(String),
+    InvalidQuery(String),
+    DatabaseError(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum AssignmentError {
+    NotFound(String),
+    AlreadyExists(String),
+    InvalidPayload(String),
+    DatabaseError(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum ApiKeyError {
+    NotFound(String),
+    InvalidPayload(String),
+    Conflict(String),
+    DatabaseError(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum LeadError {
+    NotFound(String),
+    InvalidPayload(String),
+    NotApplicable(String),
+    Conflict(String),
     DatabaseError(String),
 }
 
@@ -59,10 +128,19 @@ pub enum AppError {
     Promo(PromoError),
     PromoTenor(PromoTenorError),
     PromoStore(PromoStoreError),
+    Voucher(VoucherError),
+    Company(CompanyError),
+    Transaction(TransactionError),
+    Lead(LeadError),
+    History(HistoryError),
+    Assignment(AssignmentError),
+    ApiKey(ApiKeyError),
     
     // Auth errors
     Unauthorized,
     InvalidToken(String),
+    Forbidden(String),
+    TooManyRequests(String),
     
     // Generic errors
     BadRequest(String),
@@ -97,6 +175,48 @@ impl From<PromoStoreError> for AppError {
     }
 }
 
+impl From<VoucherError> for AppError {
+    fn from(err: VoucherError) -> Self {
+        AppError::Voucher(err)
+    }
+}
+
+impl From<CompanyError> for AppError {
+    fn from(err: CompanyError) -> Self {
+        AppError::Company(err)
+    }
+}
+
+impl From<TransactionError> for AppError {
+    fn from(err: TransactionError) -> Self {
+        AppError::Transaction(err)
+    }
+}
+
+impl From<LeadError> for AppError {
+    fn from(err: LeadError) -> Self {
+        AppError::Lead(err)
+    }
+}
+
+impl From<HistoryError> for AppError {
+    fn from(err: HistoryError) -> Self {
+        AppError::History(err)
+    }
+}
+
+impl From<AssignmentError> for AppError {
+    fn from(err: AssignmentError) -> Self {
+        AppError::Assignment(err)
+    }
+}
+
+impl From<ApiKeyError> for AppError {
+    fn from(err: ApiKeyError) -> Self {
+        AppError::ApiKey(err)
+    }
+}
+
 // ============================================================================
 // Display Implementations
 // ============================================================================
@@ -108,6 +228,7 @@ impl fmt::Display for StoreError {
             StoreError::AlreadyExists(msg) => write!(f, "Store already exists: {}", msg),
             StoreError::InvalidRoute(msg) => write!(f, "Invalid store route: {}", msg),
             StoreError::InvalidPayload(msg) => write!(f, "Invalid store payload: {}", msg),
+            StoreError::InUse(conflict) => write!(f, "Store in use: {}", conflict.error),
             StoreError::DatabaseError(msg) => write!(f, "Store database error: {}", msg),
         }
     }
@@ -120,6 +241,8 @@ impl fmt::Display for PromoError {
             PromoError::AlreadyExists(msg) => write!(f, "Promo already exists: {}", msg),
             PromoError::InvalidId(msg) => write!(f, "Invalid promo ID: {}", msg),
             PromoError::InvalidPayload(msg) => write!(f, "Invalid promo payload: {}", msg),
+            PromoError::Conflict(msg) => write!(f, "Promo conflict: {}", msg),
+            PromoError::InUse(conflict) => write!(f, "Promo in use: {}", conflict.error),
             PromoError::DatabaseError(msg) => write!(f, "Promo database error: {}", msg),
         }
     }
@@ -135,6 +258,8 @@ impl fmt::Display for PromoTenorError {
             PromoTenorError::InvalidInterestRate(msg) => write!(f, "Invalid interest rate: {}", msg),
             PromoTenorError::InvalidVoucher(msg) => write!(f, "Invalid voucher code: {}", msg),
             PromoTenorError::InvalidPayload(msg) => write!(f, "Invalid promo tenor payload: {}", msg),
+            PromoTenorError::Conflict(msg) => write!(f, "Promo tenor conflict: {}", msg),
+            PromoTenorError::InUse(conflict) => write!(f, "Promo tenor in use: {}", conflict.error),
             PromoTenorError::DatabaseError(msg) => write!(f, "Promo tenor database error: {}", msg),
         }
     }
@@ -147,11 +272,94 @@ impl fmt::Display for PromoStoreError {
             PromoStoreError::AlreadyExists(msg) => write!(f, "Promo store relation already exists: {}", msg),
             PromoStoreError::InvalidKey(msg) => write!(f, "Invalid promo store key: {}", msg),
             PromoStoreError::InvalidPayload(msg) => write!(f, "Invalid promo store payload: {}", msg),
+            PromoStoreError::Conflict(msg) => write!(f, "Promo store conflict: {}", msg),
             PromoStoreError::DatabaseError(msg) => write!(f, "Promo store database error: {}", msg),
         }
     }
 }
 
+impl fmt::Display for VoucherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VoucherError::NotFound(msg) => write!(f, "Voucher not found: {}", msg),
+            VoucherError::NotApplicable(msg) => write!(f, "Voucher not applicable: {}", msg),
+            VoucherError::Expired(msg) => write!(f, "Voucher expired: {}", msg),
+            VoucherError::UsageLimitReached(msg) => write!(f, "Voucher usage limit reached: {}", msg),
+            VoucherError::InvalidPayload(msg) => write!(f, "Invalid voucher payload: {}", msg),
+            VoucherError::Conflict(msg) => write!(f, "Voucher redemption conflict: {}", msg),
+            VoucherError::DatabaseError(msg) => write!(f, "Voucher database error: {}", msg),
+        }
+    }
+}
+
+impl fmt::Display for CompanyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompanyError::NotFound(msg) => write!(f, "Company not found: {}", msg),
+            CompanyError::AlreadyExists(msg) => write!(f, "Company already exists: {}", msg),
+            CompanyError::InvalidPayload(msg) => write!(f, "Invalid company payload: {}", msg),
+            CompanyError::InUse(msg) => write!(f, "Company in use: {}", msg),
+            CompanyError::DatabaseError(msg) => write!(f, "Company database error: {}", msg),
+        }
+    }
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionError::NotFound(msg) => write!(f, "Transaction not found: {}", msg),
+            TransactionError::InvalidPayload(msg) => write!(f, "Invalid transaction payload: {}", msg),
+            TransactionError::NotApplicable(msg) => write!(f, "Promo not applicable: {}", msg),
+            TransactionError::BudgetExceeded(msg) => write!(f, "Promo budget exceeded: {}", msg),
+            TransactionError::DatabaseError(msg) => write!(f, "Transaction database error: {}", msg),
+        }
+    }
+}
+
+impl fmt::Display for LeadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LeadError::NotFound(msg) => write!(f, "Lead not found: {}", msg),
+            LeadError::InvalidPayload(msg) => write!(f, "Invalid lead payload: {}", msg),
+            LeadError::NotApplicable(msg) => write!(f, "Promo not applicable: {}", msg),
+            LeadError::Conflict(msg) => write!(f, "Lead conflict: {}", msg),
+            LeadError::DatabaseError(msg) => write!(f, "Lead database error: {}", msg),
+        }
+    }
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::NotFound(msg) => write!(f, "Version not found: {}", msg),
+            HistoryError::InvalidQuery(msg) => write!(f, "Invalid version query: {}", msg),
+            HistoryError::DatabaseError(msg) => write!(f, "Version history database error: {}", msg),
+        }
+    }
+}
+
+impl fmt::Display for AssignmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssignmentError::NotFound(msg) => write!(f, "Store assignment not found: {}", msg),
+            AssignmentError::AlreadyExists(msg) => write!(f, "Store assignment already exists: {}", msg),
+            AssignmentError::InvalidPayload(msg) => write!(f, "Invalid store assignment payload: {}", msg),
+            AssignmentError::DatabaseError(msg) => write!(f, "Store assignment database error: {}", msg),
+        }
+    }
+}
+
+impl fmt::Display for ApiKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiKeyError::NotFound(msg) => write!(f, "API key not found: {}", msg),
+            ApiKeyError::InvalidPayload(msg) => write!(f, "Invalid API key payload: {}", msg),
+            ApiKeyError::Conflict(msg) => write!(f, "API key conflict: {}", msg),
+            ApiKeyError::DatabaseError(msg) => write!(f, "API key database error: {}", msg),
+        }
+    }
+}
+
 impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -159,8 +367,17 @@ impl fmt::Display for AppError {
             AppError::Promo(err) => write!(f, "{}", err),
             AppError::PromoTenor(err) => write!(f, "{}", err),
             AppError::PromoStore(err) => write!(f, "{}", err),
+            AppError::Voucher(err) => write!(f, "{}", err),
+            AppError::Company(err) => write!(f, "{}", err),
+            AppError::Transaction(err) => write!(f, "{}", err),
+            AppError::Lead(err) => write!(f, "{}", err),
+            AppError::History(err) => write!(f, "{}", err),
+            AppError::Assignment(err) => write!(f, "{}", err),
+            AppError::ApiKey(err) => write!(f, "{}", err),
             AppError::Unauthorized => write!(f, "Unauthorized"),
             AppError::InvalidToken(msg) => write!(f, "Invalid token: {}", msg),
+            AppError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
+            AppError::TooManyRequests(msg) => write!(f, "Too many requests: {}", msg),
             AppError::BadRequest(msg) => write!(f, "Bad request: {}", msg),
             AppError::Internal(msg) => write!(f, "Internal error: {}", msg),
         }
@@ -174,6 +391,13 @@ impl fmt::Display for AppError {
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, body) = match self {
+            // Restrict delete: 409 dengan daftar dependent terstruktur (JSON)
+            AppError::Store(StoreError::InUse(conflict))
+            | AppError::Promo(PromoError::InUse(conflict))
+            | AppError::PromoTenor(PromoTenorError::InUse(conflict)) => {
+                return (StatusCode::CONFLICT, Json(conflict)).into_response();
+            }
+
             // Store errors
             AppError::Store(StoreError::NotFound(msg)) => (StatusCode::NOT_FOUND, msg),
             AppError::Store(StoreError::AlreadyExists(msg)) => (StatusCode::CONFLICT, msg),
@@ -186,6 +410,7 @@ impl IntoResponse for AppError {
             AppError::Promo(PromoError::AlreadyExists(msg)) => (StatusCode::CONFLICT, msg),
             AppError::Promo(PromoError::InvalidId(msg)) => (StatusCode::BAD_REQUEST, msg),
             AppError::Promo(PromoError::InvalidPayload(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::Promo(PromoError::Conflict(msg)) => (StatusCode::CONFLICT, msg),
             AppError::Promo(PromoError::DatabaseError(msg)) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             
             // PromoTenor errors
@@ -196,6 +421,7 @@ impl IntoResponse for AppError {
             AppError::PromoTenor(PromoTenorError::InvalidInterestRate(msg)) => (StatusCode::BAD_REQUEST, msg),
             AppError::PromoTenor(PromoTenorError::InvalidVoucher(msg)) => (StatusCode::BAD_REQUEST, msg),
             AppError::PromoTenor(PromoTenorError::InvalidPayload(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::PromoTenor(PromoTenorError::Conflict(msg)) => (StatusCode::CONFLICT, msg),
             AppError::PromoTenor(PromoTenorError::DatabaseError(msg)) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             
             // PromoStore errors
@@ -203,11 +429,61 @@ impl IntoResponse for AppError {
             AppError::PromoStore(PromoStoreError::AlreadyExists(msg)) => (StatusCode::CONFLICT, msg),
             AppError::PromoStore(PromoStoreError::InvalidKey(msg)) => (StatusCode::BAD_REQUEST, msg),
             AppError::PromoStore(PromoStoreError::InvalidPayload(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::PromoStore(PromoStoreError::Conflict(msg)) => (StatusCode::CONFLICT, msg),
             AppError::PromoStore(PromoStoreError::DatabaseError(msg)) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             
+            // Voucher errors
+            AppError::Voucher(VoucherError::NotFound(msg)) => (StatusCode::NOT_FOUND, msg),
+            AppError::Voucher(VoucherError::NotApplicable(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::Voucher(VoucherError::Expired(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::Voucher(VoucherError::UsageLimitReached(msg)) => (StatusCode::CONFLICT, msg),
+            AppError::Voucher(VoucherError::InvalidPayload(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::Voucher(VoucherError::Conflict(msg)) => (StatusCode::CONFLICT, msg),
+            AppError::Voucher(VoucherError::DatabaseError(msg)) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            
+            // Company errors
+            AppError::Company(CompanyError::NotFound(msg)) => (StatusCode::NOT_FOUND, msg),
+            AppError::Company(CompanyError::AlreadyExists(msg)) => (StatusCode::CONFLICT, msg),
+            AppError::Company(CompanyError::InvalidPayload(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::Company(CompanyError::InUse(msg)) => (StatusCode::CONFLICT, msg),
+            AppError::Company(CompanyError::DatabaseError(msg)) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+
+            // Transaction errors
+            AppError::Transaction(TransactionError::NotFound(msg)) => (StatusCode::NOT_FOUND, msg),
+            AppError::Transaction(TransactionError::InvalidPayload(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::Transaction(TransactionError::NotApplicable(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::Transaction(TransactionError::BudgetExceeded(msg)) => (StatusCode::CONFLICT, msg),
+            AppError::Transaction(TransactionError::DatabaseError(msg)) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+
+            // Lead errors
+            AppError::Lead(LeadError::NotFound(msg)) => (StatusCode::NOT_FOUND, msg),
+            AppError::Lead(LeadError::InvalidPayload(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::Lead(LeadError::NotApplicable(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::Lead(LeadError::Conflict(msg)) => (StatusCode::CONFLICT, msg),
+            AppError::Lead(LeadError::DatabaseError(msg)) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+
+            // Version history errors
+            AppError::History(HistoryError::NotFound(msg)) => (StatusCode::NOT_FOUND, msg),
+            AppError::History(HistoryError::InvalidQuery(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::History(HistoryError::DatabaseError(msg)) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+
+            // Store assignment errors
+            AppError::Assignment(AssignmentError::NotFound(msg)) => (StatusCode::NOT_FOUND, msg),
+            AppError::Assignment(AssignmentError::AlreadyExists(msg)) => (StatusCode::CONFLICT, msg),
+            AppError::Assignment(AssignmentError::InvalidPayload(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::Assignment(AssignmentError::DatabaseError(msg)) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+
+            // API key errors
+            AppError::ApiKey(ApiKeyError::NotFound(msg)) => (StatusCode::NOT_FOUND, msg),
+            AppError::ApiKey(ApiKeyError::InvalidPayload(msg)) => (StatusCode::BAD_REQUEST, msg),
+            AppError::ApiKey(ApiKeyError::Conflict(msg)) => (StatusCode::CONFLICT, msg),
+            AppError::ApiKey(ApiKeyError::DatabaseError(msg)) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            
             // Auth errors
             AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized".to_string()),
             AppError::InvalidToken(msg) => (StatusCode::UNAUTHORIZED, msg),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            AppError::TooManyRequests(msg) => (StatusCode::TOO_MANY_REQUESTS, msg),
             
             // Generic errors
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
@@ -0,0 +1,40 @@
+use crate::app_state::AppState;
+use crate::error::AppError;
+use crate::middleware::Actor;
+use crate::model::api_key_model::*;
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub async fn han_get_api_keys(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<ApiKey>>, AppError> {
+    let keys = state.api_key_service.ser_get_api_keys().await?;
+    Ok(Json(keys))
+}
+
+/// Pemakaian per key sejak proses start (request, ditolak, kena rate limit)
+pub async fn han_get_api_key_usage(State(state): State<Arc<AppState>>) -> Json<Vec<ApiKeyUsage>> {
+    Json(state.api_key_service.ser_usage())
+}
+
+pub async fn han_create_api_key(
+    State(state): State<Arc<AppState>>,
+    actor: Actor,
+    Json(payload): Json<CreateApiKeyPayload>,
+) -> Result<Json<IssuedApiKey>, AppError> {
+    let issued = state.api_key_service.ser_issue_api_key(payload, &actor).await?;
+    Ok(Json(issued))
+}
+
+pub async fn han_revoke_api_key(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Result<Json<ApiKey>, AppError> {
+    let revoked = state.api_key_service.ser_revoke_api_key(id, &actor).await?;
+    Ok(Json(revoked))
+}
@@ -0,0 +1,50 @@
+use crate::app_state::AppState;
+use crate::error::AppError;
+use crate::middleware::Actor;
+use crate::model::budget_model::*;
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct FinancedTransactionQuery {
+    pub promo_id: Option<Uuid>,
+    pub store_id: Option<Uuid>,
+}
+
+pub async fn han_record_transaction(
+    State(state): State<Arc<AppState>>,
+    actor: Actor,
+    Json(payload): Json<RecordTransactionPayload>,
+) -> Result<Json<RecordTransactionResult>, AppError> {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    let result = state.budget_service.ser_record_transaction(payload, &actor, &scope).await?;
+    Ok(Json(result))
+}
+
+pub async fn han_get_financed_transactions(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<FinancedTransactionQuery>,
+    actor: Actor,
+) -> Result<Json<Vec<FinancedTransaction>>, AppError> {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    let transactions = state
+        .budget_service
+        .ser_get_transactions(query.promo_id, query.store_id, &scope)
+        .await?;
+    Ok(Json(transactions))
+}
+
+pub async fn han_get_promo_budget(
+    State(state): State<Arc<AppState>>,
+    Path(id_promo): Path<Uuid>,
+    actor: Actor,
+) -> Result<Json<PromoBudgetStatus>, AppError> {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    let status = state.budget_service.ser_get_promo_budget(id_promo, &scope).await?;
+    Ok(Json(status))
+}
@@ -0,0 +1,123 @@
+use crate::app_state::AppState;
+use crate::error::{AppError, StoreError};
+use crate::middleware::Actor;
+use crate::model::company_model::*;
+use crate::model::store_assignment_model::StoreScope;
+use crate::model::store_model::Store;
+use crate::services::store_assignment_service::StoreAssignmentService;
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Non-admin hanya melihat company yang di-assign atau pemilik store yang di-assign
+pub async fn han_get_companies(
+    State(state): State<Arc<AppState>>,
+    actor: Actor,
+) -> Result<Json<Vec<Company>>, AppError> {
+    let mut companies = state.company_service.ser_get_all_companies().await?;
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    if !scope.is_all() {
+        let stores = match state.store_service.ser_get_all_stores().await {
+            Ok(stores) => stores,
+            Err(AppError::Store(StoreError::NotFound(_))) => vec![],
+            Err(e) => return Err(e),
+        };
+        companies.retain(|c| {
+            scope.allows_company(c.id)
+                || stores.iter().any(|s| scope.allows_store(s.id) && c.owns_store(s))
+        });
+    }
+    Ok(Json(companies))
+}
+
+pub async fn han_get_company_by_id(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Result<Json<Company>, AppError> {
+    let company = state.company_service.ser_get_company_by_id(id).await?;
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    ensure_company_visible(&state, &scope, &company).await?;
+    Ok(Json(company))
+}
+
+pub async fn han_get_company_stores(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Result<Json<Vec<Store>>, AppError> {
+    let company = state.company_service.ser_get_company_by_id(id).await?;
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    ensure_company_visible(&state, &scope, &company).await?;
+    let mut stores = state.company_service.ser_get_company_stores(id).await?;
+    stores.retain(|s| scope.allows_store(s.id));
+    Ok(Json(stores))
+}
+
+pub async fn han_get_company_promos(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Result<Json<Vec<CompanyPromo>>, AppError> {
+    let company = state.company_service.ser_get_company_by_id(id).await?;
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    ensure_company_visible(&state, &scope, &company).await?;
+    let mut promos = state.company_service.ser_get_company_promos(id).await?;
+    for promo in &mut promos {
+        promo.store_ids.retain(|store_id| scope.allows_store(*store_id));
+    }
+    promos.retain(|p| !p.store_ids.is_empty());
+    Ok(Json(promos))
+}
+
+/// Company terlihat jika di-assign langsung atau memiliki store dalam cakupan (sama dengan listing)
+async fn ensure_company_visible(state: &AppState, scope: &StoreScope, company: &Company) -> Result<(), AppError> {
+    if scope.allows_company(company.id) {
+        return Ok(());
+    }
+    let stores = match state.store_service.ser_get_all_stores().await {
+        Ok(stores) => stores,
+        Err(AppError::Store(StoreError::NotFound(_))) => vec![],
+        Err(e) => return Err(e),
+    };
+    if stores.iter().any(|s| scope.allows_store(s.id) && company.owns_store(s)) {
+        return Ok(());
+    }
+    StoreAssignmentService::ensure_company(scope, company.id)
+}
+
+pub async fn han_create_company(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CreateCompanyPayload>,
+) -> Result<Json<Company>, AppError> {
+    let company = state.company_service.ser_create_company(payload).await?;
+    Ok(Json(company))
+}
+
+pub async fn han_update_company(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateCompanyPayload>,
+) -> Result<Json<Company>, AppError> {
+    let company = state.company_service.ser_update_company(id, payload).await?;
+    Ok(Json(company))
+}
+
+pub async fn han_delete_company(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<()>, AppError> {
+    state.company_service.ser_delete_company(id).await?;
+    Ok(Json(()))
+}
+
+pub async fn han_migrate_store_companies(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<MigrateStoreCompanyPayload>,
+) -> Result<Json<StoreCompanyMigrationResult>, AppError> {
+    let result = state.company_service.ser_migrate_store_companies(payload).await?;
+    Ok(Json(result))
+}
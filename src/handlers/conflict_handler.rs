@@ -0,0 +1,34 @@
+use crate::app_state::AppState;
+use crate::error::AppError;
+use crate::middleware::Actor;
+use crate::model::conflict_model::*;
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, HeaderValue},
+};
+use std::sync::Arc;
+
+pub const CONFLICT_WARNING_HEADER: &str = "x-promo-conflict-warning";
+
+pub async fn han_get_promo_conflicts(
+    State(state): State<Arc<AppState>>,
+    actor: Actor,
+) -> Result<Json<Vec<StoreConflictReport>>, AppError> {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    let mut report = state.conflict_service.ser_get_conflict_report().await?;
+    report.retain(|r| scope.allows_store(r.store_id));
+    Ok(Json(report))
+}
+
+/// Response mode warn: header berisi jumlah konflik, body berisi detail konfliknya
+pub fn with_conflict_warnings<T>(
+    data: T,
+    conflicts: Vec<PromoConflict>,
+) -> (HeaderMap, Json<WithConflictWarnings<T>>) {
+    let mut headers = HeaderMap::new();
+    if !conflicts.is_empty() {
+        headers.insert(CONFLICT_WARNING_HEADER, HeaderValue::from(conflicts.len()));
+    }
+    (headers, Json(WithConflictWarnings { data, conflicts }))
+}
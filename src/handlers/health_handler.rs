@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Instant;
 use crate::app_state::AppState;
+use crate::model::api_key_model::ApiKeyMetrics;
 use axum::extract::State;
 use chrono::Utc;
 
@@ -36,6 +37,8 @@ pub struct MetricsResponse {
     pub cache_store_count: usize,
     pub cache_promo_store_count: usize,
     pub cache_promo_tenor_count: usize,
+    /// Total counter API key sejak proses start; rincian per key di `/api-key-usage` (admin)
+    pub api_key_usage: ApiKeyMetrics,
 }
 
 pub async fn health_check(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
@@ -82,5 +85,6 @@ pub async fn metrics(State(state): State<Arc<AppState>>) -> Json<MetricsResponse
         cache_store_count: store_count,
         cache_promo_store_count: promo_store_count,
         cache_promo_tenor_count: promo_tenor_count,
+        api_key_usage: state.api_key_service.ser_usage_metrics(),
     })
 }
@@ -0,0 +1,99 @@
+use crate::app_state::AppState;
+use crate::error::AppError;
+use crate::model::installment_model::*;
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, header},
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub async fn han_simulate_installment(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SimulateInstallmentPayload>,
+) -> Result<Json<InstallmentSimulation>, AppError> {
+    let simulation = state
+        .installment_service
+        .ser_simulate_installment(payload)
+        .await?;
+    Ok(Json(simulation))
+}
+
+pub async fn han_recommend_promo(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<RecommendPromoPayload>,
+) -> Result<Json<PromoRecommendationResult>, AppError> {
+    let result = state.installment_service.ser_recommend_promo(payload).await?;
+    Ok(Json(result))
+}
+
+/// Negosiasi header `Accept`: CSV hanya jika `text/csv` punya q lebih tinggi dari JSON.
+/// Tiap media type memakai q dari media range paling spesifik yang cocok (`text/csv` >
+/// `text/*` > `*/*`); seri dimenangkan JSON.
+pub fn prefers_csv(headers: &HeaderMap) -> bool {
+    let Some(accept) = headers.get(header::ACCEPT).and_then(|h| h.to_str().ok()) else {
+        return false;
+    };
+
+    let quality = |media_type: &str, subtype: &str| -> f32 {
+        accept
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';').map(str::trim);
+                let (range_type, range_subtype) = parts.next()?.split_once('/')?;
+                let specificity = if range_type.eq_ignore_ascii_case(media_type)
+                    && range_subtype.eq_ignore_ascii_case(subtype)
+                {
+                    2
+                } else if range_type.eq_ignore_ascii_case(media_type) && range_subtype == "*" {
+                    1
+                } else if range_type == "*" && range_subtype == "*" {
+                    0
+                } else {
+                    return None;
+                };
+                let q = match parts.find_map(|p| p.strip_prefix("q=").or_else(|| p.strip_prefix("Q="))) {
+                    Some(q) => q.trim().parse::<f32>().ok()?,
+                    None => 1.0,
+                };
+                Some((specificity, q))
+            })
+            .max_by_key(|(specificity, _)| *specificity)
+            .map_or(0.0, |(_, q)| q)
+    };
+
+    let csv = quality("text", "csv");
+    csv > 0.0 && csv > quality("application", "json")
+}
+
+/// JSON secara default, `text/csv` jika diminta lewat header `Accept`
+pub async fn han_get_installment_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(promo_tenor_id): Path<Uuid>,
+    Query(query): Query<InstallmentScheduleQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let schedule = state
+        .installment_service
+        .ser_get_installment_schedule(promo_tenor_id, query)
+        .await?;
+
+    if prefers_csv(&headers) {
+        let filename = format!(
+            "attachment; filename=\"schedule-{}-{}.csv\"",
+            promo_tenor_id, schedule.amount
+        );
+        return Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, filename),
+            ],
+            schedule.to_csv(),
+        )
+            .into_response());
+    }
+
+    Ok(Json(schedule).into_response())
+}
@@ -0,0 +1,71 @@
+use crate::app_state::AppState;
+use crate::error::AppError;
+use crate::middleware::Actor;
+use crate::model::lead_model::*;
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct LeadQuery {
+    pub store_id: Option<Uuid>,
+    pub status: Option<LeadStatus>,
+    pub assigned_to: Option<String>,
+}
+
+pub async fn han_submit_lead(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SubmitLeadPayload>,
+) -> Result<Json<LeadReceipt>, AppError> {
+    let receipt = state.lead_service.ser_submit_lead(payload).await?;
+    Ok(Json(receipt))
+}
+
+pub async fn han_get_leads(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<LeadQuery>,
+    actor: Actor,
+) -> Result<Json<Vec<Lead>>, AppError> {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    let leads = state
+        .lead_service
+        .ser_get_leads(query.store_id, query.status, query.assigned_to.as_deref(), &scope)
+        .await?;
+    Ok(Json(leads))
+}
+
+pub async fn han_get_lead_by_id(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Result<Json<Lead>, AppError> {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    let lead = state.lead_service.ser_get_lead_by_id(id, &scope).await?;
+    Ok(Json(lead))
+}
+
+pub async fn han_assign_lead(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    Json(payload): Json<AssignLeadPayload>,
+) -> Result<Json<Lead>, AppError> {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    let lead = state.lead_service.ser_assign_lead(id, payload, &actor, &scope).await?;
+    Ok(Json(lead))
+}
+
+pub async fn han_update_lead_status(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+    Json(payload): Json<UpdateLeadStatusPayload>,
+) -> Result<Json<Lead>, AppError> {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    let lead = state.lead_service.ser_update_lead_status(id, payload, &actor, &scope).await?;
+    Ok(Json(lead))
+}
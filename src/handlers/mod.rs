@@ -1,5 +1,15 @@
+pub mod api_key_handler;
+pub mod budget_handler;
+pub mod company_handler;
+pub mod conflict_handler;
 pub mod health_handler;
+pub mod installment_handler;
+pub mod lead_handler;
 pub mod promo_handler;
 pub mod promo_store_handler;
 pub mod promo_tenor_handler;
+pub mod settlement_handler;
+pub mod store_assignment_handler;
 pub mod store_handler;
+pub mod version_handler;
+pub mod voucher_handler;
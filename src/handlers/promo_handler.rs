@@ -1,9 +1,15 @@
 use crate::app_state::AppState;
 use crate::error::{AppError, PromoError};
+use crate::handlers::conflict_handler::with_conflict_warnings;
+use crate::middleware::Actor;
+use crate::services::conflict_service::log_conflicts;
+use crate::model::conflict_model::WithConflictWarnings;
+use crate::model::delete_model::{DeleteQuery, DeleteReport};
 use crate::model::promo_model::*;
 use axum::{
     Json,
     extract::{Path, Query, State},
+    http::HeaderMap,
 };
 use serde::Deserialize;
 use std::sync::Arc;
@@ -13,6 +19,7 @@ use uuid::Uuid;
 #[derive(Deserialize)]
 pub struct PromoQuery {
     pub store_id: Option<Uuid>,
+    pub status: Option<PromoStatus>,
 }
 
 pub async fn han_get_all_promos(
@@ -24,7 +31,12 @@ pub async fn han_get_all_promos(
         return Ok(Json(promos));
     }
 
-    let promos = state.promo_service.ser_get_all_promos().await?;
+    // Endpoint publik: hanya promo published; tanpa `?status=` hanya promo yang sedang live
+    let mut promos = state.promo_service.ser_get_all_promos().await?;
+    match query.status {
+        Some(status) => promos.retain(|p| p.is_published() && p.status == status),
+        None => promos.retain(|p| p.is_live()),
+    }
     if promos.is_empty() {
         return Err(PromoError::NotFound("No promos found".to_string()).into());
     }
@@ -40,11 +52,34 @@ pub async fn han_get_promo_by_id(
     Ok(Json(promo))
 }
 
+#[derive(Deserialize)]
+pub struct PromoLifecycleQuery {
+    pub state: Option<PromoLifecycle>,
+}
+
+pub async fn han_get_promos_by_lifecycle(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PromoLifecycleQuery>,
+) -> Result<Json<Vec<PromoResponse>>, AppError> {
+    let promos = state.promo_service.ser_get_promos_by_lifecycle(query.state).await?;
+    Ok(Json(promos))
+}
+
 pub async fn han_create_promo(
     State(state): State<Arc<AppState>>,
+    actor: Actor,
     Json(payload): Json<CreatePromoPayload>,
 ) -> Result<Json<PromoResponse>, AppError> {
-    let created = state.promo_service.ser_create_promo(payload).await?;
+    let touches_budget = payload.budget_max_subsidy.is_some() || payload.budget_max_transactions.is_some();
+    let mut created = state.promo_service.ser_create_promo(payload, &actor).await?;
+    if touches_budget {
+        created.budget_exhausted = state
+            .budget_service
+            .ser_refresh_budget(created.id_promo)
+            .await?
+            .budget
+            .exhausted;
+    }
     Ok(Json(created))
 }
 
@@ -52,15 +87,75 @@ pub async fn han_update_promo(
     State(state): State<Arc<AppState>>,
     Path(id_promo): Path<Uuid>,
     Json(payload): Json<UpdatePromoPayload>,
-) -> Result<Json<PromoResponse>, AppError> {
-    let updated = state.promo_service.ser_update_promo(id_promo, payload).await?;
-    Ok(Json(updated))
+) -> Result<(HeaderMap, Json<WithConflictWarnings<PromoResponse>>), AppError> {
+    let conflicts = state
+        .conflict_service
+        .ser_check_promo_update(id_promo, &payload)
+        .await?;
+    log_conflicts("update-promo", &conflicts);
+
+    let touches_budget = payload.touches_budget();
+    let mut updated = state.promo_service.ser_update_promo(id_promo, payload).await?;
+    if touches_budget {
+        // Batas anggaran berubah: sinkronkan flag budget_exhausted dengan ledger
+        updated.budget_exhausted = state
+            .budget_service
+            .ser_refresh_budget(id_promo)
+            .await?
+            .budget
+            .exhausted;
+    }
+    Ok(with_conflict_warnings(updated, conflicts))
+}
+
+pub async fn han_clone_promo(
+    State(state): State<Arc<AppState>>,
+    Path(id_promo): Path<Uuid>,
+    actor: Actor,
+    Json(payload): Json<ClonePromoPayload>,
+) -> Result<Json<PromoGraph>, AppError> {
+    let graph = state.promo_service.ser_clone_promo(id_promo, payload, &actor).await?;
+    Ok(Json(graph))
+}
+
+pub async fn han_transition_promo(
+    State(state): State<Arc<AppState>>,
+    Path(id_promo): Path<Uuid>,
+    actor: Actor,
+    Json(payload): Json<TransitionPromoPayload>,
+) -> Result<(HeaderMap, Json<WithConflictWarnings<PromoTransitionResult>>), AppError> {
+    // Review bisa terjadi sebelum promo lain dipublish; cek ulang tepat sebelum promo ini live
+    let conflicts = if payload.to == PromoLifecycle::Published {
+        state.conflict_service.ser_check_promo_publish(id_promo).await?
+    } else {
+        vec![]
+    };
+    log_conflicts("transition-promo", &conflicts);
+
+    let result = state
+        .promo_service
+        .ser_transition_promo(id_promo, payload, &actor)
+        .await?;
+    Ok(with_conflict_warnings(result, conflicts))
+}
+
+pub async fn han_get_promo_transitions(
+    State(state): State<Arc<AppState>>,
+    Path(id_promo): Path<Uuid>,
+) -> Result<Json<Vec<PromoTransition>>, AppError> {
+    let transitions = state.promo_service.ser_get_promo_transitions(id_promo).await?;
+    Ok(Json(transitions))
 }
 
 pub async fn han_delete_promo(
     State(state): State<Arc<AppState>>,
     Path(id_promo): Path<Uuid>,
-) -> Result<Json<()>, AppError> {
-    state.promo_service.ser_delete_promo(id_promo).await?;
-    Ok(Json(()))
+    Query(query): Query<DeleteQuery>,
+    actor: Actor,
+) -> Result<Json<DeleteReport>, AppError> {
+    let report = state
+        .delete_service
+        .ser_delete_promo(id_promo, query, &actor)
+        .await?;
+    Ok(Json(report))
 }
@@ -1,10 +1,18 @@
 use crate::app_state::AppState;
 use crate::error::{AppError, PromoStoreError};
+use crate::handlers::conflict_handler::with_conflict_warnings;
+use crate::middleware::Actor;
+use crate::services::store_assignment_service::StoreAssignmentService;
+use crate::services::conflict_service::log_conflicts;
+use crate::model::conflict_model::WithConflictWarnings;
 use crate::model::promo_store_model::PromoStore;
-use crate::model::promo_store_model::{CreatePromoStorePayload, UpdatePromoStorePayload};
+use crate::model::promo_store_model::{
+    BulkPromoStorePayload, BulkPromoStoreResult, CreatePromoStorePayload, UpdatePromoStorePayload,
+};
 use axum::{
     Json,
     extract::{Path, Query, State},
+    http::HeaderMap,
 };
 use serde::Deserialize;
 use std::sync::Arc;
@@ -16,21 +24,28 @@ pub struct PromoStoreQuery {
     pub store_id: Option<Uuid>,
 }
 
+/// Non-admin hanya melihat link untuk store dalam cakupannya
 pub async fn han_get_promo_stores(
     State(state): State<Arc<AppState>>,
     Query(query): Query<PromoStoreQuery>,
+    actor: Actor,
 ) -> Result<Json<Vec<PromoStore>>, AppError> {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+
     if let Some(promo_id) = query.promo_id {
-        let promo_stores = state.promo_store_service.ser_get_promo_stores_by_promo_id(promo_id).await?;
+        let mut promo_stores = state.promo_store_service.ser_get_promo_stores_by_promo_id(promo_id).await?;
+        promo_stores.retain(|ps| scope.allows_store(ps.store_id));
         return Ok(Json(promo_stores));
     }
 
     if let Some(store_id) = query.store_id {
+        StoreAssignmentService::ensure_store(&scope, store_id)?;
         let promo_stores = state.promo_store_service.ser_get_promo_stores_by_store_id(store_id).await?;
         return Ok(Json(promo_stores));
     }
 
-    let promo_stores = state.promo_store_service.ser_get_all_promo_stores().await?;
+    let mut promo_stores = state.promo_store_service.ser_get_all_promo_stores().await?;
+    promo_stores.retain(|ps| scope.allows_store(ps.store_id));
     if promo_stores.is_empty() {
         return Err(PromoStoreError::NotFound("No promo store relations found".to_string()).into());
     }
@@ -46,6 +61,7 @@ pub struct PromoStoreKey {
 pub async fn han_get_promo_store_by_key(
     State(state): State<Arc<AppState>>,
     Path(key): Path<String>,
+    actor: Actor,
 ) -> Result<Json<PromoStore>, AppError> {
     if key.len() != 73 {
         return Err(PromoStoreError::InvalidKey("Invalid key format. Expected: {uuid}-{uuid}".to_string()).into());
@@ -56,6 +72,9 @@ pub async fn han_get_promo_store_by_key(
     let store_id = Uuid::parse_str(&key[37..73])
         .map_err(|_| PromoStoreError::InvalidKey("Invalid store_id format".to_string()))?;
 
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    StoreAssignmentService::ensure_store(&scope, store_id)?;
+
     let promo_store = state
         .promo_store_service
         .ser_get_promo_store_by_key(promo_id, store_id)
@@ -74,18 +93,43 @@ pub async fn han_get_promo_store_by_key(
 
 pub async fn han_create_promo_store(
     State(state): State<Arc<AppState>>,
+    actor: Actor,
     Json(payload): Json<CreatePromoStorePayload>,
-) -> Result<Json<PromoStore>, AppError> {
-    let created = state
+) -> Result<(HeaderMap, Json<WithConflictWarnings<PromoStore>>), AppError> {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    StoreAssignmentService::ensure_store(&scope, payload.store_id)?;
+
+    let conflicts = state.conflict_service.ser_check_promo_store_link(&payload).await?;
+    log_conflicts("create-promo-store", &conflicts);
+
+    let touches_budget = payload.budget_max_subsidy.is_some() || payload.budget_max_transactions.is_some();
+    let mut created = state
         .promo_store_service
         .ser_create_promo_store(payload)
         .await?;
-    Ok(Json(created))
+    if touches_budget {
+        created.budget_exhausted = refresh_link_budget(&state, created.promo_id, created.store_id).await?;
+    }
+    Ok(with_conflict_warnings(created, conflicts))
+}
+
+pub async fn han_bulk_promo_store(
+    State(state): State<Arc<AppState>>,
+    actor: Actor,
+    Json(payload): Json<BulkPromoStorePayload>,
+) -> Result<Json<BulkPromoStoreResult>, AppError> {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    let result = state
+        .promo_store_service
+        .ser_bulk_promo_stores(payload, &state.conflict_service, &scope)
+        .await?;
+    Ok(Json(result))
 }
 
 pub async fn han_update_promo_store(
     State(state): State<Arc<AppState>>,
     Path(key): Path<String>,
+    actor: Actor,
     Json(payload): Json<UpdatePromoStorePayload>,
 ) -> Result<Json<PromoStore>, AppError> {
     if key.len() != 73 {
@@ -97,23 +141,41 @@ pub async fn han_update_promo_store(
     let store_id = Uuid::parse_str(&key[37..73])
         .map_err(|_| PromoStoreError::InvalidKey("Invalid store_id format".to_string()))?;
 
-    if let Err(_) = han_get_promo_store_by_key(State(state.clone()), Path(key.clone())).await {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    StoreAssignmentService::ensure_store(&scope, store_id)?;
+
+    if state.promo_store_service.ser_get_promo_store_by_key(promo_id, store_id).await.is_err() {
         return Err(PromoStoreError::NotFound(format!(
             "PromoStore with promo_id '{}' and store_id '{}' not found",
             promo_id, store_id
         )).into());
     }
 
-    let updated = state
+    let touches_budget = payload.touches_budget();
+    let mut updated = state
         .promo_store_service
         .ser_update_promo_store(promo_id, store_id, payload)
         .await?;
+    if touches_budget {
+        updated.budget_exhausted = refresh_link_budget(&state, promo_id, store_id).await?;
+    }
     Ok(Json(updated))
 }
 
+/// Sinkronkan flag budget_exhausted link dengan ledger setelah batas anggarannya berubah
+async fn refresh_link_budget(state: &AppState, promo_id: Uuid, store_id: Uuid) -> Result<bool, AppError> {
+    let status = state.budget_service.ser_refresh_budget(promo_id).await?;
+    Ok(status
+        .stores
+        .iter()
+        .find(|s| s.store_id == store_id)
+        .is_some_and(|s| s.budget.exhausted))
+}
+
 pub async fn han_delete_promo_store(
     State(state): State<Arc<AppState>>,
     Path(key): Path<String>,
+    actor: Actor,
 ) -> Result<Json<()>, AppError> {
     if key.len() != 73 {
         return Err(PromoStoreError::InvalidKey("Invalid key format. Expected: {uuid}-{uuid}".to_string()).into());
@@ -124,7 +186,10 @@ pub async fn han_delete_promo_store(
     let store_id = Uuid::parse_str(&key[37..73])
         .map_err(|_| PromoStoreError::InvalidKey("Invalid store_id format".to_string()))?;
 
-    if let Err(_) = han_get_promo_store_by_key(State(state.clone()), Path(key)).await {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    StoreAssignmentService::ensure_store(&scope, store_id)?;
+
+    if state.promo_store_service.ser_get_promo_store_by_key(promo_id, store_id).await.is_err() {
         return Err(PromoStoreError::NotFound(format!(
             "PromoStore with promo_id '{}' and store_id '{}' not found",
             promo_id, store_id
@@ -1,6 +1,9 @@
 use crate::app_state::AppState;
 use crate::error::{AppError, PromoTenorError};
+use crate::middleware::Actor;
+use crate::model::delete_model::{DeleteQuery, DeleteReport};
 use crate::model::promo_tenor_model::*;
+use crate::model::tenor_change_model::*;
 use axum::{
     Json,
     extract::{Path, Query, State},
@@ -21,26 +24,29 @@ pub async fn han_get_all_promo_tenors(
     State(state): State<Arc<AppState>>,
     Query(query): Query<PromoTenorQuery>,
 ) -> Result<Json<Vec<PromoTenorResponse>>, AppError> {
+    let service = &state.promo_tenor_service;
+
     // Filter by promo_id
     if let Some(promo_id) = query.promo_id {
-        let promo_tenors = state.promo_tenor_service.ser_get_promo_tenors_by_promo_id(promo_id).await?;
-        return Ok(Json(promo_tenors));
+        let promo_tenors = service.ser_get_promo_tenors_by_promo_id(promo_id).await?;
+        return Ok(Json(service.ser_retain_published(promo_tenors).await?));
     }
 
     // Filter by tenor
     if let Some(tenor) = query.tenor {
-        let promo_tenors = state.promo_tenor_service.ser_get_promo_tenors_by_tenor(tenor).await?;
-        return Ok(Json(promo_tenors));
+        let promo_tenors = service.ser_get_promo_tenors_by_tenor(tenor).await?;
+        return Ok(Json(service.ser_retain_published(promo_tenors).await?));
     }
 
     // Filter by voucher
     if let Some(voucher) = query.voucher {
-        let promo_tenors = state.promo_tenor_service.ser_get_promo_tenors_by_voucher(&voucher).await?;
-        return Ok(Json(promo_tenors));
+        let promo_tenors = service.ser_get_promo_tenors_by_voucher(&voucher).await?;
+        return Ok(Json(service.ser_retain_published(promo_tenors).await?));
     }
 
     // Get all
-    let promo_tenors = state.promo_tenor_service.ser_get_all_promo_tenors().await?;
+    let promo_tenors = service.ser_get_all_promo_tenors().await?;
+    let promo_tenors = service.ser_retain_published(promo_tenors).await?;
     if promo_tenors.is_empty() {
         return Err(PromoTenorError::NotFound("No promo tenor found".to_string()).into());
     }
@@ -76,15 +82,81 @@ pub async fn han_update_promo_tenor(
 pub async fn han_delete_promo_tenor(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
-) -> Result<Json<()>, AppError> {
-    state.promo_tenor_service.ser_delete_promo_tenor(id).await?;
-    Ok(Json(()))
+    Query(query): Query<DeleteQuery>,
+    actor: Actor,
+) -> Result<Json<DeleteReport>, AppError> {
+    let report = state
+        .delete_service
+        .ser_delete_promo_tenor(id, query, &actor)
+        .await?;
+    Ok(Json(report))
 }
 
 pub async fn han_get_promo_tenors_by_store_id(
     State(state): State<Arc<AppState>>,
     Path(store_id): Path<Uuid>,
-) -> Result<Json<Vec<PromoTenorResponse>>, AppError> {
+) -> Result<Json<Vec<StorePromoTenor>>, AppError> {
     let tenors = state.promo_tenor_service.ser_get_promo_tenors_by_store_id(store_id).await?;
     Ok(Json(tenors))
 }
+
+#[derive(Deserialize)]
+pub struct PromoTenorChangeQuery {
+    pub status: Option<TenorChangeStatus>,
+    pub promo_tenor_id: Option<Uuid>,
+}
+
+pub async fn han_schedule_promo_tenor_change(
+    State(state): State<Arc<AppState>>,
+    actor: Actor,
+    Json(payload): Json<SchedulePromoTenorChangePayload>,
+) -> Result<Json<PromoTenorChange>, AppError> {
+    let change = state
+        .promo_tenor_service
+        .ser_schedule_promo_tenor_change(payload, &actor)
+        .await?;
+    Ok(Json(change))
+}
+
+pub async fn han_get_promo_tenor_changes(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PromoTenorChangeQuery>,
+) -> Result<Json<Vec<PromoTenorChange>>, AppError> {
+    let changes = state
+        .promo_tenor_service
+        .ser_get_promo_tenor_changes(query.status, query.promo_tenor_id)
+        .await?;
+    Ok(Json(changes))
+}
+
+pub async fn han_get_promo_tenor_change_by_id(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PromoTenorChange>, AppError> {
+    let change = state.promo_tenor_service.ser_get_promo_tenor_change_by_id(id).await?;
+    Ok(Json(change))
+}
+
+pub async fn han_approve_promo_tenor_change(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Result<Json<PromoTenorChange>, AppError> {
+    let change = state
+        .promo_tenor_service
+        .ser_approve_promo_tenor_change(id, &actor)
+        .await?;
+    Ok(Json(change))
+}
+
+pub async fn han_cancel_promo_tenor_change(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Result<Json<PromoTenorChange>, AppError> {
+    let change = state
+        .promo_tenor_service
+        .ser_cancel_promo_tenor_change(id, &actor)
+        .await?;
+    Ok(Json(change))
+}
@@ -0,0 +1,48 @@
+use crate::app_state::AppState;
+use crate::error::AppError;
+use crate::handlers::installment_handler::prefers_csv;
+use crate::middleware::Actor;
+use crate::model::settlement_model::*;
+use crate::services::store_assignment_service::StoreAssignmentService;
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::{HeaderMap, header},
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+/// JSON secara default, `text/csv` jika diminta lewat header `Accept`.
+/// Non-admin hanya melihat transaksi store dalam cakupannya.
+pub async fn han_get_settlement_report(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<SettlementQuery>,
+    headers: HeaderMap,
+    actor: Actor,
+) -> Result<Response, AppError> {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    if let Some(store_id) = query.store_id {
+        StoreAssignmentService::ensure_store(&scope, store_id)?;
+    }
+    let report = state
+        .settlement_service
+        .ser_get_settlement_report(query, &scope)
+        .await?;
+
+    if prefers_csv(&headers) {
+        let filename = format!(
+            "attachment; filename=\"settlement-{}-{}.csv\"",
+            report.from, report.to
+        );
+        return Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, filename),
+            ],
+            report.to_csv(),
+        )
+            .into_response());
+    }
+
+    Ok(Json(report).into_response())
+}
@@ -0,0 +1,49 @@
+use crate::app_state::AppState;
+use crate::error::AppError;
+use crate::middleware::Actor;
+use crate::model::store_assignment_model::*;
+use crate::model::store_model::Store;
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub async fn han_get_store_assignments(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StoreAssignmentQuery>,
+) -> Result<Json<Vec<StoreAssignment>>, AppError> {
+    let assignments = state.store_assignment_service.ser_get_assignments(query).await?;
+    Ok(Json(assignments))
+}
+
+pub async fn han_create_store_assignment(
+    State(state): State<Arc<AppState>>,
+    actor: Actor,
+    Json(payload): Json<CreateStoreAssignmentPayload>,
+) -> Result<Json<StoreAssignment>, AppError> {
+    let assignment = state
+        .store_assignment_service
+        .ser_create_assignment(payload, &actor)
+        .await?;
+    Ok(Json(assignment))
+}
+
+pub async fn han_delete_store_assignment(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    actor: Actor,
+) -> Result<Json<()>, AppError> {
+    state.store_assignment_service.ser_delete_assignment(id, &actor).await?;
+    Ok(Json(()))
+}
+
+/// Store dalam cakupan pemanggil (semua store untuk admin)
+pub async fn han_get_my_stores(
+    State(state): State<Arc<AppState>>,
+    actor: Actor,
+) -> Result<Json<Vec<Store>>, AppError> {
+    let stores = state.store_assignment_service.ser_get_my_stores(&actor).await?;
+    Ok(Json(stores))
+}
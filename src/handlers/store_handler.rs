@@ -1,9 +1,12 @@
 use crate::error::{AppError, StoreError};
 use crate::model::store_model::{Store, CreateStorePayload, UpdateStorePayload};
+use crate::model::delete_model::{DeleteQuery, DeleteReport};
 use crate::app_state::AppState;
+use crate::middleware::Actor;
+use crate::services::store_assignment_service::StoreAssignmentService;
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
 };
 use std::sync::Arc;
 
@@ -33,9 +36,18 @@ pub async fn han_get_store_by_route(
 
 pub async fn han_create_store(
     State(state): State<Arc<AppState>>,
+    actor: Actor,
     Json(payload): Json<CreateStorePayload>,
 ) -> Result<Json<Store>, AppError> {
-    if let Ok(_) = han_get_store_by_route(State(state.clone()), Path(payload.route.clone())).await {
+    // Non-admin hanya boleh membuat store di bawah company yang di-assign
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    if !scope.is_all() {
+        let company_id = payload.company_id.ok_or_else(|| {
+            AppError::Forbidden("company_id of an assigned company is required to create a store".to_string())
+        })?;
+        StoreAssignmentService::ensure_company(&scope, company_id)?;
+    }
+    if han_get_store_by_route(State(state.clone()), Path(payload.route.clone())).await.is_ok() {
         return Err(StoreError::AlreadyExists(format!("Store with route '{}' already exists", payload.route)).into());
     }
     let store = state.store_service.ser_create_store(payload).await?;
@@ -45,10 +57,26 @@ pub async fn han_create_store(
 pub async fn han_update_store(
     State(state): State<Arc<AppState>>,
     Path(store_route): Path<String>,
+    actor: Actor,
     Json(payload): Json<UpdateStorePayload>,
 ) -> Result<Json<Store>, AppError> {
-    if let Err(_) = han_get_store_by_route(State(state.clone()), Path(store_route.clone())).await {
+    let Ok(Json(existing)) = han_get_store_by_route(State(state.clone()), Path(store_route.clone())).await else {
         return Err(StoreError::NotFound(format!("Store with route '{}' not found", store_route)).into());
+    };
+
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    StoreAssignmentService::ensure_store(&scope, existing.id)?;
+    if !scope.is_all() {
+        // Pindah company hanya lewat company_id yang juga di-assign; ganti nama / lepas company = admin
+        match (payload.company_id, &payload.company) {
+            (Some(Some(company_id)), _) => StoreAssignmentService::ensure_company(&scope, company_id)?,
+            (None, None) => {}
+            _ => {
+                return Err(AppError::Forbidden(
+                    "Only admins can change a store's company by name or detach it; send an assigned company_id".to_string(),
+                ));
+            }
+        }
     }
     let store = state.store_service.ser_update_store(&store_route, payload).await?;
     Ok(Json(store))
@@ -57,7 +85,12 @@ pub async fn han_update_store(
 pub async fn han_delete_store(
     State(state): State<Arc<AppState>>,
     Path(store_route): Path<String>,
-) -> Result<Json<()>, AppError> {
-    state.store_service.ser_delete_store(&store_route).await?;
-    Ok(Json(()))
+    Query(query): Query<DeleteQuery>,
+    actor: Actor,
+) -> Result<Json<DeleteReport>, AppError> {
+    let report = state
+        .delete_service
+        .ser_delete_store(&store_route, query, &actor)
+        .await?;
+    Ok(Json(report))
 }
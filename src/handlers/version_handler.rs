@@ -0,0 +1,62 @@
+use crate::app_state::AppState;
+use crate::error::AppError;
+use crate::model::version_model::*;
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct AtQuery {
+    pub at: String,
+}
+
+pub async fn han_get_entity_versions(
+    State(state): State<Arc<AppState>>,
+    Path((entity_type, entity_id)): Path<(VersionedEntity, String)>,
+) -> Result<Json<Vec<EntityVersion>>, AppError> {
+    let versions = state
+        .version_service
+        .ser_get_versions(entity_type, &entity_id)
+        .await?;
+    Ok(Json(versions))
+}
+
+pub async fn han_diff_entity_versions(
+    State(state): State<Arc<AppState>>,
+    Path((entity_type, entity_id)): Path<(VersionedEntity, String)>,
+    Query(query): Query<VersionDiffQuery>,
+) -> Result<Json<VersionDiff>, AppError> {
+    let diff = state
+        .version_service
+        .ser_diff_versions(entity_type, &entity_id, query)
+        .await?;
+    Ok(Json(diff))
+}
+
+pub async fn han_get_entity_as_of(
+    State(state): State<Arc<AppState>>,
+    Path((entity_type, entity_id)): Path<(VersionedEntity, String)>,
+    Query(query): Query<AtQuery>,
+) -> Result<Json<EntityVersion>, AppError> {
+    let version = state
+        .version_service
+        .ser_get_as_of(entity_type, &entity_id, &query.at)
+        .await?;
+    Ok(Json(version))
+}
+
+pub async fn han_get_store_terms_as_of(
+    State(state): State<Arc<AppState>>,
+    Path(store_id): Path<Uuid>,
+    Query(query): Query<AsOfQuery>,
+) -> Result<Json<StoreTermsAsOf>, AppError> {
+    let terms = state
+        .version_service
+        .ser_get_store_terms_as_of(store_id, query)
+        .await?;
+    Ok(Json(terms))
+}
@@ -0,0 +1,38 @@
+use crate::app_state::AppState;
+use crate::error::AppError;
+use crate::middleware::Actor;
+use crate::model::voucher_model::*;
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use std::sync::Arc;
+
+pub async fn han_validate_voucher(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<ValidateVoucherPayload>,
+) -> Result<Json<VoucherValidation>, AppError> {
+    let validation = state.voucher_service.ser_validate_voucher(payload).await?;
+    Ok(Json(validation))
+}
+
+pub async fn han_redeem_voucher(
+    State(state): State<Arc<AppState>>,
+    actor: Actor,
+    Json(payload): Json<RedeemVoucherPayload>,
+) -> Result<Json<VoucherRedemptionResult>, AppError> {
+    let scope = state.store_assignment_service.ser_scope(&actor).await?;
+    let result = state.voucher_service.ser_redeem_voucher(payload, &scope).await?;
+    Ok(Json(result))
+}
+
+pub async fn han_get_voucher_redemptions(
+    State(state): State<Arc<AppState>>,
+    Path(voucher_code): Path<String>,
+) -> Result<Json<Vec<VoucherRedemption>>, AppError> {
+    let redemptions = state
+        .voucher_service
+        .ser_get_redemptions_by_voucher(&voucher_code)
+        .await?;
+    Ok(Json(redemptions))
+}
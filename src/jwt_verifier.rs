@@ -0,0 +1,281 @@
+use crate::app_state::AppState;
+use crate::error::AppError;
+use crate::middleware::Claims;
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, JwkSet, KeyAlgorithm};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{error, info, warn};
+
+/// Sumber dokumen JWKS: `JWKS_URL` (mis. `https://<project>.supabase.co/auth/v1/.well-known/jwks.json`)
+/// atau `JWKS_FILE` untuk lingkungan tanpa akses keluar.
+#[derive(Debug, Clone)]
+pub enum JwksSource {
+    Url(String),
+    File(String),
+}
+
+impl std::fmt::Display for JwksSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JwksSource::Url(url) => write!(f, "{}", url),
+            JwksSource::File(path) => write!(f, "file {}", path),
+        }
+    }
+}
+
+struct JwksKey {
+    key: DecodingKey,
+    algorithm: Algorithm,
+    /// JWK mentah, untuk mendeteksi key yang diganti tanpa ganti `kid`
+    jwk: String,
+}
+
+/// Verifikasi JWT: HS256 dengan `JWT_SECRET` dan/atau RS256/ES256 dengan key dari JWKS
+/// yang dipilih lewat `kid`. Konfigurasi dibaca sekali saat startup.
+pub struct JwtVerifier {
+    secret: Option<DecodingKey>,
+    jwks_source: Option<JwksSource>,
+    jwks_keys: RwLock<HashMap<String, JwksKey>>,
+    /// Naik setiap isi JWKS berubah; claims yang di-cache dengan versi lama tidak dipakai lagi
+    key_set_version: AtomicU64,
+    /// Dipegang selama fetch, jadi request dengan `kid` baru yang datang bersamaan cukup menunggu satu refresh
+    last_refresh: Mutex<Option<Instant>>,
+    min_refresh_interval: Duration,
+    refresh_interval: Duration,
+    issuer: Option<String>,
+    audience: Vec<String>,
+    http: reqwest::Client,
+}
+
+impl JwtVerifier {
+    pub fn from_env() -> Self {
+        let secret = std::env::var("JWT_SECRET")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|s| DecodingKey::from_secret(s.as_bytes()));
+
+        let jwks_source = match (std::env::var("JWKS_URL"), std::env::var("JWKS_FILE")) {
+            (Ok(url), _) if !url.is_empty() => Some(JwksSource::Url(url)),
+            (_, Ok(path)) if !path.is_empty() => Some(JwksSource::File(path)),
+            _ => None,
+        };
+
+        let issuer = std::env::var("JWT_ISSUER").ok().filter(|s| !s.is_empty());
+        let audience: Vec<String> = std::env::var("JWT_AUDIENCE")
+            .unwrap_or_else(|_| "authenticated".to_string())
+            .split(',')
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty())
+            .collect();
+
+        let refresh_interval = std::env::var("JWKS_REFRESH_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(3600));
+        let min_refresh_interval = std::env::var("JWKS_MIN_REFRESH_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(30));
+
+        info!(
+            "JWT verifier configured: HS256 {}, JWKS {}, issuer {}, audience {:?}",
+            if secret.is_some() { "enabled" } else { "disabled" },
+            jwks_source.as_ref().map(|s| s.to_string()).unwrap_or_else(|| "disabled".to_string()),
+            issuer.as_deref().unwrap_or("not checked"),
+            audience
+        );
+        if secret.is_none() && jwks_source.is_none() {
+            warn!("Neither JWT_SECRET nor JWKS_URL/JWKS_FILE is set; every protected request will fail");
+        }
+
+        Self {
+            secret,
+            jwks_source,
+            jwks_keys: RwLock::new(HashMap::new()),
+            key_set_version: AtomicU64::new(0),
+            last_refresh: Mutex::new(None),
+            min_refresh_interval,
+            refresh_interval,
+            issuer,
+            audience,
+            // Request dengan `kid` baru menunggu fetch ini, jadi jangan biarkan menggantung
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    pub async fn verify(&self, token: &str) -> Result<Claims, AppError> {
+        let header = decode_header(token).map_err(|e| {
+            error!("JWT header error: {:?}", e);
+            AppError::Unauthorized
+        })?;
+
+        let (key, algorithm) = match header.alg {
+            Algorithm::HS256 => {
+                let secret = self.secret.clone().ok_or_else(|| {
+                    if self.jwks_source.is_none() {
+                        AppError::Internal("No JWT verification key configured".to_string())
+                    } else {
+                        AppError::InvalidToken("HS256 tokens are not accepted".to_string())
+                    }
+                })?;
+                (secret, Algorithm::HS256)
+            }
+            Algorithm::RS256 | Algorithm::ES256 => {
+                let kid = header
+                    .kid
+                    .ok_or_else(|| AppError::InvalidToken("Token has no kid".to_string()))?;
+                self.jwks_key(&kid, header.alg).await?
+            }
+            other => {
+                return Err(AppError::InvalidToken(format!("Unsupported JWT algorithm {:?}", other)));
+            }
+        };
+
+        let mut validation = Validation::new(algorithm);
+        validation.set_audience(&self.audience);
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+
+        decode::<Claims>(token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(|e| {
+                error!("JWT Validation Error: {:?}", e);
+                AppError::Unauthorized
+            })
+    }
+
+    /// Key untuk `kid`; `kid` yang belum dikenal memicu refresh (dibatasi `min_refresh_interval`)
+    async fn jwks_key(&self, kid: &str, alg: Algorithm) -> Result<(DecodingKey, Algorithm), AppError> {
+        if self.jwks_source.is_none() {
+            return Err(AppError::InvalidToken(format!("{:?} tokens are not accepted", alg)));
+        }
+
+        if !self.jwks_keys.read().await.contains_key(kid) {
+            info!(kid = %kid, "Unknown JWT kid, refreshing JWKS");
+            if let Err(e) = self.refresh(false).await {
+                warn!("JWKS refresh failed: {}", e);
+            }
+        }
+
+        let keys = self.jwks_keys.read().await;
+        let entry = keys
+            .get(kid)
+            .ok_or_else(|| AppError::InvalidToken(format!("Unknown signing key '{}'", kid)))?;
+        if entry.algorithm != alg {
+            return Err(AppError::InvalidToken(format!(
+                "Signing key '{}' is {:?}, token uses {:?}",
+                kid, entry.algorithm, alg
+            )));
+        }
+        Ok((entry.key.clone(), entry.algorithm))
+    }
+
+    /// Muat ulang JWKS. Tanpa `force`, refresh dilewati jika refresh terakhir belum lewat
+    /// `min_refresh_interval` (mencegah token dengan `kid` acak membanjiri sumber JWKS).
+    pub async fn refresh(&self, force: bool) -> Result<(), String> {
+        let Some(source) = &self.jwks_source else {
+            return Ok(());
+        };
+
+        let mut last_refresh = self.last_refresh.lock().await;
+        if !force && last_refresh.is_some_and(|at| at.elapsed() < self.min_refresh_interval) {
+            return Ok(());
+        }
+        *last_refresh = Some(Instant::now());
+
+        let raw = match source {
+            JwksSource::Url(url) => self
+                .http
+                .get(url)
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| format!("fetch {}: {}", url, e))?
+                .text()
+                .await
+                .map_err(|e| format!("read {}: {}", url, e))?,
+            JwksSource::File(path) => tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| format!("read {}: {}", path, e))?,
+        };
+        let set: JwkSet = serde_json::from_str(&raw).map_err(|e| format!("parse JWKS: {}", e))?;
+
+        let mut keys = HashMap::new();
+        for jwk in &set.keys {
+            let Some(kid) = jwk.common.key_id.clone() else {
+                warn!("JWKS key without kid skipped");
+                continue;
+            };
+            let algorithm = match (&jwk.common.key_algorithm, &jwk.algorithm) {
+                (Some(KeyAlgorithm::RS256), AlgorithmParameters::RSA(_)) => Algorithm::RS256,
+                (Some(KeyAlgorithm::ES256), AlgorithmParameters::EllipticCurve(ec))
+                    if ec.curve == EllipticCurve::P256 =>
+                {
+                    Algorithm::ES256
+                }
+                (None, AlgorithmParameters::RSA(_)) => Algorithm::RS256,
+                (None, AlgorithmParameters::EllipticCurve(ec)) if ec.curve == EllipticCurve::P256 => {
+                    Algorithm::ES256
+                }
+                _ => {
+                    warn!(kid = %kid, "JWKS key skipped: only RS256 and ES256 (P-256) are supported");
+                    continue;
+                }
+            };
+            match DecodingKey::from_jwk(jwk) {
+                Ok(key) => {
+                    let raw = serde_json::to_string(jwk).unwrap_or_default();
+                    keys.insert(kid, JwksKey { key, algorithm, jwk: raw });
+                }
+                Err(e) => warn!(kid = %kid, "JWKS key skipped: {}", e),
+            }
+        }
+
+        let count = keys.len();
+        let mut current = self.jwks_keys.write().await;
+        let changed = current.len() != keys.len()
+            || keys
+                .iter()
+                .any(|(kid, key)| current.get(kid).is_none_or(|old| old.jwk != key.jwk));
+        *current = keys;
+        drop(current);
+        if changed {
+            self.key_set_version.fetch_add(1, Ordering::SeqCst);
+        }
+        info!("JWKS loaded from {}: {} key(s), changed: {}", source, count, changed);
+        Ok(())
+    }
+
+    /// Versi isi JWKS saat ini; berubah saat key dirotasi atau dicabut
+    pub fn key_set_version(&self) -> u64 {
+        self.key_set_version.load(Ordering::SeqCst)
+    }
+}
+
+/// Background job yang memuat ulang JWKS setiap `JWKS_REFRESH_INTERVAL_SECONDS`
+/// (default 1 jam) agar key yang dirotasi/dicabut ikut berganti. Muatan pertama
+/// dilakukan di `main` sebelum server menerima request.
+pub async fn run(state: Arc<AppState>) {
+    let verifier = &state.jwt_verifier;
+    if verifier.jwks_source.is_none() {
+        return;
+    }
+    info!("JWKS refresh started (interval {:?})", verifier.refresh_interval);
+
+    loop {
+        tokio::time::sleep(verifier.refresh_interval).await;
+        if let Err(e) = verifier.refresh(true).await {
+            warn!("JWKS refresh failed: {}", e);
+        }
+    }
+}
@@ -15,46 +15,104 @@ use std::{sync::Arc, time::Duration};
 use tower_http::cors::CorsLayer;
 use tracing::info;
 
+use crate::access_policy::{AccessPolicy, PolicyRouter};
 use crate::app_state::AppState;
 use handlers::promo_handler::{
-    han_create_promo, han_delete_promo, han_get_all_promos, han_get_promo_by_id,
-    han_update_promo,
+    han_clone_promo, han_create_promo, han_delete_promo, han_get_all_promos, han_get_promo_by_id,
+    han_get_promo_transitions, han_get_promos_by_lifecycle, han_transition_promo, han_update_promo,
 };
 use handlers::promo_tenor_handler::{
-    han_create_promo_tenor, han_delete_promo_tenor, han_get_all_promo_tenors,
-    han_get_promo_tenor_by_id, han_update_promo_tenor, han_get_promo_tenors_by_store_id,
+    han_approve_promo_tenor_change, han_cancel_promo_tenor_change, han_create_promo_tenor, han_delete_promo_tenor,
+    han_get_all_promo_tenors, han_get_promo_tenor_by_id, han_get_promo_tenor_change_by_id,
+    han_get_promo_tenor_changes, han_get_promo_tenors_by_store_id, han_schedule_promo_tenor_change,
+    han_update_promo_tenor,
 };
 use handlers::promo_store_handler::{
-    han_create_promo_store, han_delete_promo_store, han_get_promo_store_by_key,
+    han_bulk_promo_store, han_create_promo_store, han_delete_promo_store, han_get_promo_store_by_key,
     han_get_promo_stores, han_update_promo_store,
 };
+use handlers::settlement_handler::han_get_settlement_report;
 use handlers::store_handler::{
     han_create_store, han_delete_store, han_get_store_by_route, han_get_stores, han_update_store,
 };
+use handlers::store_assignment_handler::{
+    han_create_store_assignment, han_delete_store_assignment, han_get_my_stores,
+    han_get_store_assignments,
+};
+use handlers::version_handler::{
+    han_diff_entity_versions, han_get_entity_as_of, han_get_entity_versions,
+    han_get_store_terms_as_of,
+};
+use handlers::voucher_handler::{
+    han_get_voucher_redemptions, han_redeem_voucher, han_validate_voucher,
+};
+use handlers::company_handler::{
+    han_create_company, han_delete_company, han_get_companies, han_get_company_by_id,
+    han_get_company_promos, han_get_company_stores, han_migrate_store_companies,
+    han_update_company,
+};
+use handlers::api_key_handler::{han_create_api_key, han_get_api_key_usage, han_get_api_keys, han_revoke_api_key};
+use handlers::budget_handler::{
+    han_get_financed_transactions, han_get_promo_budget, han_record_transaction,
+};
+use handlers::conflict_handler::han_get_promo_conflicts;
 use handlers::health_handler::{health_check, ready_check, metrics};
+use handlers::installment_handler::{
+    han_get_installment_schedule, han_recommend_promo, han_simulate_installment,
+};
+use handlers::lead_handler::{
+    han_assign_lead, han_get_lead_by_id, han_get_leads, han_submit_lead, han_update_lead_status,
+};
 use middleware::{auth, create_cors_layer};
+use repositories::api_key_repository::ApiKeyRepository;
 use repositories::cache_repository::CacheRepository;
+use repositories::company_repository::CompanyRepository;
+use repositories::entity_version_repository::EntityVersionRepository;
+use repositories::financed_transaction_repository::FinancedTransactionRepository;
+use repositories::lead_repository::LeadRepository;
 use repositories::promo_repository::PromoRepository;
 use repositories::promo_store_repository::PromoStoreRepository;
+use repositories::promo_tenor_change_repository::PromoTenorChangeRepository;
 use repositories::promo_tenor_repository::PromoTenorRepository;
+use repositories::promo_transition_repository::PromoTransitionRepository;
+use repositories::store_assignment_repository::StoreAssignmentRepository;
 use repositories::store_repository::StoreRepository;
+use repositories::voucher_redemption_repository::VoucherRedemptionRepository;
+use services::api_key_service::ApiKeyService;
+use services::budget_service::BudgetService;
+use services::company_service::CompanyService;
+use services::conflict_service::ConflictService;
+use services::delete_service::DeleteService;
+use services::installment_service::InstallmentService;
+use services::lead_service::LeadService;
 use services::promo_service::PromoService;
 use services::promo_store_service::PromoStoreService;
 use services::promo_tenor_service::PromoTenorService;
+use services::settlement_service::SettlementService;
+use services::store_assignment_service::StoreAssignmentService;
 use services::store_service::StoreService;
+use services::version_service::VersionService;
+use services::voucher_service::VoucherService;
 use supabase::SupabaseClient;
 
+mod access_policy;
 mod app_state;
+mod business_time;
 mod constants;
+mod disclosure;
 mod error;
 mod handlers;
+mod jwt_verifier;
 mod middleware;
 mod model;
+mod promo_scheduler;
 mod rate_limiter;
 mod repositories;
+mod scheduler;
 mod services;
 mod startup;
 mod supabase;
+mod tenor_change_scheduler;
 
 #[tokio::main(flavor = "multi_thread", worker_threads = 2)]
 async fn main() {
@@ -80,6 +138,10 @@ async fn main() {
     info!("Supabase client created successfully."); // <-- PERBAIKAN 2
     let cache_repository = Arc::new(CacheRepository::new());
 
+    let version_repo = Arc::new(EntityVersionRepository::new(
+        Arc::clone(&supabase_client),
+        Arc::clone(&cache_repository),
+    ));
     let promo_repo = Arc::new(PromoRepository::new(
         Arc::clone(&supabase_client),
         Arc::clone(&cache_repository),
@@ -96,6 +158,35 @@ async fn main() {
         Arc::clone(&supabase_client),
         Arc::clone(&cache_repository),
     ));
+    let promo_tenor_change_repo = Arc::new(PromoTenorChangeRepository::new(
+        Arc::clone(&supabase_client),
+        Arc::clone(&cache_repository),
+    ));
+    let promo_transition_repo = Arc::new(PromoTransitionRepository::new(
+        Arc::clone(&supabase_client),
+        Arc::clone(&cache_repository),
+    ));
+    let company_repo = Arc::new(CompanyRepository::new(
+        Arc::clone(&supabase_client),
+        Arc::clone(&cache_repository),
+    ));
+    let voucher_redemption_repo = Arc::new(VoucherRedemptionRepository::new(
+        Arc::clone(&supabase_client),
+        Arc::clone(&cache_repository),
+    ));
+    let financed_transaction_repo = Arc::new(FinancedTransactionRepository::new(
+        Arc::clone(&supabase_client),
+        Arc::clone(&cache_repository),
+    ));
+    let lead_repo = Arc::new(LeadRepository::new(
+        Arc::clone(&supabase_client),
+        Arc::clone(&cache_repository),
+    ));
+    let store_assignment_repo = Arc::new(StoreAssignmentRepository::new(Arc::clone(&supabase_client)));
+    let api_key_repo = Arc::new(ApiKeyRepository::new(
+        Arc::clone(&supabase_client),
+        Arc::clone(&cache_repository),
+    ));
 
     info!("Repositories initialized successfully.");
 
@@ -107,69 +198,276 @@ async fn main() {
     )
     .await;
 
+    tokio::spawn(promo_scheduler::run(
+        Arc::clone(&promo_repo),
+        Arc::clone(&cache_repository),
+    ));
+
     // Tidak perlu Arc di sini karena service akan dipindahkan ke dalam AppState
-    let promo_service = PromoService::new(promo_repo);
-    let promo_tenor_service = PromoTenorService::new(promo_tenor_repo);
-    let store_service = StoreService::new(store_repo);
-    let promo_store_service = PromoStoreService::new(promo_store_repo);
+    let installment_service = InstallmentService::new(
+        Arc::clone(&store_repo),
+        Arc::clone(&promo_repo),
+        Arc::clone(&promo_tenor_repo),
+    );
+    let voucher_service = VoucherService::new(
+        voucher_redemption_repo,
+        Arc::clone(&store_repo),
+        Arc::clone(&promo_repo),
+        Arc::clone(&promo_tenor_repo),
+    );
+    let conflict_service = ConflictService::new(
+        Arc::clone(&promo_repo),
+        Arc::clone(&promo_store_repo),
+        Arc::clone(&promo_tenor_repo),
+        Arc::clone(&store_repo),
+    );
+    let company_service = CompanyService::new(
+        Arc::clone(&company_repo),
+        Arc::clone(&store_repo),
+        Arc::clone(&promo_repo),
+        Arc::clone(&promo_store_repo),
+    );
+    let lead_service = LeadService::new(
+        lead_repo,
+        Arc::clone(&store_repo),
+        Arc::clone(&promo_repo),
+        Arc::clone(&promo_tenor_repo),
+    );
+    let settlement_service = SettlementService::new(
+        Arc::clone(&financed_transaction_repo),
+        Arc::clone(&store_repo),
+        Arc::clone(&company_repo),
+        Arc::clone(&promo_repo),
+    );
+    let budget_service = BudgetService::new(
+        financed_transaction_repo,
+        Arc::clone(&promo_repo),
+        Arc::clone(&promo_store_repo),
+        Arc::clone(&promo_tenor_repo),
+        Arc::clone(&store_repo),
+    );
+    let promo_store_service = PromoStoreService::new(
+        Arc::clone(&promo_store_repo),
+        Arc::clone(&promo_tenor_repo),
+        Arc::clone(&promo_repo),
+        Arc::clone(&store_repo),
+    );
+    let delete_service = DeleteService::new(
+        Arc::clone(&promo_repo),
+        Arc::clone(&promo_tenor_repo),
+        Arc::clone(&promo_store_repo),
+        Arc::clone(&store_repo),
+        Arc::clone(&promo_tenor_change_repo),
+        Arc::clone(&store_assignment_repo),
+        Arc::clone(&cache_repository),
+    );
+    let promo_tenor_service = PromoTenorService::new(
+        Arc::clone(&promo_tenor_repo),
+        Arc::clone(&promo_repo),
+        promo_tenor_change_repo,
+    );
+    let promo_service = PromoService::new(
+        promo_repo,
+        promo_tenor_repo,
+        Arc::clone(&promo_store_repo),
+        promo_transition_repo,
+    );
+    let store_assignment_service = StoreAssignmentService::new(
+        store_assignment_repo,
+        Arc::clone(&store_repo),
+        Arc::clone(&company_repo),
+    );
+    let store_service = StoreService::new(store_repo, company_repo);
+    let api_key_service = ApiKeyService::new(api_key_repo);
+    let version_service = VersionService::new(version_repo);
+
+    let jwt_verifier = jwt_verifier::JwtVerifier::from_env();
+    if let Err(e) = jwt_verifier.refresh(true).await {
+        tracing::warn!("Initial JWKS load failed, retrying on first unknown kid: {}", e);
+    }
 
     let state = Arc::new(AppState {
         cache_repository,
+        jwt_verifier,
         promo_service,
         promo_tenor_service,
         store_service,
         promo_store_service,
+        installment_service,
+        voucher_service,
+        conflict_service,
+        company_service,
+        budget_service,
+        settlement_service,
+        lead_service,
+        version_service,
+        delete_service,
+        store_assignment_service,
+        api_key_service,
     });
 
-    // Public promo routes
-    let public_promo = Router::new()
-        .route("/get-promo", get(han_get_all_promos));
-
-    // Protected promo routes
-    let protected_promo = Router::new()
-        .route("/get-promo/{id_promo}", get(han_get_promo_by_id))
-        .route("/create-promo", post(han_create_promo))
-        .route("/update-promo/{id_promo}", put(han_update_promo))
-        .route("/delete-promo/{id_promo}", delete(han_delete_promo));
-
-    // Public promo_tenor routes
-    let public_promo_tenor = Router::new()
-        .route("/get-promo-tenor", get(han_get_all_promo_tenors))
-        .route("/get-promo-tenor-by-store/{store_id}", get(han_get_promo_tenors_by_store_id));
-
-    // Protected promo_tenor routes
-    let protected_promo_tenor = Router::new()
-        .route("/get-promo-tenor/{id}", get(han_get_promo_tenor_by_id))
-        .route("/create-promo-tenor", post(han_create_promo_tenor))
-        .route("/update-promo-tenor/{id}", put(han_update_promo_tenor))
-        .route("/delete-promo-tenor/{id}", delete(han_delete_promo_tenor));
-
-    // Public store routes
-    let public_store = Router::new()
-        .route("/get-store", get(han_get_stores))
-        .route("/get-store/{route}", get(han_get_store_by_route));
-
-    // Protected store routes
-    let protected_store = Router::new()
-        .route("/create-store", post(han_create_store))
-        .route("/update-store/{route}", put(han_update_store))
-        .route("/delete-store/{route}", delete(han_delete_store));
-
-    // Protected promo_store routes (all protected)
-    let protected_promo_store = Router::new()
-        .route("/get-promo-store", get(han_get_promo_stores))
-        .route("/get-promo-store/{key}", get(han_get_promo_store_by_key))
-        .route("/create-promo-store", post(han_create_promo_store))
-        .route("/update-promo-store/{key}", put(han_update_promo_store))
-        .route("/delete-promo-store/{key}", delete(han_delete_promo_store));
-
-    // Merge all protected routes
-    let protected_routes = Router::new()
-        .merge(protected_promo)
-        .merge(protected_promo_tenor)
-        .merge(protected_store)
-        .merge(protected_promo_store)
-        .route_layer(from_fn_with_state(state.clone(), auth));
+    tokio::spawn(tenor_change_scheduler::run(Arc::clone(&state)));
+    tokio::spawn(jwt_verifier::run(Arc::clone(&state)));
+
+    // Setiap route mendeklarasikan AccessPolicy-nya; auth & rate limiter membaca deklarasi ini
+    use AccessPolicy::{Public, Roles};
+    use constants::{ADMIN_ONLY, ANY_ROLE, PROMO_EDITORS, PROMO_STORE_EDITORS, STORE_STAFF};
+
+    // Promo routes
+    let promo_routes = PolicyRouter::new()
+        .route("/get-promo", Public, get(han_get_all_promos))
+        .route("/get-promo/{id_promo}", Roles(ANY_ROLE), get(han_get_promo_by_id))
+        .route("/create-promo", Roles(PROMO_EDITORS), post(han_create_promo))
+        .route("/clone-promo/{id_promo}", Roles(PROMO_EDITORS), post(han_clone_promo))
+        .route("/update-promo/{id_promo}", Roles(PROMO_EDITORS), put(han_update_promo))
+        .route("/transition-promo/{id_promo}", Roles(PROMO_EDITORS), post(han_transition_promo))
+        .route("/promo-transition/{id_promo}", Roles(ANY_ROLE), get(han_get_promo_transitions))
+        .route("/promo-lifecycle", Roles(ANY_ROLE), get(han_get_promos_by_lifecycle))
+        .route("/delete-promo/{id_promo}", Roles(PROMO_EDITORS), delete(han_delete_promo));
+
+    // PromoTenor routes
+    let promo_tenor_routes = PolicyRouter::new()
+        .route("/get-promo-tenor", Public, get(han_get_all_promo_tenors))
+        .route("/get-promo-tenor-by-store/{store_id}", Public, get(han_get_promo_tenors_by_store_id))
+        .route("/get-promo-tenor/{id}", Roles(ANY_ROLE), get(han_get_promo_tenor_by_id))
+        .route("/create-promo-tenor", Roles(PROMO_EDITORS), post(han_create_promo_tenor))
+        .route("/update-promo-tenor/{id}", Roles(PROMO_EDITORS), put(han_update_promo_tenor))
+        .route("/delete-promo-tenor/{id}", Roles(PROMO_EDITORS), delete(han_delete_promo_tenor))
+        .route("/schedule-promo-tenor-change", Roles(PROMO_EDITORS), post(han_schedule_promo_tenor_change))
+        .route("/promo-tenor-change", Roles(ANY_ROLE), get(han_get_promo_tenor_changes))
+        .route("/promo-tenor-change/{id}", Roles(ANY_ROLE), get(han_get_promo_tenor_change_by_id))
+        .route("/approve-promo-tenor-change/{id}", Roles(ADMIN_ONLY), post(han_approve_promo_tenor_change))
+        .route("/cancel-promo-tenor-change/{id}", Roles(PROMO_EDITORS), post(han_cancel_promo_tenor_change));
+
+    // Store routes
+    let store_routes = PolicyRouter::new()
+        .route("/get-store", Public, get(han_get_stores))
+        .route("/get-store/{route}", Public, get(han_get_store_by_route))
+        .route("/create-store", Roles(STORE_STAFF), post(han_create_store))
+        .route("/update-store/{route}", Roles(STORE_STAFF), put(han_update_store))
+        .route("/delete-store/{route}", Roles(ADMIN_ONLY), delete(han_delete_store));
+
+    // Store assignment routes (cakupan store untuk non-admin)
+    let store_assignment_routes = PolicyRouter::new()
+        .route("/get-my-store", Roles(ANY_ROLE), get(han_get_my_stores))
+        .route("/get-store-assignment", Roles(ADMIN_ONLY), get(han_get_store_assignments))
+        .route("/create-store-assignment", Roles(ADMIN_ONLY), post(han_create_store_assignment))
+        .route("/delete-store-assignment/{id}", Roles(ADMIN_ONLY), delete(han_delete_store_assignment));
+
+    // Service API key routes (klien mesin)
+    let api_key_routes = PolicyRouter::new()
+        .route("/get-api-key", Roles(ADMIN_ONLY), get(han_get_api_keys))
+        .route("/create-api-key", Roles(ADMIN_ONLY), post(han_create_api_key))
+        .route("/revoke-api-key/{id}", Roles(ADMIN_ONLY), post(han_revoke_api_key))
+        .route("/api-key-usage", Roles(ADMIN_ONLY), get(han_get_api_key_usage));
+
+    // Installment simulation & schedule routes
+    let installment_routes = PolicyRouter::new()
+        .route("/simulate-installment", Public, post(han_simulate_installment))
+        .route("/recommend-promo", Public, post(han_recommend_promo))
+        .route("/installment-schedule/{promo_tenor_id}", Roles(ANY_ROLE), get(han_get_installment_schedule));
+
+    // Voucher routes
+    let voucher_routes = PolicyRouter::new()
+        .route("/validate-voucher", Public, post(han_validate_voucher))
+        .route("/redeem-voucher", Roles(STORE_STAFF), post(han_redeem_voucher))
+        .route("/get-voucher-redemption/{voucher_code}", Roles(ANY_ROLE), get(han_get_voucher_redemptions));
+
+    // Public lead capture, dengan rate limit sendiri (limiter global melewati route publik)
+    let lead_rate_limit_requests = std::env::var("LEAD_RATE_LIMIT_REQUESTS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(5);
+    let lead_rate_limit_window = std::env::var("LEAD_RATE_LIMIT_WINDOW_SECONDS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(600);
+    let lead_rate_limiter = rate_limiter::RateLimiter::new(
+        lead_rate_limit_requests,
+        Duration::from_secs(lead_rate_limit_window),
+    );
+    info!(
+        "Lead rate limiter configured: {} requests per {} seconds",
+        lead_rate_limit_requests, lead_rate_limit_window
+    );
+    let public_lead = PolicyRouter::new()
+        .route("/submit-lead", Public, post(han_submit_lead))
+        .map_router(|router| {
+            router.route_layer(from_fn(move |req, next| {
+                let limiter = lead_rate_limiter.clone();
+                async move { limiter.enforce(req, next).await }
+            }))
+        });
+
+    // Lead routes (sales)
+    let lead_routes = PolicyRouter::new()
+        .route("/lead", Roles(STORE_STAFF), get(han_get_leads))
+        .route("/lead/{id}", Roles(STORE_STAFF), get(han_get_lead_by_id))
+        .route("/assign-lead/{id}", Roles(STORE_STAFF), post(han_assign_lead))
+        .route("/update-lead-status/{id}", Roles(STORE_STAFF), post(han_update_lead_status));
+
+    // PromoStore routes
+    let promo_store_routes = PolicyRouter::new()
+        .route("/get-promo-store", Roles(ANY_ROLE), get(han_get_promo_stores))
+        .route("/get-promo-store/{key}", Roles(ANY_ROLE), get(han_get_promo_store_by_key))
+        .route("/create-promo-store", Roles(PROMO_STORE_EDITORS), post(han_create_promo_store))
+        .route("/bulk-promo-store", Roles(PROMO_STORE_EDITORS), post(han_bulk_promo_store))
+        .route("/update-promo-store/{key}", Roles(PROMO_STORE_EDITORS), put(han_update_promo_store))
+        .route("/delete-promo-store/{key}", Roles(PROMO_STORE_EDITORS), delete(han_delete_promo_store));
+
+    // Company routes
+    let company_routes = PolicyRouter::new()
+        .route("/get-company", Roles(ANY_ROLE), get(han_get_companies))
+        .route("/get-company/{id}", Roles(ANY_ROLE), get(han_get_company_by_id))
+        .route("/get-company/{id}/stores", Roles(ANY_ROLE), get(han_get_company_stores))
+        .route("/get-company/{id}/promos", Roles(ANY_ROLE), get(han_get_company_promos))
+        .route("/create-company", Roles(ADMIN_ONLY), post(han_create_company))
+        .route("/update-company/{id}", Roles(ADMIN_ONLY), put(han_update_company))
+        .route("/delete-company/{id}", Roles(ADMIN_ONLY), delete(han_delete_company))
+        .route("/migrate-store-company", Roles(ADMIN_ONLY), post(han_migrate_store_companies));
+
+    // Promo conflict report
+    let conflict_routes = PolicyRouter::new()
+        .route("/get-promo-conflict", Roles(ANY_ROLE), get(han_get_promo_conflicts));
+
+    // Budget, financed transaction ledger & settlement routes
+    let budget_routes = PolicyRouter::new()
+        .route("/record-transaction", Roles(STORE_STAFF), post(han_record_transaction))
+        .route("/financed-transaction", Roles(ANY_ROLE), get(han_get_financed_transactions))
+        .route("/promo-budget/{id_promo}", Roles(ANY_ROLE), get(han_get_promo_budget))
+        .route("/settlement-report", Roles(ANY_ROLE), get(han_get_settlement_report));
+
+    // Version history routes
+    let version_routes = PolicyRouter::new()
+        .route("/entity-version/{entity_type}/{entity_id}", Roles(ANY_ROLE), get(han_get_entity_versions))
+        .route("/entity-version/{entity_type}/{entity_id}/diff", Roles(ANY_ROLE), get(han_diff_entity_versions))
+        .route("/entity-version/{entity_type}/{entity_id}/as-of", Roles(ANY_ROLE), get(han_get_entity_as_of))
+        .route("/store-terms-as-of/{store_id}", Roles(ANY_ROLE), get(han_get_store_terms_as_of));
+
+    // Health routes
+    let health_routes = PolicyRouter::new()
+        .route("/health", Public, get(health_check))
+        .route("/ready", Public, get(ready_check))
+        .route("/metrics", Public, get(metrics));
+
+    let (routes, route_policies) = PolicyRouter::new()
+        .merge(health_routes)
+        .merge(promo_routes)
+        .merge(promo_tenor_routes)
+        .merge(store_routes)
+        .merge(store_assignment_routes)
+        .merge(promo_store_routes)
+        .merge(installment_routes)
+        .merge(voucher_routes)
+        .merge(public_lead)
+        .merge(lead_routes)
+        .merge(company_routes)
+        .merge(conflict_routes)
+        .merge(budget_routes)
+        .merge(version_routes)
+        .merge(api_key_routes)
+        .into_parts();
+    state.api_key_service.set_routes(Arc::clone(&route_policies));
 
     let rate_limit_requests = std::env::var("RATE_LIMIT_REQUESTS")
         .ok()
@@ -187,21 +485,18 @@ async fn main() {
     let cors = create_cors_layer();
     info!("CORS configured with whitelist from environment");
 
-    let app = Router::new()
-        .route("/health", get(health_check))
-        .route("/ready", get(ready_check))
-        .route("/metrics", get(metrics))
-        .merge(public_promo)
-        .merge(public_promo_tenor)
-        .merge(public_store)
-        .merge(protected_routes)
-        .layer(cors)
-        .layer(tower_http::compression::CompressionLayer::new())
-        .layer(tower_http::limit::RequestBodyLimitLayer::new(1024 * 1024))
-        .layer(from_fn(move |req, next| {
+    // Route layer: yang terakhir dipasang berjalan pertama, jadi policy dipasang sebelum
+    // rate limiter dan auth membacanya
+    let app = routes
+        .route_layer(from_fn_with_state(state.clone(), auth))
+        .route_layer(from_fn(move |req, next| {
             let limiter = rate_limiter.clone();
             async move { limiter.middleware(req, next).await }
         }))
+        .route_layer(from_fn_with_state(route_policies, access_policy::attach))
+        .layer(cors)
+        .layer(tower_http::compression::CompressionLayer::new())
+        .layer(tower_http::limit::RequestBodyLimitLayer::new(1024 * 1024))
         .layer(from_fn(middleware::request_logging))
         .with_state(state);
 
@@ -1,26 +1,111 @@
 use axum::{
     body::Body,
-    extract::State,
-    http::{Request, header, Uri, Method},
+    extract::{FromRequestParts, MatchedPath, State},
+    http::{Request, header, request::Parts, Uri, Method},
     middleware::Next,
     response::Response,
 };
-use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::Instant;
 use tower_http::cors::{CorsLayer, Any};
 use tracing::{error, info, Span};
 use chrono::TimeZone;
 use uuid::Uuid;
 
-use crate::{app_state::AppState, constants::PUBLIC_ENDPOINTS, error::AppError};
+use crate::{access_policy::AccessPolicy, app_state::AppState, constants::API_KEY_HEADER, error::AppError};
+
+const DEFAULT_ROLE_CLAIM: &str = "app_metadata.role,app_metadata.roles";
+
+static ROLE_CLAIM_PATHS: OnceLock<Vec<Vec<String>>> = OnceLock::new();
+
+/// Path claim role dari env `JWT_ROLE_CLAIM`: dipisah koma, tiap path bertitik
+/// (mis. `app_metadata.role`, `user_role`). Nilai claim boleh string atau array string.
+fn role_claim_paths() -> &'static [Vec<String>] {
+    ROLE_CLAIM_PATHS.get_or_init(|| {
+        let raw = std::env::var("JWT_ROLE_CLAIM").unwrap_or_else(|_| DEFAULT_ROLE_CLAIM.to_string());
+        let paths: Vec<Vec<String>> = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| p.split('.').map(str::to_string).collect())
+            .collect();
+        info!("JWT role claim configured: {}", raw);
+        paths
+    })
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String, // Subject (user_id)
-    pub aud: String, // Audience
+    pub aud: serde_json::Value, // Audience (string atau array)
     pub exp: usize,  // Expiration time
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>, // Claim lain (app_metadata, role, ...)
+}
+
+impl Claims {
+    /// Role dari claim yang dikonfigurasi `JWT_ROLE_CLAIM`
+    pub fn roles(&self) -> Vec<String> {
+        let mut roles: Vec<String> = vec![];
+        for path in role_claim_paths() {
+            let Some((first, rest)) = path.split_first() else {
+                continue;
+            };
+            let value = rest
+                .iter()
+                .fold(self.extra.get(first), |value, key| value.and_then(|v| v.get(key)));
+            let found: Vec<&str> = match value {
+                Some(serde_json::Value::String(role)) => vec![role.as_str()],
+                Some(serde_json::Value::Array(items)) => items.iter().filter_map(|r| r.as_str()).collect(),
+                _ => vec![],
+            };
+            for role in found {
+                if !roles.iter().any(|r| r == role) {
+                    roles.push(role.to_string());
+                }
+            }
+        }
+        roles
+    }
+}
+
+/// Pemanggil request yang sudah lolos `auth`: user id untuk audit dan role untuk otorisasi.
+/// Di MODE=dev (auth dilewati) pemanggil dianggap `dev` dengan role admin.
+#[derive(Debug, Clone)]
+pub struct Actor {
+    pub user_id: String,
+    pub roles: Vec<String>,
+}
+
+impl Actor {
+    pub fn has_any_role(&self, roles: &[&str]) -> bool {
+        self.roles.iter().any(|r| roles.contains(&r.as_str()))
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Actor {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        // Dipasang `auth` untuk request dengan API key
+        if let Some(actor) = parts.extensions.get::<Actor>() {
+            return Ok(actor.clone());
+        }
+        if let Some(claims) = parts.extensions.get::<Arc<Claims>>() {
+            return Ok(Actor {
+                user_id: claims.sub.clone(),
+                roles: claims.roles(),
+            });
+        }
+        if std::env::var("MODE").is_ok_and(|mode| mode == "dev") {
+            return Ok(Actor {
+                user_id: "dev".to_string(),
+                roles: vec![crate::constants::ROLE_ADMIN.to_string()],
+            });
+        }
+        Err(AppError::Unauthorized)
+    }
 }
 
 // Middleware authentication: cek cache dulu (scoped read-lock), jika tidak ada -> decode JWT
@@ -36,10 +121,33 @@ pub async fn auth(
         return Ok(next.run(request).await);
     }
 
-    let path = request.uri().path();
-    
-    // Check if path is public endpoint
-    if PUBLIC_ENDPOINTS.iter().any(|&endpoint| path.starts_with(endpoint)) {
+    // Policy dideklarasikan per route di main.rs (lihat `access_policy`)
+    let policy = AccessPolicy::of(request.extensions());
+    if policy.is_public() {
+        return Ok(next.run(request).await);
+    }
+
+    // Klien mesin: API key menggantikan JWT, kuota per key dicek di sini
+    let api_key = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    if let Some(api_key) = api_key {
+        let path = request
+            .extensions()
+            .get::<MatchedPath>()
+            .map(|p| p.as_str().to_string());
+        let actor = state
+            .api_key_service
+            .ser_authenticate(&api_key, path.as_deref(), policy)
+            .await?;
+        info!(
+            api_key_validation_ms = start.elapsed().as_millis(),
+            actor = %actor.user_id,
+            "API key validated"
+        );
+        request.extensions_mut().insert(actor);
         return Ok(next.run(request).await);
     }
 
@@ -51,8 +159,14 @@ pub async fn auth(
         .ok_or(AppError::Unauthorized)?;
 
     let cache_start = Instant::now();
+    let key_set_version = state.jwt_verifier.key_set_version();
+    state
+        .cache_repository
+        .sync_claims_key_set_version(key_set_version)
+        .await;
     let cache_hit = if let Some(cached) = state.cache_repository.get_cached_claims(&token).await {
         if let Ok(claims) = serde_json::from_value::<Claims>(cached) {
+            authorize(policy, &claims)?;
             request.extensions_mut().insert(Arc::new(claims));
             let jwt_duration = start.elapsed().as_millis();
             info!(
@@ -67,30 +181,17 @@ pub async fn auth(
         false
     };
 
-    let jwt_secret = std::env::var("JWT_SECRET")
-        .map_err(|_| AppError::Internal("JWT_SECRET not set".to_string()))?;
-
-    let mut validation = Validation::new(Algorithm::HS256);
-    validation.set_audience(&["authenticated"]);
-
-    let decode_start = Instant::now();
-    let decoded = decode::<Claims>(
-        &token,
-        &DecodingKey::from_secret(jwt_secret.as_ref()),
-        &validation,
-    )
-    .map_err(|e| {
-        error!("JWT Validation Error: {:?}", e);
-        AppError::Unauthorized
-    })?;
-
-    let token_data = decoded.claims;
+    let token_data = state.jwt_verifier.verify(&token).await?;
+    authorize(policy, &token_data)?;
 
-    if let Ok(json_claims) = serde_json::to_value(&token_data) {
+    // Jangan cache claims yang diverifikasi dengan JWKS yang sudah diganti di tengah request
+    if state.jwt_verifier.key_set_version() == key_set_version
+        && let Ok(json_claims) = serde_json::to_value(&token_data)
+    {
         let expiry = chrono::Utc
             .timestamp_opt(token_data.exp as i64, 0)
             .single()
-            .unwrap_or_else(|| chrono::Utc::now());
+            .unwrap_or_else(chrono::Utc::now);
         state
             .cache_repository
             .save_token_claims(token.clone(), Some(json_claims), expiry)
@@ -109,6 +210,20 @@ pub async fn auth(
     Ok(next.run(request).await)
 }
 
+/// Cek role untuk route `AccessPolicy::Roles`; token valid tapi role kurang = 403
+fn authorize(policy: AccessPolicy, claims: &Claims) -> Result<(), AppError> {
+    let AccessPolicy::Roles(allowed) = policy else {
+        return Ok(());
+    };
+    if claims.roles().iter().any(|r| allowed.contains(&r.as_str())) {
+        return Ok(());
+    }
+    Err(AppError::Forbidden(format!(
+        "This endpoint requires one of the roles: {}",
+        allowed.join(", ")
+    )))
+}
+
 pub async fn request_logging(
     request: Request<Body>,
     next: Next,
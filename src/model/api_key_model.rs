@@ -0,0 +1,127 @@
+use crate::business_time::parse_business_datetime;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Prefix key mentah, memudahkan mengenali key yang bocor di log atau repo
+pub const API_KEY_PREFIX: &str = "mpk_";
+
+/// API key untuk klien mesin (batch job, integrasi partner). Hanya hash SHA-256 yang
+/// disimpan; key mentah ditampilkan sekali saat dibuat.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    /// Beberapa karakter awal key mentah untuk identifikasi di UI admin
+    pub key_prefix: String,
+    #[serde(skip_serializing, default)]
+    pub key_hash: String,
+    /// Role yang dibawa key, dicocokkan dengan `AccessPolicy::Roles` seperti role JWT
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Template path route yang boleh dipanggil (mis. `/record-transaction`); kosong = tidak dibatasi route
+    #[serde(default)]
+    pub routes: Vec<String>,
+    /// Kuota per key per window `API_KEY_RATE_LIMIT_WINDOW_SECONDS`; `None` = `API_KEY_RATE_LIMIT_REQUESTS`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit_requests: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revoked_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revoked_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+}
+
+impl ApiKey {
+    /// Expiry yang tidak bisa di-parse dianggap sudah lewat (fail closed)
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+            .as_deref()
+            .map(|raw| parse_business_datetime(raw, true).unwrap_or(DateTime::<Utc>::MIN_UTC))
+    }
+
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at().is_none_or(|expiry| now < expiry)
+    }
+
+    /// User id pemanggil untuk audit dan store assignment (`/create-store-assignment`)
+    pub fn actor_id(&self) -> String {
+        format!("api-key:{}", self.id)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateApiKeyPayload {
+    pub name: String,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    #[serde(default)]
+    pub routes: Vec<String>,
+    #[serde(default)]
+    pub rate_limit_requests: Option<i32>,
+    /// Format tanggal promo (RFC3339 / `YYYY-MM-DD` = akhir hari bisnis); tanpa expiry key berlaku sampai dicabut
+    #[serde(default)]
+    pub expires_at: Option<String>,
+}
+
+/// Baris yang dikirim ke tabel `api_key`
+#[derive(Serialize, Debug, Clone)]
+pub struct ApiKeyRow {
+    pub name: String,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub roles: Vec<String>,
+    pub routes: Vec<String>,
+    pub rate_limit_requests: Option<i32>,
+    pub expires_at: Option<String>,
+    pub created_by: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RevokeApiKeyRow {
+    pub revoked_at: String,
+    pub revoked_by: String,
+}
+
+/// Response `/create-api-key`: satu-satunya kesempatan melihat `key`
+#[derive(Serialize, Debug, Clone)]
+pub struct IssuedApiKey {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+/// Pemakaian per key sejak proses start, untuk `/api-key-usage`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ApiKeyUsage {
+    pub id: Uuid,
+    pub requests: u64,
+    pub rejected: u64,
+    pub rate_limited: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<String>,
+}
+
+/// Total counter semua key untuk `/metrics` (publik); rincian per key hanya di `/api-key-usage`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ApiKeyMetrics {
+    /// Jumlah key yang tercatat memakai API sejak proses start
+    pub keys: usize,
+    pub requests: u64,
+    pub rejected: u64,
+    pub rate_limited: u64,
+}
+
+impl ApiKeyMetrics {
+    pub fn add(&mut self, usage: &ApiKeyUsage) {
+        self.keys += 1;
+        self.requests += usage.requests;
+        self.rejected += usage.rejected;
+        self.rate_limited += usage.rate_limited;
+    }
+}
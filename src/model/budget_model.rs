@@ -0,0 +1,339 @@
+use crate::model::installment_model::TenorInstallment;
+use crate::model::money_model::{Decimal, Money, Rate, validate_non_negative};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Batas anggaran promo (total) atau PromoStore (per store); `None` = tanpa batas
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct BudgetCaps {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_subsidy: Option<Money>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_transactions: Option<i32>,
+}
+
+impl BudgetCaps {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(max) = self.max_subsidy {
+            validate_non_negative("budget_max_subsidy", max)?;
+        }
+        if let Some(max) = self.max_transactions
+            && max < 0
+        {
+            return Err(format!("budget_max_transactions must not be negative, got: {}", max));
+        }
+        Ok(())
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_subsidy.is_none() && self.max_transactions.is_none()
+    }
+
+    /// Kuota habis: tidak ada sisa transaksi atau sisa subsidi
+    pub fn is_reached(&self, usage: &BudgetUsage) -> bool {
+        self.max_transactions.is_some_and(|max| usage.transactions >= max.max(0) as i64)
+            || self.max_subsidy.is_some_and(|max| max <= usage.subsidy)
+    }
+
+    /// Apakah satu transaksi lagi dengan `subsidy` rupiah masih muat
+    pub fn check(&self, usage: &BudgetUsage, subsidy: i64) -> Result<(), String> {
+        if let Some(max) = self.max_transactions
+            && usage.transactions + 1 > max.max(0) as i64
+        {
+            return Err(format!("transaction quota of {} reached", max));
+        }
+        if let Some(max) = self.max_subsidy
+            && usage.subsidy.checked_add(subsidy).is_none_or(|total| max < total)
+        {
+            return Err(format!(
+                "subsidy budget of {} exceeded (used {}, transaction needs {})",
+                max, usage.subsidy, subsidy
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn remaining(&self, usage: &BudgetUsage) -> (Option<Money>, Option<i64>) {
+        (
+            self.max_subsidy
+                .map(|max| {
+                    Money::from_int(usage.subsidy)
+                        .and_then(|used| max.checked_sub(used))
+                        .filter(|left| !left.is_negative())
+                        .unwrap_or(Decimal::ZERO)
+                }),
+            self.max_transactions
+                .map(|max| (max.max(0) as i64 - usage.transactions).max(0)),
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct BudgetUsage {
+    pub transactions: i64,
+    pub subsidy: i64,
+}
+
+/// Pemakaian anggaran satu promo, total dan per store, dihitung fungsi database
+/// `financed_transaction_usage` (agregat SQL, tidak terpotong batas baris PostgREST)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PromoBudgetUsage {
+    #[serde(flatten)]
+    pub usage: BudgetUsage,
+    #[serde(default)]
+    pub stores: Vec<StoreBudgetUsage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoreBudgetUsage {
+    pub store_id: Uuid,
+    #[serde(flatten)]
+    pub usage: BudgetUsage,
+}
+
+impl PromoBudgetUsage {
+    pub fn of_store(&self, store_id: Uuid) -> BudgetUsage {
+        self.stores
+            .iter()
+            .find(|s| s.store_id == store_id)
+            .map(|s| s.usage)
+            .unwrap_or_default()
+    }
+}
+
+/// Parameter fungsi database `financed_transaction_usage`
+#[derive(Serialize, Debug, Clone)]
+pub struct BudgetUsageParams {
+    pub p_promo_id: Uuid,
+}
+
+/// Satu transaksi pembiayaan yang sudah terjadi, beserta snapshot term yang dipakai.
+/// Menjadi ledger konsumsi anggaran promo dan sumber laporan settlement.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FinancedTransaction {
+    pub id: Uuid,
+    pub transaction_ref: String,
+    pub promo_id: Uuid,
+    pub promo_tenor_id: Uuid,
+    pub store_id: Uuid,
+    pub tenor: i32,
+    pub amount: i64,
+    pub discount: i64,
+    pub financed_amount: i64,
+    pub admin_fee: i64,
+    pub total_interest: i64,
+    pub subsidy: i64,
+    pub free_installment_value: i64,
+    pub interest_rate: Rate,
+    pub subsidi_rate: Rate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+}
+
+/// `transaction_ref` adalah kunci idempotensi dari sistem pemanggil
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordTransactionPayload {
+    pub transaction_ref: String,
+    pub store_route: String,
+    pub promo_tenor_id: Uuid,
+    pub amount: i64,
+}
+
+/// Baris yang dikirim ke tabel `financed_transaction`
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateFinancedTransactionRow {
+    pub transaction_ref: String,
+    pub promo_id: Uuid,
+    pub promo_tenor_id: Uuid,
+    pub store_id: Uuid,
+    pub tenor: i32,
+    pub amount: i64,
+    pub discount: i64,
+    pub financed_amount: i64,
+    pub admin_fee: i64,
+    pub total_interest: i64,
+    pub subsidy: i64,
+    pub free_installment_value: i64,
+    pub interest_rate: Rate,
+    pub subsidi_rate: Rate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+}
+
+impl CreateFinancedTransactionRow {
+    pub fn from_installment(
+        transaction_ref: String,
+        store_id: Uuid,
+        subsidi_rate: Rate,
+        installment: &TenorInstallment,
+        amount: i64,
+        created_by: Option<String>,
+    ) -> Self {
+        Self {
+            transaction_ref,
+            promo_id: installment.promo_id,
+            promo_tenor_id: installment.promo_tenor_id,
+            store_id,
+            tenor: installment.tenor,
+            amount,
+            discount: installment.discount,
+            financed_amount: installment.financed_amount,
+            admin_fee: installment.admin_fee,
+            total_interest: installment.total_interest,
+            subsidy: installment.subsidy,
+            free_installment_value: installment
+                .disclosure
+                .as_ref()
+                .map(|d| d.free_installment_value)
+                .unwrap_or_default(),
+            interest_rate: installment.interest_rate,
+            subsidi_rate,
+            created_by,
+        }
+    }
+}
+
+/// Parameter fungsi database `record_financed_transaction` (cek anggaran + insert dalam satu transaksi)
+#[derive(Serialize, Debug, Clone)]
+pub struct RecordTransactionParams {
+    pub p_row: CreateFinancedTransactionRow,
+    pub p_promo_max_subsidy: Option<Money>,
+    pub p_promo_max_transactions: Option<i32>,
+    pub p_store_max_subsidy: Option<Money>,
+    pub p_store_max_transactions: Option<i32>,
+}
+
+impl RecordTransactionParams {
+    pub fn new(row: CreateFinancedTransactionRow, promo_caps: BudgetCaps, store_caps: Option<BudgetCaps>) -> Self {
+        let store_caps = store_caps.unwrap_or_default();
+        Self {
+            p_row: row,
+            p_promo_max_subsidy: promo_caps.max_subsidy,
+            p_promo_max_transactions: promo_caps.max_transactions,
+            p_store_max_subsidy: store_caps.max_subsidy,
+            p_store_max_transactions: store_caps.max_transactions,
+        }
+    }
+}
+
+/// Hasil fungsi database `record_financed_transaction`; pemakaian dihitung database
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum RecordOutcome {
+    /// `promo_usage`/`store_usage` sudah termasuk transaksi ini
+    Recorded {
+        transaction: FinancedTransaction,
+        promo_usage: BudgetUsage,
+        store_usage: BudgetUsage,
+    },
+    /// `transaction_ref` sudah tercatat (request paralel dengan ref yang sama)
+    Replayed { transaction: FinancedTransaction },
+    PromoBudgetExceeded { promo_usage: BudgetUsage },
+    StoreBudgetExceeded { store_usage: BudgetUsage },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordTransactionResult {
+    pub transaction: FinancedTransaction,
+    pub replayed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BudgetStatus {
+    pub caps: BudgetCaps,
+    pub used: BudgetUsage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_subsidy: Option<Money>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_transactions: Option<i64>,
+    pub exhausted: bool,
+}
+
+impl BudgetStatus {
+    pub fn new(caps: BudgetCaps, used: BudgetUsage) -> Self {
+        let (remaining_subsidy, remaining_transactions) = caps.remaining(&used);
+        Self {
+            caps,
+            used,
+            remaining_subsidy,
+            remaining_transactions,
+            exhausted: caps.is_reached(&used),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoreBudgetStatus {
+    pub store_id: Uuid,
+    #[serde(flatten)]
+    pub budget: BudgetStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PromoBudgetStatus {
+    pub promo_id: Uuid,
+    #[serde(flatten)]
+    pub budget: BudgetStatus,
+    pub stores: Vec<StoreBudgetStatus>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caps(max_subsidy: Option<&str>, max_transactions: Option<i32>) -> BudgetCaps {
+        BudgetCaps {
+            max_subsidy: max_subsidy.map(|raw| raw.parse().unwrap()),
+            max_transactions,
+        }
+    }
+
+    fn usage(transactions: i64, subsidy: i64) -> BudgetUsage {
+        BudgetUsage { transactions, subsidy }
+    }
+
+    #[test]
+    fn unlimited_caps_always_pass() {
+        let caps = BudgetCaps::default();
+        assert!(caps.is_unlimited());
+        assert!(caps.check(&usage(1_000, i64::MAX - 1), 1).is_ok());
+        assert!(!caps.is_reached(&usage(1_000, i64::MAX)));
+    }
+
+    #[test]
+    fn subsidy_cap_hit_exactly_is_allowed_then_reached() {
+        let caps = caps(Some("1000000"), None);
+        assert!(caps.check(&usage(3, 900_000), 100_000).is_ok());
+        assert!(caps.check(&usage(3, 900_000), 100_001).is_err());
+        assert!(!caps.is_reached(&usage(3, 999_999)));
+        assert!(caps.is_reached(&usage(4, 1_000_000)));
+        assert_eq!(caps.remaining(&usage(4, 1_000_000)).0, Some(Decimal::ZERO));
+    }
+
+    #[test]
+    fn transaction_cap_hit_exactly_is_allowed_then_reached() {
+        let caps = caps(None, Some(5));
+        assert!(caps.check(&usage(4, 0), 0).is_ok());
+        assert!(caps.is_reached(&usage(5, 0)));
+        assert!(caps.check(&usage(5, 0), 0).is_err());
+        assert_eq!(caps.remaining(&usage(7, 0)).1, Some(0));
+    }
+
+    #[test]
+    fn zero_caps_reject_everything() {
+        let caps = caps(Some("0"), Some(0));
+        assert!(caps.is_reached(&usage(0, 0)));
+        assert!(caps.check(&usage(0, 0), 0).is_err());
+    }
+
+    #[test]
+    fn subsidy_overflow_is_rejected() {
+        let caps = caps(Some("900000000000000"), None);
+        assert!(caps.check(&usage(1, i64::MAX), 1).is_err());
+        assert!(caps.check(&usage(1, 0), i64::MAX).is_err());
+        // Pemakaian di luar jangkauan Money tidak membuat sisa negatif
+        assert_eq!(caps.remaining(&usage(1, i64::MAX)).0, Some(Decimal::ZERO));
+    }
+}
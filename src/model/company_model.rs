@@ -0,0 +1,88 @@
+use crate::model::promo_model::Promo;
+use crate::model::store_model::{Store, normalize_company_name};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// `normalized_name` unik per company, dipakai untuk mencocokkan `Store.company` lama
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Company {
+    pub id: Uuid,
+    pub name: String,
+    pub normalized_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+impl Company {
+    /// Store milik company: via `company_id`, atau nama yang cocok untuk store yang belum dimigrasi
+    pub fn owns_store(&self, store: &Store) -> bool {
+        match store.company_id {
+            Some(company_id) => company_id == self.id,
+            None => normalize_company_name(&store.company) == self.normalized_name,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateCompanyPayload {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdateCompanyPayload {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Baris yang benar-benar dikirim ke tabel `company`
+#[derive(Serialize, Debug, Clone)]
+pub struct CompanyRow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normalized_name: Option<String>,
+}
+
+impl CompanyRow {
+    pub fn from_name(name: &str) -> Self {
+        let name = name.trim().to_string();
+        Self {
+            normalized_name: Some(normalize_company_name(&name)),
+            name: Some(name),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CompanyPromo {
+    #[serde(flatten)]
+    pub promo: Promo,
+    pub store_ids: Vec<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MigrateStoreCompanyPayload {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoreCompanyLink {
+    pub store_id: Uuid,
+    pub store_name: String,
+    pub company: String,
+    /// `None` saat dry-run untuk company yang belum ada
+    pub company_id: Option<Uuid>,
+    pub normalized_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoreCompanyMigrationResult {
+    pub dry_run: bool,
+    pub companies_created: Vec<String>,
+    pub stores_linked: Vec<StoreCompanyLink>,
+    pub stores_already_linked: usize,
+    pub stores_skipped: Vec<Uuid>,
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Cara menangani konflik promo saat create/update (env `PROMO_CONFLICT_MODE`)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictMode {
+    Off,
+    #[default]
+    Warn,
+    Reject,
+}
+
+impl ConflictMode {
+    pub fn from_env() -> Self {
+        match std::env::var("PROMO_CONFLICT_MODE")
+            .unwrap_or_default()
+            .to_lowercase()
+            .as_str()
+        {
+            "off" => ConflictMode::Off,
+            "reject" => ConflictMode::Reject,
+            _ => ConflictMode::Warn,
+        }
+    }
+}
+
+/// Dua promo aktif di store yang sama dengan window tanggal beririsan
+/// dan menawarkan tenor (bulan) yang sama
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PromoConflict {
+    pub store_id: Uuid,
+    pub promo_id: Uuid,
+    pub title_promo: String,
+    pub conflicting_promo_id: Uuid,
+    pub conflicting_title_promo: String,
+    pub tenors: Vec<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overlap_start: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overlap_end: Option<String>,
+}
+
+/// Response create/update dalam mode warn: entity yang disimpan plus daftar konflik
+/// (field `conflicts` hanya muncul jika ada konflik)
+#[derive(Serialize, Debug, Clone)]
+pub struct WithConflictWarnings<T> {
+    #[serde(flatten)]
+    pub data: T,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<PromoConflict>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoreConflictReport {
+    pub store_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_route: Option<String>,
+    pub conflicts: Vec<PromoConflict>,
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+/// `?cascade=true` ikut menghapus/melepas dependent; default restrict (409 jika masih ada dependent)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DeleteQuery {
+    #[serde(default)]
+    pub cascade: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletableEntity {
+    Promo,
+    PromoTenor,
+    PromoTenorChange,
+    Store,
+    PromoStore,
+    StoreAssignment,
+}
+
+impl DeletableEntity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DeletableEntity::Promo => "promo",
+            DeletableEntity::PromoTenor => "promo_tenor",
+            DeletableEntity::PromoTenorChange => "promo_tenor_change",
+            DeletableEntity::Store => "store",
+            DeletableEntity::PromoStore => "promo_store",
+            DeletableEntity::StoreAssignment => "store_assignment",
+        }
+    }
+}
+
+/// Apa yang terjadi pada dependent saat cascade
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DependentAction {
+    /// Baris ikut dihapus
+    Delete,
+    /// Referensi ke entity yang dihapus dilepas (mis. tenor dari `tenor_ids` PromoStore)
+    Detach,
+    /// Perubahan terjadwal yang masih `pending` dibatalkan
+    Cancel,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Dependent {
+    pub entity_type: DeletableEntity,
+    pub id: String,
+    pub description: String,
+    pub action: DependentAction,
+}
+
+impl Dependent {
+    pub fn new(
+        entity_type: DeletableEntity,
+        id: impl ToString,
+        description: impl Into<String>,
+        action: DependentAction,
+    ) -> Self {
+        Self {
+            entity_type,
+            id: id.to_string(),
+            description: description.into(),
+            action,
+        }
+    }
+}
+
+/// Body 409 untuk mode restrict: pesan ringkas plus daftar dependent terstruktur
+/// supaya klien tidak perlu mem-parse pesan
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DependentConflict {
+    pub error: String,
+    pub dependents: Vec<Dependent>,
+}
+
+impl DependentConflict {
+    pub fn new(entity: DeletableEntity, label: &str, dependents: Vec<Dependent>) -> Self {
+        Self {
+            error: format!(
+                "{} '{}' still has {} dependent(s). Retry with ?cascade=true to remove them as well",
+                entity.as_str(),
+                label,
+                dependents.len()
+            ),
+            dependents,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeleteReport {
+    pub entity_type: DeletableEntity,
+    pub id: String,
+    pub cascade: bool,
+    /// Dependent yang ikut dihapus / dilepas / dibatalkan (kosong jika tidak ada)
+    pub dependents: Vec<Dependent>,
+}
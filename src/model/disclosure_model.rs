@@ -0,0 +1,32 @@
+use crate::model::money_model::Rate;
+use serde::{Deserialize, Serialize};
+
+/// Angka biaya kredit yang wajib ditampilkan ke konsumen untuk satu tenor.
+/// Semua nominal dalam rupiah untuk `reference_amount`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TenorDisclosure {
+    pub reference_amount: i64,
+    pub financed_amount: i64,
+    pub monthly_installment: i64,
+    pub paid_installments: i32,
+    pub free_installment: i32,
+    /// Bunga sebelum subsidi merchant
+    pub total_interest: i64,
+    /// Bunga yang ditanggung customer (setelah subsidi)
+    pub customer_interest: i64,
+    pub total_fees: i64,
+    pub merchant_subsidy: i64,
+    /// Nilai cicilan gratis yang ditanggung merchant
+    pub free_installment_value: i64,
+    pub total_payable: i64,
+    /// Biaya kredit customer: `total_payable - financed_amount` (bisa negatif saat promo)
+    pub customer_cost: i64,
+    /// Biaya kredit yang ditanggung merchant: subsidi + cicilan gratis
+    pub merchant_cost: i64,
+    /// Bunga nominal tahunan (% , bulanan x 12) dari arus kas aktual
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annual_percentage_rate: Option<Rate>,
+    /// Bunga efektif tahunan (%, dimajemukkan bulanan)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_annual_rate: Option<Rate>,
+}
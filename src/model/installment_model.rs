@@ -0,0 +1,163 @@
+use crate::model::disclosure_model::TenorDisclosure;
+use crate::model::money_model::Rate;
+use crate::model::promo_tenor_model::TenorTermSources;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SimulateInstallmentPayload {
+    pub store_route: String,
+    pub amount: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenor: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TenorInstallment {
+    pub promo_id: Uuid,
+    pub title_promo: String,
+    pub promo_tenor_id: Uuid,
+    pub tenor: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voucher_code: Option<String>,
+    pub interest_rate: Rate,
+    pub discount: i64,
+    pub financed_amount: i64,
+    pub admin_fee: i64,
+    pub total_interest: i64,
+    pub subsidy: i64,
+    pub monthly_installment: i64,
+    pub free_installment: i32,
+    pub total_payable: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub term_sources: Option<TenorTermSources>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disclosure: Option<TenorDisclosure>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstallmentSimulation {
+    pub store_id: Uuid,
+    pub store_route: String,
+    pub amount: i64,
+    pub installments: Vec<TenorInstallment>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendSortBy {
+    #[default]
+    TotalCost,
+    MonthlyPayment,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecommendPromoPayload {
+    pub store_route: String,
+    pub amount: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_tenor: Option<i32>,
+    #[serde(default)]
+    pub sort_by: RecommendSortBy,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PromoRecommendation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<usize>,
+    pub eligible: bool,
+    pub reasons: Vec<String>,
+    pub installment: TenorInstallment,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PromoRecommendationResult {
+    pub store_id: Uuid,
+    pub store_route: String,
+    pub amount: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_tenor: Option<i32>,
+    pub sort_by: RecommendSortBy,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recommended: Option<PromoRecommendation>,
+    pub candidates: Vec<PromoRecommendation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstallmentScheduleQuery {
+    pub amount: i64,
+    /// Pakai term efektif store (override PromoStore) jika diisi
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_id: Option<Uuid>,
+    /// Satuan pembulatan rupiah, override `SCHEDULE_ROUNDING_UNIT`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rounding: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SchedulePeriod {
+    pub period: i32,
+    pub principal: i64,
+    pub interest: i64,
+    pub fee: i64,
+    pub installment: i64,
+    /// Cicilan gratis: `installment` dibebaskan, hanya `fee` yang dibayar
+    pub is_free: bool,
+    pub amount_due: i64,
+    pub remaining_principal: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ScheduleTotals {
+    pub principal: i64,
+    pub interest: i64,
+    pub fees: i64,
+    pub waived: i64,
+    pub amount_due: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstallmentSchedule {
+    pub promo_tenor_id: Uuid,
+    pub promo_id: Uuid,
+    pub title_promo: String,
+    pub tenor: i32,
+    pub amount: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_id: Option<Uuid>,
+    pub rounding_unit: i64,
+    pub summary: TenorInstallment,
+    pub periods: Vec<SchedulePeriod>,
+    pub totals: ScheduleTotals,
+}
+
+impl InstallmentSchedule {
+    /// Satu baris per periode, diakhiri baris total
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "period,principal,interest,fee,installment,is_free,amount_due,remaining_principal\n",
+        );
+        for p in &self.periods {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                p.period,
+                p.principal,
+                p.interest,
+                p.fee,
+                p.installment,
+                p.is_free,
+                p.amount_due,
+                p.remaining_principal
+            ));
+        }
+        csv.push_str(&format!(
+            "total,{},{},{},{},,{},\n",
+            self.totals.principal,
+            self.totals.interest,
+            self.totals.fees,
+            self.totals.principal + self.totals.interest,
+            self.totals.amount_due
+        ));
+        csv
+    }
+}
@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LeadStatus {
+    #[default]
+    New,
+    Contacted,
+    Qualified,
+    Converted,
+    Lost,
+}
+
+impl LeadStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LeadStatus::New => "new",
+            LeadStatus::Contacted => "contacted",
+            LeadStatus::Qualified => "qualified",
+            LeadStatus::Converted => "converted",
+            LeadStatus::Lost => "lost",
+        }
+    }
+
+    /// `new` → `contacted` → `qualified` → `converted`; `lost` dari status mana pun
+    /// yang belum final. `converted` dan `lost` adalah status final.
+    pub fn can_transition_to(self, to: LeadStatus) -> bool {
+        matches!(
+            (self, to),
+            (LeadStatus::New, LeadStatus::Contacted)
+                | (LeadStatus::Contacted, LeadStatus::Qualified)
+                | (LeadStatus::Qualified, LeadStatus::Converted)
+                | (LeadStatus::New | LeadStatus::Contacted | LeadStatus::Qualified, LeadStatus::Lost)
+        )
+    }
+
+    pub fn is_final(self) -> bool {
+        matches!(self, LeadStatus::Converted | LeadStatus::Lost)
+    }
+}
+
+/// Minat customer terhadap satu promo tenor di store, beserta snapshot simulasi saat dikirim
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Lead {
+    pub id: Uuid,
+    pub store_id: Uuid,
+    pub promo_id: Uuid,
+    pub promo_tenor_id: Uuid,
+    pub tenor: i32,
+    pub amount: i64,
+    pub monthly_installment: i64,
+    pub customer_name: String,
+    pub phone: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub status: LeadStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assigned_to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assigned_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+/// Body publik `/submit-lead`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SubmitLeadPayload {
+    pub store_route: String,
+    pub promo_tenor_id: Uuid,
+    pub amount: i64,
+    pub customer_name: String,
+    pub phone: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Baris yang dikirim ke tabel `lead`
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateLeadRow {
+    pub store_id: Uuid,
+    pub promo_id: Uuid,
+    pub promo_tenor_id: Uuid,
+    pub tenor: i32,
+    pub amount: i64,
+    pub monthly_installment: i64,
+    pub customer_name: String,
+    pub phone: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub status: LeadStatus,
+}
+
+/// Response publik: tanpa data kontak dan data internal
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LeadReceipt {
+    pub id: Uuid,
+    pub status: LeadStatus,
+    pub promo_tenor_id: Uuid,
+    pub tenor: i32,
+    pub amount: i64,
+    pub monthly_installment: i64,
+}
+
+impl From<&Lead> for LeadReceipt {
+    fn from(lead: &Lead) -> Self {
+        Self {
+            id: lead.id,
+            status: lead.status,
+            promo_tenor_id: lead.promo_tenor_id,
+            tenor: lead.tenor,
+            amount: lead.amount,
+            monthly_installment: lead.monthly_installment,
+        }
+    }
+}
+
+/// `assigned_to: null` melepas assignment
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AssignLeadPayload {
+    pub assigned_to: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdateLeadStatusPayload {
+    pub status: LeadStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Update parsial; `assigned_to`/`assigned_at` dikirim sebagai `null` saat assignment dilepas
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct UpdateLeadRow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<LeadStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assigned_to: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assigned_at: Option<Option<String>>,
+    pub updated_by: String,
+    pub updated_at: String,
+}
@@ -1,4 +1,18 @@
+pub mod api_key_model;
+pub mod budget_model;
+pub mod company_model;
+pub mod conflict_model;
+pub mod delete_model;
+pub mod disclosure_model;
+pub mod installment_model;
+pub mod lead_model;
+pub mod money_model;
 pub mod promo_model;
 pub mod promo_store_model;
 pub mod promo_tenor_model;
+pub mod settlement_model;
+pub mod store_assignment_model;
 pub mod store_model;
+pub mod tenor_change_model;
+pub mod version_model;
+pub mod voucher_model;
@@ -0,0 +1,358 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Angka fixed-point dengan 4 digit desimal, disimpan sebagai i64 (nilai x 10.000).
+///
+/// Dipakai untuk nominal rupiah (`Money`) maupun persentase (`Rate`) supaya
+/// perhitungan tidak bergantung pada pembulatan `f64`. Semua aritmetika checked
+/// (`None` saat overflow atau dibagi nol), tidak ada saturasi diam-diam.
+/// Serialisasi JSON: bilangan bulat sebagai angka, nilai pecahan sebagai string
+/// (`"1.75"`) supaya tidak lewat `f64`; deserialisasi menerima angka maupun string.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal(i64);
+
+/// Nominal rupiah
+pub type Money = Decimal;
+/// Persentase 0–100 (mis. `1.75` = 1,75%)
+pub type Rate = Decimal;
+
+impl Decimal {
+    pub const DECIMALS: u32 = 4;
+    pub const SCALE: i64 = 10_000;
+    pub const ZERO: Decimal = Decimal(0);
+    pub const HUNDRED: Decimal = Decimal(100 * Self::SCALE);
+
+    /// `None` jika `value` x 10.000 tidak muat di i64
+    pub const fn from_int(value: i64) -> Option<Self> {
+        match value.checked_mul(Self::SCALE) {
+            Some(raw) => Some(Decimal(raw)),
+            None => None,
+        }
+    }
+
+    /// Nilai mentah (x 10.000)
+    pub const fn raw(self) -> i64 {
+        self.0
+    }
+
+    /// Untuk hasil iteratif (mis. APR); dibulatkan ke 4 desimal. `None` jika tidak finite
+    /// atau di luar jangkauan
+    pub fn from_f64(value: f64) -> Option<Self> {
+        let scaled = (value * Self::SCALE as f64).round();
+        // i64::MAX as f64 dibulatkan ke 2^63, jadi batas atas eksklusif
+        if scaled.is_finite() && scaled >= i64::MIN as f64 && scaled < i64::MAX as f64 {
+            Some(Decimal(scaled as i64))
+        } else {
+            None
+        }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / Self::SCALE as f64
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    pub fn checked_add(self, rhs: Decimal) -> Option<Decimal> {
+        self.0.checked_add(rhs.0).map(Decimal)
+    }
+
+    pub fn checked_sub(self, rhs: Decimal) -> Option<Decimal> {
+        self.0.checked_sub(rhs.0).map(Decimal)
+    }
+
+    /// Hasil dibulatkan ke 4 desimal (setengah menjauhi nol)
+    pub fn checked_mul(self, rhs: Decimal) -> Option<Decimal> {
+        Self::from_i128(div_round(self.0 as i128 * rhs.0 as i128, Self::SCALE as i128))
+    }
+
+    /// Hasil dibulatkan ke 4 desimal (setengah menjauhi nol); `None` jika `rhs` nol
+    pub fn checked_div(self, rhs: Decimal) -> Option<Decimal> {
+        if rhs.0 == 0 {
+            return None;
+        }
+        Self::from_i128(div_round(self.0 as i128 * Self::SCALE as i128, rhs.0 as i128))
+    }
+
+    /// `percent`% dari nilai ini
+    pub fn percent(self, percent: Rate) -> Option<Decimal> {
+        self.checked_mul(percent)?.checked_div(Self::HUNDRED)
+    }
+
+    /// Bulatkan ke rupiah terdekat (setengah menjauhi nol)
+    pub fn round_to_int(self) -> i64 {
+        div_round(self.0 as i128, Self::SCALE as i128) as i64
+    }
+
+    /// Bulatkan ke atas ke rupiah
+    pub fn ceil_to_int(self) -> i64 {
+        self.0.div_euclid(Self::SCALE) + i64::from(self.0.rem_euclid(Self::SCALE) != 0)
+    }
+
+    /// Bulatkan ke `decimals` digit desimal (maks 4); `None` jika pembulatan keluar jangkauan
+    pub fn round_dp(self, decimals: u32) -> Option<Decimal> {
+        let step = 10_i128.pow(Self::DECIMALS - decimals.min(Self::DECIMALS));
+        Self::from_i128(div_round(self.0 as i128, step) * step)
+    }
+
+    fn from_i128(value: i128) -> Option<Self> {
+        i64::try_from(value).ok().map(Decimal)
+    }
+}
+
+/// Pembagian bulat dengan pembulatan setengah menjauhi nol
+fn div_round(numerator: i128, denominator: i128) -> i128 {
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if remainder.abs() * 2 >= denominator.abs() {
+        quotient + numerator.signum() * denominator.signum()
+    } else {
+        quotient
+    }
+}
+
+/// Selalu muat: i32 x 10.000 < i64::MAX
+impl From<i32> for Decimal {
+    fn from(value: i32) -> Self {
+        Decimal(value as i64 * Self::SCALE)
+    }
+}
+
+// Dibandingkan di i128 supaya bilangan bulat di luar jangkauan Decimal tetap benar
+impl PartialEq<i64> for Decimal {
+    fn eq(&self, other: &i64) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd<i64> for Decimal {
+    fn partial_cmp(&self, other: &i64) -> Option<Ordering> {
+        Some((self.0 as i128).cmp(&(*other as i128 * Self::SCALE as i128)))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        let scale = Self::SCALE as u64;
+        let (whole, fraction) = (abs / scale, abs % scale);
+        if fraction == 0 {
+            return write!(f, "{}{}", sign, whole);
+        }
+        let fraction = format!("{:04}", fraction);
+        write!(f, "{}{}.{}", sign, whole, fraction.trim_end_matches('0'))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseDecimalError(String);
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid decimal: {}", self.0)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    /// Parse tepat tanpa lewat `f64`; lebih dari 4 desimal ditolak
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let s = raw.trim();
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let valid = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !valid(whole) || !valid(fraction) {
+            return Err(ParseDecimalError(raw.to_string()));
+        }
+        if fraction.len() > Self::DECIMALS as usize {
+            return Err(ParseDecimalError(format!(
+                "{} (more than {} decimal places)",
+                raw,
+                Self::DECIMALS
+            )));
+        }
+
+        let whole: i128 = if whole.is_empty() {
+            0
+        } else {
+            whole.parse().map_err(|_| ParseDecimalError(raw.to_string()))?
+        };
+        let fraction: i128 = format!("{:0<4}", fraction)
+            .parse()
+            .map_err(|_| ParseDecimalError(raw.to_string()))?;
+
+        let value = whole * Self::SCALE as i128 + fraction;
+        let value = if negative { -value } else { value };
+        i64::try_from(value)
+            .map(Decimal)
+            .map_err(|_| ParseDecimalError(format!("{} (out of range)", raw)))
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0 % Self::SCALE == 0 {
+            serializer.serialize_i64(self.0 / Self::SCALE)
+        } else {
+            // Pecahan sebagai teks desimal tepat; f64 kehilangan digit di nominal besar
+            serializer.collect_str(self)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DecimalVisitor;
+
+        impl Visitor<'_> for DecimalVisitor {
+            type Value = Decimal;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a number or numeric string with at most 4 decimal places")
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Decimal, E> {
+                v.checked_mul(Decimal::SCALE)
+                    .map(Decimal)
+                    .ok_or_else(|| E::custom(format!("{} is out of range", v)))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Decimal, E> {
+                i64::try_from(v)
+                    .map_err(|_| E::custom(format!("{} is out of range", v)))
+                    .and_then(|v| self.visit_i64(v))
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Decimal, E> {
+                // Lewat teks terpendek f64 supaya 0.1 menjadi tepat 0.1
+                if !v.is_finite() {
+                    return Err(E::custom(format!("{} is not a finite number", v)));
+                }
+                v.to_string().parse().map_err(E::custom)
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Decimal, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+}
+
+/// Validasi nilai sesuai tipe promo: PERCENT harus 0–100, FIX tidak negatif
+pub fn validate_percent(field: &str, value: Decimal) -> Result<(), String> {
+    if value.is_negative() || value > Decimal::HUNDRED {
+        return Err(format!("{} must be between 0 and 100 percent, got: {}", field, value));
+    }
+    Ok(())
+}
+
+pub fn validate_non_negative(field: &str, value: Decimal) -> Result<(), String> {
+    if value.is_negative() {
+        return Err(format!("{} must not be negative, got: {}", field, value));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(raw: &str) -> Decimal {
+        raw.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_and_display_round_trip() {
+        for raw in ["0", "1", "-1", "1.75", "0.0001", "-0.5", "123456789.1234", "922337203685477.5807"] {
+            assert_eq!(dec(raw).to_string(), raw);
+        }
+        assert_eq!(dec("+2.50").to_string(), "2.5");
+        assert_eq!(dec(".5").to_string(), "0.5");
+    }
+
+    #[test]
+    fn parse_rejects_invalid_input() {
+        for raw in ["", ".", "abc", "1.2.3", "1e3", "1.23456", "922337203685477.5808", "--1"] {
+            assert!(raw.parse::<Decimal>().is_err(), "{} should be rejected", raw);
+        }
+    }
+
+    #[test]
+    fn serde_round_trip_is_exact() {
+        for raw in ["0", "42", "-7", "1.75", "0.0001", "922337203685477.5807", "-922337203685477.5808"] {
+            let value = dec(raw);
+            let json = serde_json::to_string(&value).unwrap();
+            assert_eq!(serde_json::from_str::<Decimal>(&json).unwrap(), value, "{}", json);
+        }
+    }
+
+    #[test]
+    fn serializes_integers_as_numbers_and_fractions_as_strings() {
+        assert_eq!(serde_json::to_string(&dec("1500000")).unwrap(), "1500000");
+        assert_eq!(serde_json::to_string(&dec("1.75")).unwrap(), "\"1.75\"");
+        assert_eq!(
+            serde_json::to_string(&dec("900719925474099.1234")).unwrap(),
+            "\"900719925474099.1234\""
+        );
+    }
+
+    #[test]
+    fn deserializes_numbers_and_strings() {
+        assert_eq!(serde_json::from_str::<Decimal>("0.1").unwrap(), dec("0.1"));
+        assert_eq!(serde_json::from_str::<Decimal>("\"0.1\"").unwrap(), dec("0.1"));
+        assert_eq!(serde_json::from_str::<Decimal>("12").unwrap(), dec("12"));
+        assert!(serde_json::from_str::<Decimal>("1e300").is_err());
+        assert!(serde_json::from_str::<Decimal>("922337203685478").is_err());
+    }
+
+    #[test]
+    fn arithmetic_is_checked() {
+        let max = Decimal(i64::MAX);
+        assert_eq!(dec("1.5").checked_add(dec("2.25")), Some(dec("3.75")));
+        assert_eq!(max.checked_add(dec("0.0001")), None);
+        assert_eq!(Decimal(i64::MIN).checked_sub(dec("0.0001")), None);
+        assert_eq!(dec("1.5").checked_mul(dec("2")), Some(dec("3")));
+        assert_eq!(max.checked_mul(dec("2")), None);
+        assert_eq!(dec("1").checked_div(dec("3")), Some(dec("0.3333")));
+        assert_eq!(dec("2").checked_div(dec("3")), Some(dec("0.6667")));
+        assert_eq!(dec("1").checked_div(Decimal::ZERO), None);
+        assert_eq!(Decimal::from_int(i64::MAX), None);
+        assert_eq!(Decimal::from_f64(f64::NAN), None);
+        assert_eq!(Decimal::from_f64(1e300), None);
+    }
+
+    #[test]
+    fn percent_and_rounding() {
+        assert_eq!(dec("1000000").percent(dec("1.75")), Some(dec("17500")));
+        assert_eq!(dec("2.5").round_to_int(), 3);
+        assert_eq!(dec("-2.5").round_to_int(), -3);
+        assert_eq!(dec("2.0001").ceil_to_int(), 3);
+        assert_eq!(dec("-2.5").ceil_to_int(), -2);
+        assert_eq!(dec("1.2345").round_dp(2), Some(dec("1.23")));
+        assert_eq!(dec("1.235").round_dp(2), Some(dec("1.24")));
+    }
+
+    #[test]
+    fn compares_with_integers_beyond_range() {
+        assert!(dec("5") == 5);
+        assert!(Decimal(i64::MAX) < i64::MAX);
+        assert!(dec("0.5") > 0);
+    }
+}
@@ -1,39 +1,116 @@
+use crate::business_time::parse_business_datetime;
+use crate::constants::{ROLE_ADMIN, ROLE_PROMO_MANAGER};
+use crate::model::budget_model::BudgetCaps;
+use crate::model::money_model::{Money, Rate};
+use crate::model::promo_store_model::{PromoStore, deserialize_explicit_null};
+use crate::model::promo_tenor_model::PromoTenor;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum AdminPromoType {
     FIX,
     PERCENT,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum DiscountPromoType {
     FIX,
     PERCENT,
 }
 
+/// Status promo berdasarkan window `start_date_promo`/`end_date_promo`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PromoStatus {
+    Upcoming,
+    #[default]
+    Active,
+    Expired,
+}
+
+/// Tahap persetujuan promo; hanya `Published` yang tampil di endpoint publik
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PromoLifecycle {
+    #[default]
+    Draft,
+    PendingReview,
+    Approved,
+    Published,
+    Archived,
+}
+
+impl PromoLifecycle {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PromoLifecycle::Draft => "draft",
+            PromoLifecycle::PendingReview => "pending_review",
+            PromoLifecycle::Approved => "approved",
+            PromoLifecycle::Published => "published",
+            PromoLifecycle::Archived => "archived",
+        }
+    }
+
+    /// Role yang boleh memindahkan promo dari state ini ke `to`; `None` = transisi tidak dikenal
+    pub fn transition_roles(self, to: PromoLifecycle) -> Option<&'static [&'static str]> {
+        use PromoLifecycle::*;
+        const EDITORS: &[&str] = &[ROLE_ADMIN, ROLE_PROMO_MANAGER];
+        const APPROVERS: &[&str] = &[ROLE_ADMIN];
+        match (self, to) {
+            (Draft, PendingReview) => Some(EDITORS),
+            (PendingReview, Approved) => Some(APPROVERS),
+            // Reject review / tarik kembali sebelum publish
+            (PendingReview, Draft) => Some(APPROVERS),
+            (Approved, Draft) => Some(EDITORS),
+            (Approved, Published) => Some(EDITORS),
+            (Published, Archived) => Some(EDITORS),
+            // Unpublish untuk diedit ulang atau restore promo arsip
+            (Published, Draft) | (Archived, Draft) => Some(APPROVERS),
+            _ => None,
+        }
+    }
+}
+
+/// Baris lama (sebelum kolom `lifecycle` ada) dianggap sudah published
+fn legacy_lifecycle() -> PromoLifecycle {
+    PromoLifecycle::Published
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CreatePromoPayload {
     pub title_promo: String,
     pub admin_promo_type: AdminPromoType,
-    pub interest_rate: f64,
+    pub interest_rate: Rate,
     pub discount_type: DiscountPromoType,
     pub is_active: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub start_date_promo: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub end_date_promo: Option<String>,
+    /// Batas total subsidi (rupiah) seluruh store
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_max_subsidy: Option<Money>,
+    /// Batas jumlah transaksi seluruh store
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_max_transactions: Option<i32>,
+    /// Promo baru selalu dibuat sebagai draft
+    #[serde(default, skip_deserializing)]
+    pub lifecycle: PromoLifecycle,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct UpdatePromoPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title_promo: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub admin_promo_type: Option<AdminPromoType>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub interest_rate: Option<f64>,
+    pub interest_rate: Option<Rate>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub discount_type: Option<DiscountPromoType>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -42,6 +119,120 @@ pub struct UpdatePromoPayload {
     pub start_date_promo: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub end_date_promo: Option<String>,
+    /// Field tidak dikirim = tidak diubah, `null` = hapus batas
+    #[serde(
+        default,
+        deserialize_with = "deserialize_explicit_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub budget_max_subsidy: Option<Option<Money>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_explicit_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub budget_max_transactions: Option<Option<i32>>,
+    /// Diisi oleh budget service saat kuota habis / tersedia lagi
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub budget_exhausted: Option<bool>,
+    /// Hanya diisi lewat `/transition-promo`, tidak dari body update
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub lifecycle: Option<PromoLifecycle>,
+}
+
+impl UpdatePromoPayload {
+    pub fn touches_budget(&self) -> bool {
+        self.budget_max_subsidy.is_some() || self.budget_max_transactions.is_some()
+    }
+
+    /// Hanya mengubah batas anggaran; boleh dilakukan di luar tahap draft
+    pub fn is_budget_only(&self) -> bool {
+        self.touches_budget()
+            && self.title_promo.is_none()
+            && self.admin_promo_type.is_none()
+            && self.interest_rate.is_none()
+            && self.discount_type.is_none()
+            && self.is_active.is_none()
+            && self.start_date_promo.is_none()
+            && self.end_date_promo.is_none()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransitionPromoPayload {
+    pub to: PromoLifecycle,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Jejak audit perpindahan lifecycle; `from_state` kosong untuk pembuatan promo
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PromoTransition {
+    pub id: Uuid,
+    pub promo_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_state: Option<PromoLifecycle>,
+    pub to_state: PromoLifecycle,
+    pub actor: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreatePromoTransitionPayload {
+    pub promo_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_state: Option<PromoLifecycle>,
+    pub to_state: PromoLifecycle,
+    pub actor: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PromoTransitionResult {
+    pub promo: Promo,
+    pub transition: PromoTransition,
+}
+
+/// Perlakuan voucher_code saat clone: kode voucher harus unik, jadi kode lama tidak dipakai ulang
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VoucherCloneStrategy {
+    /// Kode baru dengan prefix kode lama, mis. `CICIL0-7KX2QD`
+    #[default]
+    Regenerate,
+    Clear,
+}
+
+/// Field yang tidak dikirim disalin dari promo sumber
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ClonePromoPayload {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_promo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interest_rate: Option<Rate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date_promo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date_promo: Option<String>,
+    /// Ikut salin link PromoStore (tenor_ids & override di-remap ke tenor baru)
+    #[serde(default)]
+    pub include_store_links: bool,
+    #[serde(default)]
+    pub voucher_strategy: VoucherCloneStrategy,
+}
+
+/// Promo beserta seluruh tenor dan link store-nya
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PromoGraph {
+    pub promo: Promo,
+    pub tenors: Vec<PromoTenor>,
+    pub store_links: Vec<PromoStore>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -49,7 +240,7 @@ pub struct Promo {
     pub id_promo: Uuid,
     pub title_promo: String,
     pub admin_promo_type: AdminPromoType,
-    pub interest_rate: f64,
+    pub interest_rate: Rate,
     pub discount_type: DiscountPromoType,
     pub is_active: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -60,6 +251,148 @@ pub struct Promo {
     pub created_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub updated_at: Option<String>,
+    #[serde(default = "legacy_lifecycle")]
+    pub lifecycle: PromoLifecycle,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_max_subsidy: Option<Money>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_max_transactions: Option<i32>,
+    /// Kuota anggaran habis; promo tidak tampil di listing/simulasi
+    #[serde(default)]
+    pub budget_exhausted: bool,
+    // Dihitung dari window tanggal, bukan kolom database
+    #[serde(default, skip_deserializing)]
+    pub status: PromoStatus,
+}
+
+impl Promo {
+    /// Tanggal yang tidak bisa di-parse dianggap di luar window (fail closed): start di masa depan
+    pub fn start_at(&self) -> Option<DateTime<Utc>> {
+        self.start_date_promo.as_deref().map(|raw| {
+            parse_business_datetime(raw, false).unwrap_or_else(|| {
+                warn!("start_date_promo '{}' promo {} tidak valid; promo dianggap belum mulai", raw, self.id_promo);
+                DateTime::<Utc>::MAX_UTC
+            })
+        })
+    }
+
+    /// Tanggal yang tidak bisa di-parse dianggap di luar window (fail closed): sudah berakhir
+    pub fn end_at(&self) -> Option<DateTime<Utc>> {
+        self.end_date_promo.as_deref().map(|raw| {
+            parse_business_datetime(raw, true).unwrap_or_else(|| {
+                warn!("end_date_promo '{}' promo {} tidak valid; promo dianggap sudah berakhir", raw, self.id_promo);
+                DateTime::<Utc>::MIN_UTC
+            })
+        })
+    }
+
+    /// Ada tanggal window yang tidak bisa di-parse (data lama; write path menolaknya)
+    pub fn has_invalid_window(&self) -> bool {
+        self.start_date_promo
+            .as_deref()
+            .is_some_and(|raw| parse_business_datetime(raw, false).is_none())
+            || self
+                .end_date_promo
+                .as_deref()
+                .is_some_and(|raw| parse_business_datetime(raw, true).is_none())
+    }
+
+    pub fn status_at(&self, now: DateTime<Utc>) -> PromoStatus {
+        if self.start_at().is_some_and(|start| now < start) {
+            PromoStatus::Upcoming
+        } else if self.end_at().is_some_and(|end| now > end) {
+            PromoStatus::Expired
+        } else {
+            PromoStatus::Active
+        }
+    }
+
+    /// Hitung ulang `status` berdasarkan waktu sekarang
+    pub fn with_current_status(self) -> Self {
+        self.with_status_at(Utc::now())
+    }
+
+    /// Hitung ulang `status` berdasarkan waktu `at` (mis. untuk snapshot riwayat)
+    pub fn with_status_at(mut self, at: DateTime<Utc>) -> Self {
+        self.status = self.status_at(at);
+        self
+    }
+
+    /// Promo tampil di listing publik hanya jika published, aktif, di dalam window tanggal
+    /// dan anggarannya belum habis
+    pub fn is_live(&self) -> bool {
+        self.is_published()
+            && self.is_active
+            && self.status == PromoStatus::Active
+            && !self.budget_exhausted
+    }
+
+    pub fn budget_caps(&self) -> BudgetCaps {
+        BudgetCaps {
+            max_subsidy: self.budget_max_subsidy,
+            max_transactions: self.budget_max_transactions,
+        }
+    }
+
+    pub fn is_published(&self) -> bool {
+        self.lifecycle == PromoLifecycle::Published
+    }
+
+    /// Term promo (termasuk tenor dan override store) hanya boleh diubah saat draft supaya
+    /// setiap perubahan melewati review lagi
+    pub fn ensure_draft(&self) -> Result<(), String> {
+        if self.lifecycle == PromoLifecycle::Draft {
+            Ok(())
+        } else {
+            Err(format!(
+                "promo is {}; move it back to draft before editing",
+                self.lifecycle.as_str()
+            ))
+        }
+    }
+
+    /// Salinan promo dengan perubahan dari payload update diterapkan (tanpa menyimpan)
+    pub fn with_update(&self, payload: &UpdatePromoPayload) -> Promo {
+        let mut promo = self.clone();
+        if let Some(title_promo) = &payload.title_promo {
+            promo.title_promo = title_promo.clone();
+        }
+        if let Some(admin_promo_type) = &payload.admin_promo_type {
+            promo.admin_promo_type = admin_promo_type.clone();
+        }
+        if let Some(interest_rate) = payload.interest_rate {
+            promo.interest_rate = interest_rate;
+        }
+        if let Some(discount_type) = &payload.discount_type {
+            promo.discount_type = discount_type.clone();
+        }
+        if let Some(is_active) = payload.is_active {
+            promo.is_active = is_active;
+        }
+        if payload.start_date_promo.is_some() {
+            promo.start_date_promo = payload.start_date_promo.clone();
+        }
+        if payload.end_date_promo.is_some() {
+            promo.end_date_promo = payload.end_date_promo.clone();
+        }
+        if let Some(max_subsidy) = payload.budget_max_subsidy {
+            promo.budget_max_subsidy = max_subsidy;
+        }
+        if let Some(max_transactions) = payload.budget_max_transactions {
+            promo.budget_max_transactions = max_transactions;
+        }
+        promo.with_current_status()
+    }
+
+    /// Batas waktu berikutnya (mulai atau berakhir) setelah `now`
+    pub fn next_boundary_after(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        [self.start_at(), self.end_at()]
+            .into_iter()
+            .flatten()
+            .filter(|boundary| *boundary > now)
+            .min()
+    }
 }
 
 pub type PromoResponse = Promo;
+
@@ -1,6 +1,45 @@
-use serde::{Deserialize, Serialize};
+use crate::model::budget_model::BudgetCaps;
+use crate::model::conflict_model::PromoConflict;
+use crate::model::money_model::{Decimal, Money, Rate};
+use crate::model::promo_tenor_model::{
+    PromoTenor, StorePromoTenor, TenorTermSources, TenorTerms, TermSource,
+};
+use crate::model::store_model::StoreType;
+use serde::{Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
 
+/// Override term tenor untuk satu store; field `None` memakai nilai PromoTenor
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TenorOverride {
+    pub tenor_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subsidi: Option<Rate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub admin: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discount: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_discount: Option<Money>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_transaction: Option<Money>,
+}
+
+impl From<&TenorOverride> for TenorTerms {
+    fn from(o: &TenorOverride) -> Self {
+        Self {
+            min_transaction: o.min_transaction,
+            subsidi: o.subsidi,
+            admin: o.admin,
+            discount: o.discount,
+            max_discount: o.max_discount,
+        }
+    }
+}
+
+/// `tenor_ids` menentukan tenor yang berlaku untuk store ini:
+/// - `None` (null) : semua tenor milik promo
+/// - `Some([])`    : tidak ada tenor
+/// - `Some([..])`  : hanya tenor yang disebutkan (harus milik promo yang sama)
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PromoStore {
     pub id: Uuid,
@@ -9,21 +48,243 @@ pub struct PromoStore {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tenor_ids: Option<Vec<Uuid>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenor_overrides: Option<Vec<TenorOverride>>,
+    /// Batas subsidi (rupiah) untuk store ini saja
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_max_subsidy: Option<Money>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_max_transactions: Option<i32>,
+    /// Kuota store habis; tenor promo ini tidak tampil di store tersebut
+    #[serde(default)]
+    pub budget_exhausted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub updated_at: Option<String>,
 }
 
+impl PromoStore {
+    pub fn budget_caps(&self) -> BudgetCaps {
+        BudgetCaps {
+            max_subsidy: self.budget_max_subsidy,
+            max_transactions: self.budget_max_transactions,
+        }
+    }
+
+    pub fn includes_tenor(&self, tenor_id: Uuid) -> bool {
+        self.tenor_ids
+            .as_ref()
+            .is_none_or(|ids| ids.contains(&tenor_id))
+    }
+
+    /// Relasi menyebut tenor ini secara eksplisit (di `tenor_ids` atau `tenor_overrides`)
+    pub fn references_tenor(&self, tenor_id: Uuid) -> bool {
+        self.tenor_ids.iter().flatten().any(|id| *id == tenor_id)
+            || self.tenor_overrides.iter().flatten().any(|o| o.tenor_id == tenor_id)
+    }
+
+    /// Update yang melepas tenor dari relasi ini. `tenor_ids` yang menjadi kosong tetap
+    /// dikirim kosong (store tidak lagi menawarkan tenor apa pun dari promo ini),
+    /// sedangkan override yang habis dikembalikan ke `null`.
+    pub fn detach_tenor(&self, tenor_id: Uuid) -> UpdatePromoStorePayload {
+        let tenor_ids = self
+            .tenor_ids
+            .as_ref()
+            .filter(|ids| ids.contains(&tenor_id))
+            .map(|ids| Some(ids.iter().copied().filter(|id| *id != tenor_id).collect()));
+        let tenor_overrides = self
+            .tenor_overrides
+            .as_ref()
+            .filter(|overrides| overrides.iter().any(|o| o.tenor_id == tenor_id))
+            .map(|overrides| {
+                let remaining: Vec<TenorOverride> =
+                    overrides.iter().filter(|o| o.tenor_id != tenor_id).cloned().collect();
+                (!remaining.is_empty()).then_some(remaining)
+            });
+        UpdatePromoStorePayload {
+            tenor_ids,
+            tenor_overrides,
+            ..Default::default()
+        }
+    }
+
+    /// Gabungkan PromoTenor dengan override store ini
+    pub fn effective_tenor(&self, tenor: &PromoTenor) -> StorePromoTenor {
+        let mut promo_tenor = tenor.clone();
+        let mut term_sources = TenorTermSources::default();
+
+        let tenor_override = self
+            .tenor_overrides
+            .iter()
+            .flatten()
+            .find(|o| o.tenor_id == tenor.id);
+
+        if let Some(o) = tenor_override {
+            if let Some(subsidi) = o.subsidi {
+                promo_tenor.subsidi = subsidi;
+                term_sources.subsidi = TermSource::StoreOverride;
+            }
+            if let Some(admin) = o.admin {
+                promo_tenor.admin = admin;
+                term_sources.admin = TermSource::StoreOverride;
+            }
+            if let Some(discount) = o.discount {
+                promo_tenor.discount = discount;
+                term_sources.discount = TermSource::StoreOverride;
+            }
+            if let Some(max_discount) = o.max_discount {
+                promo_tenor.max_discount = max_discount;
+                term_sources.max_discount = TermSource::StoreOverride;
+            }
+            if let Some(min_transaction) = o.min_transaction {
+                promo_tenor.min_transaction = min_transaction;
+                term_sources.min_transaction = TermSource::StoreOverride;
+            }
+        }
+
+        StorePromoTenor {
+            promo_tenor,
+            store_id: self.store_id,
+            term_sources,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CreatePromoStorePayload {
     pub promo_id: Uuid,
     pub store_id: Uuid,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tenor_ids: Option<Vec<Uuid>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenor_overrides: Option<Vec<TenorOverride>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_max_subsidy: Option<Money>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_max_transactions: Option<i32>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct UpdatePromoStorePayload {
+    /// Field tidak dikirim = tidak diubah, `null` = kembali ke semua tenor
+    #[serde(
+        default,
+        deserialize_with = "deserialize_explicit_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub tenor_ids: Option<Option<Vec<Uuid>>>,
+    /// Field tidak dikirim = tidak diubah, `null` = hapus semua override
+    #[serde(
+        default,
+        deserialize_with = "deserialize_explicit_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub tenor_overrides: Option<Option<Vec<TenorOverride>>>,
+    /// Field tidak dikirim = tidak diubah, `null` = hapus batas
+    #[serde(
+        default,
+        deserialize_with = "deserialize_explicit_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub budget_max_subsidy: Option<Option<Money>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_explicit_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub budget_max_transactions: Option<Option<i32>>,
+    /// Diisi oleh budget service saat kuota store habis / tersedia lagi
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub budget_exhausted: Option<bool>,
+}
+
+impl UpdatePromoStorePayload {
+    pub fn touches_budget(&self) -> bool {
+        self.budget_max_subsidy.is_some() || self.budget_max_transactions.is_some()
+    }
+}
+
+/// Pemilih store untuk bulk assignment
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StoreSelector {
+    /// Semua store dengan `company` yang sama (tanpa beda huruf besar/kecil & spasi)
+    Company { company: String },
+    /// Semua store yang sudah ter-link ke company (lihat `/migrate-store-company`)
+    CompanyId { company_id: Uuid },
+    /// Semua store dengan `store_type` KA atau NKA
+    StoreType { store_type: StoreType },
+    /// Semua store yang punya tag ini (tanpa beda huruf besar/kecil)
+    Tag { tag: String },
+    /// Daftar store eksplisit
+    Stores { store_ids: Vec<Uuid> },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkAction {
+    #[default]
+    Assign,
+    Remove,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BulkPromoStorePayload {
+    pub promo_id: Uuid,
+    pub selector: StoreSelector,
+    #[serde(default)]
+    pub action: BulkAction,
+    /// Hanya dipakai saat `assign`, berlaku untuk semua link baru
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tenor_ids: Option<Vec<Uuid>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenor_overrides: Option<Vec<TenorOverride>>,
+    /// `true` = preview saja, tidak ada perubahan di database
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkStoreOutcome {
+    Created,
+    Removed,
+    WouldCreate,
+    WouldRemove,
+    AlreadyLinked,
+    NotLinked,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BulkStoreResult {
+    pub store_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_name: Option<String>,
+    pub outcome: BulkStoreOutcome,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<PromoConflict>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BulkPromoStoreResult {
+    pub promo_id: Uuid,
+    pub action: BulkAction,
+    pub dry_run: bool,
+    pub matched_stores: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+    pub failed: usize,
+    pub results: Vec<BulkStoreResult>,
+}
+
+/// Bedakan field yang tidak dikirim (`None`) dengan `null` eksplisit (`Some(None)`)
+pub fn deserialize_explicit_null<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
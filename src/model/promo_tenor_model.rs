@@ -1,3 +1,9 @@
+use crate::business_time::parse_business_datetime;
+use crate::model::disclosure_model::TenorDisclosure;
+use crate::model::money_model::{Decimal, Money, Rate, validate_non_negative, validate_percent};
+use crate::model::promo_model::{AdminPromoType, DiscountPromoType};
+use crate::model::promo_store_model::deserialize_explicit_null;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -5,13 +11,21 @@ use uuid::Uuid;
 pub struct CreatePromoTenorPayload {
     pub promo_id: Uuid,
     pub tenor: i32,
-    pub min_transaction: i32,
-    pub subsidi: f64,
-    pub admin: f64,
-    pub discount: i64,
-    pub max_discount: i64,
+    pub min_transaction: Money,
+    pub subsidi: Rate,
+    /// Rupiah jika `admin_promo_type` FIX, persen jika PERCENT
+    pub admin: Decimal,
+    /// Rupiah jika `discount_type` FIX, persen jika PERCENT
+    pub discount: Decimal,
+    pub max_discount: Money,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub voucher_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voucher_max_usage: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voucher_max_usage_per_store: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voucher_expires_at: Option<String>,
     pub free_installment: i32,
     pub is_available: bool,
 }
@@ -23,17 +37,40 @@ pub struct UpdatePromoTenorPayload {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tenor: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub min_transaction: Option<i32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub subsidi: Option<f64>,
+    pub min_transaction: Option<Money>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub admin: Option<f64>,
+    pub subsidi: Option<Rate>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub discount: Option<i64>,
+    pub admin: Option<Decimal>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub max_discount: Option<i64>,
+    pub discount: Option<Decimal>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub voucher_code: Option<String>,
+    pub max_discount: Option<Money>,
+    /// Field voucher: tidak dikirim = tidak diubah, `null` = dikosongkan
+    #[serde(
+        default,
+        deserialize_with = "deserialize_explicit_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub voucher_code: Option<Option<String>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_explicit_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub voucher_max_usage: Option<Option<i32>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_explicit_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub voucher_max_usage_per_store: Option<Option<i32>>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_explicit_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub voucher_expires_at: Option<Option<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub free_installment: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -45,19 +82,176 @@ pub struct PromoTenor {
     pub id: Uuid,
     pub promo_id: Uuid,
     pub tenor: i32,
-    pub min_transaction: i32,
-    pub subsidi: f64,
-    pub admin: f64,
-    pub discount: i64,
-    pub max_discount: i64,
+    pub min_transaction: Money,
+    pub subsidi: Rate,
+    /// Rupiah jika `admin_promo_type` FIX, persen jika PERCENT
+    pub admin: Decimal,
+    /// Rupiah jika `discount_type` FIX, persen jika PERCENT
+    pub discount: Decimal,
+    pub max_discount: Money,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub voucher_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voucher_max_usage: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voucher_max_usage_per_store: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voucher_expires_at: Option<String>,
     pub free_installment: i32,
     pub is_available: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub updated_at: Option<String>,
+    // Dihitung dari term promo/tenor saat response, bukan kolom database
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub disclosure: Option<TenorDisclosure>,
+}
+
+impl PromoTenor {
+    /// Expiry voucher yang tidak bisa di-parse dianggap sudah lewat (fail closed)
+    pub fn voucher_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.voucher_expires_at
+            .as_deref()
+            .map(|raw| parse_business_datetime(raw, true).unwrap_or(DateTime::<Utc>::MIN_UTC))
+    }
+
+    /// Payload yang mengembalikan field yang disentuh `changes` ke nilai tenor ini
+    pub fn revert_of(&self, changes: &UpdatePromoTenorPayload) -> UpdatePromoTenorPayload {
+        UpdatePromoTenorPayload {
+            promo_id: changes.promo_id.map(|_| self.promo_id),
+            tenor: changes.tenor.map(|_| self.tenor),
+            min_transaction: changes.min_transaction.map(|_| self.min_transaction),
+            subsidi: changes.subsidi.map(|_| self.subsidi),
+            admin: changes.admin.map(|_| self.admin),
+            discount: changes.discount.map(|_| self.discount),
+            max_discount: changes.max_discount.map(|_| self.max_discount),
+            voucher_code: changes.voucher_code.as_ref().map(|_| self.voucher_code.clone()),
+            voucher_max_usage: changes.voucher_max_usage.map(|_| self.voucher_max_usage),
+            voucher_max_usage_per_store: changes.voucher_max_usage_per_store.map(|_| self.voucher_max_usage_per_store),
+            voucher_expires_at: changes.voucher_expires_at.as_ref().map(|_| self.voucher_expires_at.clone()),
+            free_installment: changes.free_installment.map(|_| self.free_installment),
+            is_available: changes.is_available.map(|_| self.is_available),
+        }
+    }
 }
 
 pub type PromoTenorResponse = PromoTenor;
+
+/// Asal nilai term efektif untuk tenor di suatu store
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TermSource {
+    #[default]
+    PromoTenor,
+    StoreOverride,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct TenorTermSources {
+    pub subsidi: TermSource,
+    pub admin: TermSource,
+    pub discount: TermSource,
+    pub max_discount: TermSource,
+    pub min_transaction: TermSource,
+}
+
+/// PromoTenor dengan nilai efektif setelah override dari link PromoStore
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StorePromoTenor {
+    #[serde(flatten)]
+    pub promo_tenor: PromoTenor,
+    pub store_id: Uuid,
+    pub term_sources: TenorTermSources,
+}
+
+/// Nilai term yang bergantung pada tipe promo; `None` = tidak diubah / tidak divalidasi
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TenorTerms {
+    pub min_transaction: Option<Money>,
+    pub subsidi: Option<Rate>,
+    pub admin: Option<Decimal>,
+    pub discount: Option<Decimal>,
+    pub max_discount: Option<Money>,
+}
+
+impl TenorTerms {
+    /// PERCENT harus 0–100, FIX dan nominal rupiah tidak boleh negatif
+    pub fn validate(
+        &self,
+        admin_promo_type: &AdminPromoType,
+        discount_type: &DiscountPromoType,
+    ) -> Result<(), String> {
+        if let Some(v) = self.min_transaction {
+            validate_non_negative("min_transaction", v)?;
+        }
+        if let Some(v) = self.max_discount {
+            validate_non_negative("max_discount", v)?;
+        }
+        if let Some(v) = self.subsidi {
+            validate_percent("subsidi", v)?;
+        }
+        if let Some(v) = self.admin {
+            match admin_promo_type {
+                AdminPromoType::FIX => validate_non_negative("admin", v)?,
+                AdminPromoType::PERCENT => validate_percent("admin", v)?,
+            }
+        }
+        if let Some(v) = self.discount {
+            match discount_type {
+                DiscountPromoType::FIX => validate_non_negative("discount", v)?,
+                DiscountPromoType::PERCENT => validate_percent("discount", v)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TenorTerms {
+    /// Isi field kosong dari `fallback` (mis. nilai tenor saat ini)
+    pub fn or(self, fallback: TenorTerms) -> TenorTerms {
+        Self {
+            min_transaction: self.min_transaction.or(fallback.min_transaction),
+            subsidi: self.subsidi.or(fallback.subsidi),
+            admin: self.admin.or(fallback.admin),
+            discount: self.discount.or(fallback.discount),
+            max_discount: self.max_discount.or(fallback.max_discount),
+        }
+    }
+}
+
+impl From<&PromoTenor> for TenorTerms {
+    fn from(t: &PromoTenor) -> Self {
+        Self {
+            min_transaction: Some(t.min_transaction),
+            subsidi: Some(t.subsidi),
+            admin: Some(t.admin),
+            discount: Some(t.discount),
+            max_discount: Some(t.max_discount),
+        }
+    }
+}
+
+impl From<&CreatePromoTenorPayload> for TenorTerms {
+    fn from(p: &CreatePromoTenorPayload) -> Self {
+        Self {
+            min_transaction: Some(p.min_transaction),
+            subsidi: Some(p.subsidi),
+            admin: Some(p.admin),
+            discount: Some(p.discount),
+            max_discount: Some(p.max_discount),
+        }
+    }
+}
+
+impl From<&UpdatePromoTenorPayload> for TenorTerms {
+    fn from(p: &UpdatePromoTenorPayload) -> Self {
+        Self {
+            min_transaction: p.min_transaction,
+            subsidi: p.subsidi,
+            admin: p.admin,
+            discount: p.discount,
+            max_discount: p.max_discount,
+        }
+    }
+}
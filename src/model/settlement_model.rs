@@ -0,0 +1,253 @@
+use crate::model::budget_model::FinancedTransaction;
+use crate::model::money_model::Rate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Periode laporan dalam tanggal bisnis (`YYYY-MM-DD`), `to` inklusif
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SettlementQuery {
+    pub from: String,
+    pub to: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub company_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_id: Option<Uuid>,
+}
+
+/// Satu transaksi di laporan settlement, memakai snapshot term saat transaksi dicatat
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SettlementLine {
+    pub transaction_id: Uuid,
+    pub transaction_ref: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    pub promo_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_promo: Option<String>,
+    pub promo_tenor_id: Uuid,
+    pub tenor: i32,
+    pub amount: i64,
+    pub discount: i64,
+    pub financed_amount: i64,
+    pub admin_fee: i64,
+    pub subsidi_rate: Rate,
+    pub subsidy: i64,
+    pub free_installment_value: i64,
+}
+
+impl SettlementLine {
+    pub fn from_transaction(transaction: &FinancedTransaction, title_promo: Option<String>) -> Self {
+        Self {
+            transaction_id: transaction.id,
+            transaction_ref: transaction.transaction_ref.clone(),
+            created_at: transaction.created_at.clone(),
+            promo_id: transaction.promo_id,
+            title_promo,
+            promo_tenor_id: transaction.promo_tenor_id,
+            tenor: transaction.tenor,
+            amount: transaction.amount,
+            discount: transaction.discount,
+            financed_amount: transaction.financed_amount,
+            admin_fee: transaction.admin_fee,
+            subsidi_rate: transaction.subsidi_rate,
+            subsidy: transaction.subsidy,
+            free_installment_value: transaction.free_installment_value,
+        }
+    }
+}
+
+/// Total settlement.
+///
+/// - `financier_payable`: dana yang dicairkan ke merchant (`financed_amount`).
+/// - `merchant_payable`: subsidi bunga yang ditanggung merchant (`subsidy`).
+/// - `net_to_merchant`: `financier_payable - merchant_payable`.
+/// - `admin_fee` dibayar customer ke pembiaya, hanya informasi.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SettlementTotals {
+    pub transactions: i64,
+    pub amount: i64,
+    pub discount: i64,
+    pub financed_amount: i64,
+    pub admin_fee: i64,
+    pub subsidy: i64,
+    pub free_installment_value: i64,
+    pub financier_payable: i64,
+    pub merchant_payable: i64,
+    pub net_to_merchant: i64,
+}
+
+impl SettlementTotals {
+    pub fn add_line(&mut self, line: &SettlementLine) {
+        self.transactions += 1;
+        self.amount += line.amount;
+        self.discount += line.discount;
+        self.financed_amount += line.financed_amount;
+        self.admin_fee += line.admin_fee;
+        self.subsidy += line.subsidy;
+        self.free_installment_value += line.free_installment_value;
+        self.financier_payable += line.financed_amount;
+        self.merchant_payable += line.subsidy;
+        self.net_to_merchant = self.financier_payable - self.merchant_payable;
+    }
+
+    pub fn add_totals(&mut self, other: &SettlementTotals) {
+        self.transactions += other.transactions;
+        self.amount += other.amount;
+        self.discount += other.discount;
+        self.financed_amount += other.financed_amount;
+        self.admin_fee += other.admin_fee;
+        self.subsidy += other.subsidy;
+        self.free_installment_value += other.free_installment_value;
+        self.financier_payable += other.financier_payable;
+        self.merchant_payable += other.merchant_payable;
+        self.net_to_merchant = self.financier_payable - self.merchant_payable;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoreSettlement {
+    pub store_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_route: Option<String>,
+    pub totals: SettlementTotals,
+    pub lines: Vec<SettlementLine>,
+}
+
+/// `company_id` kosong untuk store yang belum di-link ke company terdaftar
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CompanySettlement {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub company_id: Option<Uuid>,
+    pub company_name: String,
+    pub totals: SettlementTotals,
+    pub stores: Vec<StoreSettlement>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SettlementReport {
+    pub from: String,
+    pub to: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub company_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_id: Option<Uuid>,
+    pub totals: SettlementTotals,
+    pub companies: Vec<CompanySettlement>,
+}
+
+impl SettlementReport {
+    /// Satu baris per transaksi, lalu subtotal per store, per company, dan total akhir
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "row_type,company_id,company_name,store_id,store_name,store_route,transaction_ref,created_at,\
+             promo_id,title_promo,promo_tenor_id,tenor,subsidi_rate,transactions,amount,discount,\
+             financed_amount,admin_fee,subsidy,free_installment_value,financier_payable,merchant_payable,\
+             net_to_merchant\n",
+        );
+
+        for company in &self.companies {
+            let company_id = company.company_id.map(|id| id.to_string()).unwrap_or_default();
+            for store in &company.stores {
+                let store_cols = [
+                    store.store_id.to_string(),
+                    store.store_name.clone().unwrap_or_default(),
+                    store.store_route.clone().unwrap_or_default(),
+                ];
+                for line in &store.lines {
+                    let mut totals = SettlementTotals::default();
+                    totals.add_line(line);
+                    push_row(
+                        &mut csv,
+                        "line",
+                        [&company_id, &company.company_name],
+                        &store_cols,
+                        [
+                            line.transaction_ref.clone(),
+                            line.created_at.clone().unwrap_or_default(),
+                            line.promo_id.to_string(),
+                            line.title_promo.clone().unwrap_or_default(),
+                            line.promo_tenor_id.to_string(),
+                            line.tenor.to_string(),
+                            line.subsidi_rate.to_string(),
+                        ],
+                        &totals,
+                    );
+                }
+                push_row(
+                    &mut csv,
+                    "store_total",
+                    [&company_id, &company.company_name],
+                    &store_cols,
+                    Default::default(),
+                    &store.totals,
+                );
+            }
+            push_row(
+                &mut csv,
+                "company_total",
+                [&company_id, &company.company_name],
+                &Default::default(),
+                Default::default(),
+                &company.totals,
+            );
+        }
+        push_row(
+            &mut csv,
+            "total",
+            ["", ""],
+            &Default::default(),
+            Default::default(),
+            &self.totals,
+        );
+        csv
+    }
+}
+
+fn push_row(
+    csv: &mut String,
+    row_type: &str,
+    company_cols: [&str; 2],
+    store_cols: &[String; 3],
+    line_cols: [String; 7],
+    totals: &SettlementTotals,
+) {
+    let text_cols = company_cols
+        .iter()
+        .map(|c| csv_field(c))
+        .chain(store_cols.iter().map(|c| csv_field(c)))
+        .chain(line_cols.iter().map(|c| csv_field(c)))
+        .collect::<Vec<_>>()
+        .join(",");
+    csv.push_str(&format!(
+        "{},{},{},{},{},{},{},{},{},{},{},{}\n",
+        row_type,
+        text_cols,
+        totals.transactions,
+        totals.amount,
+        totals.discount,
+        totals.financed_amount,
+        totals.admin_fee,
+        totals.subsidy,
+        totals.free_installment_value,
+        totals.financier_payable,
+        totals.merchant_payable,
+        totals.net_to_merchant
+    ));
+}
+
+/// Kutip field yang mengandung koma, kutip atau baris baru. Teks yang diawali `=`, `+`, `-`
+/// atau `@` diberi prefix `'` supaya tidak dijalankan sebagai formula oleh spreadsheet.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Penugasan user (JWT `sub`) ke satu store, atau ke semua store milik satu company.
+/// Tepat satu dari `store_id` / `company_id` terisi.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoreAssignment {
+    pub id: Uuid,
+    pub user_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub company_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateStoreAssignmentPayload {
+    pub user_id: String,
+    #[serde(default)]
+    pub store_id: Option<Uuid>,
+    #[serde(default)]
+    pub company_id: Option<Uuid>,
+}
+
+/// Baris yang dikirim ke tabel `store_assignment`
+#[derive(Serialize, Debug, Clone)]
+pub struct StoreAssignmentRow {
+    pub user_id: String,
+    pub store_id: Option<Uuid>,
+    pub company_id: Option<Uuid>,
+    pub created_by: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct StoreAssignmentQuery {
+    pub user_id: Option<String>,
+}
+
+/// Store yang boleh dikelola pemanggil. Admin tidak dibatasi; role lain hanya store yang
+/// di-assign langsung atau milik company yang di-assign.
+#[derive(Debug, Clone)]
+pub enum StoreScope {
+    All,
+    Assigned {
+        store_ids: HashSet<Uuid>,
+        company_ids: HashSet<Uuid>,
+    },
+}
+
+impl StoreScope {
+    pub fn is_all(&self) -> bool {
+        matches!(self, StoreScope::All)
+    }
+
+    pub fn allows_store(&self, store_id: Uuid) -> bool {
+        match self {
+            StoreScope::All => true,
+            StoreScope::Assigned { store_ids, .. } => store_ids.contains(&store_id),
+        }
+    }
+
+    /// Hanya company yang di-assign langsung, bukan company dari store yang di-assign
+    pub fn allows_company(&self, company_id: Uuid) -> bool {
+        match self {
+            StoreScope::All => true,
+            StoreScope::Assigned { company_ids, .. } => company_ids.contains(&company_id),
+        }
+    }
+}
@@ -1,13 +1,21 @@
+use crate::model::promo_store_model::deserialize_explicit_null;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct CreateStorePayload {
     pub name: String,
+    /// Boleh kosong jika `company_id` diisi; nama diambil dari company
+    #[serde(default)]
     pub company: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub company_id: Option<Uuid>,
     pub address: Option<String>,
     pub route: String,
     pub store_type: StoreType,
+    /// Label bebas untuk targeting bulk (mis. `mall`, `jabodetabek`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -16,15 +24,26 @@ pub struct UpdateStorePayload {
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub company: Option<String>,
+    /// Field tidak dikirim = tidak diubah, `null` = lepas dari company
+    #[serde(
+        default,
+        deserialize_with = "deserialize_explicit_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub company_id: Option<Option<Uuid>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub address: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub route: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub store_type: Option<StoreType>,
+    /// Mengganti seluruh tag; `[]` = hapus semua tag
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum StoreType {
     KA,
     NKA,
@@ -35,11 +54,35 @@ pub struct Store {
     pub id: Uuid,
     pub name: String,
     pub company: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub company_id: Option<Uuid>,
     pub address: Option<String>,
     pub route: Option<String>,
     pub store_type: Option<StoreType>,
+    #[serde(default)]
+    pub tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created_at: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub updated_at: Option<String>,
 }
+
+/// Normalisasi tag store: trim, huruf kecil, tanpa tag kosong atau duplikat
+pub fn normalize_store_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Normalisasi nama company: trim, spasi ganda dirapikan, huruf kecil
+pub fn normalize_company_name(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
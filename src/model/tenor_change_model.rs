@@ -0,0 +1,125 @@
+use crate::business_time::parse_business_datetime;
+use crate::model::promo_tenor_model::UpdatePromoTenorPayload;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TenorChangeStatus {
+    /// Perubahan pada promo yang bukan draft; menunggu approval admin sebelum boleh diterapkan
+    PendingApproval,
+    Pending,
+    /// Diklaim scheduler dan sedang diterapkan; cancel tidak lagi berlaku
+    Applying,
+    Applied,
+    Cancelled,
+    Failed,
+}
+
+impl TenorChangeStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TenorChangeStatus::PendingApproval => "pending_approval",
+            TenorChangeStatus::Pending => "pending",
+            TenorChangeStatus::Applying => "applying",
+            TenorChangeStatus::Applied => "applied",
+            TenorChangeStatus::Cancelled => "cancelled",
+            TenorChangeStatus::Failed => "failed",
+        }
+    }
+
+    /// Belum diterapkan dan masih bisa dibatalkan
+    pub fn is_open(self) -> bool {
+        matches!(self, TenorChangeStatus::PendingApproval | TenorChangeStatus::Pending)
+    }
+}
+
+/// Perubahan PromoTenor yang dijadwalkan; `changes` disimpan sebagai jsonb dan
+/// diterapkan apa adanya lewat update tenor saat `effective_at` tercapai.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PromoTenorChange {
+    pub id: Uuid,
+    pub promo_tenor_id: Uuid,
+    pub changes: UpdatePromoTenorPayload,
+    pub effective_at: String,
+    pub status: TenorChangeStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approved_at: Option<String>,
+    /// Waktu diklaim scheduler (`applying`); klaim yang terlalu lama dianggap instance-nya mati
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claimed_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applied_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancelled_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancelled_at: Option<String>,
+    /// Alasan status `failed` (mis. term tidak lagi valid untuk tipe promo)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl PromoTenorChange {
+    pub fn effective_at(&self) -> Option<DateTime<Utc>> {
+        parse_business_datetime(&self.effective_at, false)
+    }
+
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.status == TenorChangeStatus::Pending && self.effective_at().is_some_and(|at| at <= now)
+    }
+
+    /// Klaim `applying` yang lebih tua dari `cutoff` (atau tanpa `claimed_at`) ditinggal instance yang mati
+    pub fn is_stale_claim(&self, cutoff: DateTime<Utc>) -> bool {
+        self.status == TenorChangeStatus::Applying
+            && self
+                .claimed_at
+                .as_deref()
+                .and_then(|raw| DateTime::parse_from_rfc3339(raw).ok())
+                .is_none_or(|at| at.with_timezone(&Utc) < cutoff)
+    }
+}
+
+/// Body `/schedule-promo-tenor-change`; `effective_at` mengikuti format tanggal promo
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SchedulePromoTenorChangePayload {
+    pub promo_tenor_id: Uuid,
+    pub changes: UpdatePromoTenorPayload,
+    pub effective_at: String,
+}
+
+/// Baris yang dikirim ke tabel `promo_tenor_change`
+#[derive(Serialize, Debug, Clone)]
+pub struct CreatePromoTenorChangePayload {
+    pub promo_tenor_id: Uuid,
+    pub changes: UpdatePromoTenorPayload,
+    pub effective_at: String,
+    pub status: TenorChangeStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct UpdatePromoTenorChangePayload {
+    pub status: TenorChangeStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claimed_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applied_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancelled_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancelled_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
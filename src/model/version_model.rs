@@ -0,0 +1,140 @@
+use crate::business_time::parse_business_datetime;
+use crate::model::promo_model::Promo;
+use crate::model::promo_tenor_model::StorePromoTenor;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionedEntity {
+    Promo,
+    PromoTenor,
+    PromoStore,
+}
+
+impl VersionedEntity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            VersionedEntity::Promo => "promo",
+            VersionedEntity::PromoTenor => "promo_tenor",
+            VersionedEntity::PromoStore => "promo_store",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionOperation {
+    Create,
+    Update,
+    Delete,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EntityVersion {
+    pub id: Uuid,
+    /// Nomor urut 1.. per entity, diisi trigger `record_entity_version()` saat versi dicatat
+    pub version: usize,
+    pub entity_type: VersionedEntity,
+    pub entity_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promo_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_id: Option<Uuid>,
+    pub operation: VersionOperation,
+    pub snapshot: Value,
+    pub created_at: String,
+}
+
+impl EntityVersion {
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        parse_business_datetime(&self.created_at, false)
+    }
+
+    pub fn is_delete(&self) -> bool {
+        self.operation == VersionOperation::Delete
+    }
+}
+
+/// `at` menerima format tanggal bisnis; tanggal tanpa jam berarti akhir hari tersebut
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AsOfQuery {
+    pub at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenor: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VersionDiffQuery {
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VersionDiff {
+    pub entity_type: VersionedEntity,
+    pub entity_id: String,
+    pub from: EntityVersion,
+    pub to: EntityVersion,
+    pub changes: Vec<FieldChange>,
+}
+
+impl VersionDiff {
+    /// Bandingkan field level atas kedua snapshot; field yang tidak ada dianggap `null`
+    pub fn between(from: EntityVersion, to: EntityVersion) -> Self {
+        let empty = serde_json::Map::new();
+        let before = from.snapshot.as_object().unwrap_or(&empty);
+        let after = to.snapshot.as_object().unwrap_or(&empty);
+
+        let mut fields: Vec<&String> = before.keys().chain(after.keys()).collect();
+        fields.sort();
+        fields.dedup();
+
+        let changes = fields
+            .into_iter()
+            .filter_map(|field| {
+                let old = before.get(field).cloned().unwrap_or(Value::Null);
+                let new = after.get(field).cloned().unwrap_or(Value::Null);
+                (old != new).then(|| FieldChange {
+                    field: field.clone(),
+                    before: old,
+                    after: new,
+                })
+            })
+            .collect();
+
+        Self {
+            entity_type: to.entity_type,
+            entity_id: to.entity_id.clone(),
+            from,
+            to,
+            changes,
+        }
+    }
+}
+
+/// Term efektif satu tenor di store pada waktu tertentu, beserta versi yang dipakai
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HistoricalStoreTenor {
+    pub promo: Promo,
+    pub tenor: StorePromoTenor,
+    pub promo_version: usize,
+    pub promo_tenor_version: usize,
+    pub promo_store_version: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StoreTermsAsOf {
+    pub store_id: Uuid,
+    pub at: String,
+    pub tenors: Vec<HistoricalStoreTenor>,
+}
@@ -0,0 +1,79 @@
+use crate::model::promo_tenor_model::PromoTenor;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VoucherRedemption {
+    pub id: Uuid,
+    pub voucher_code: String,
+    pub promo_tenor_id: Uuid,
+    pub store_id: Uuid,
+    pub redemption_key: String,
+    pub amount: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+}
+
+/// Parameter fungsi database `redeem_voucher` (cek kuota + insert dalam satu transaksi)
+#[derive(Serialize, Debug, Clone)]
+pub struct RedeemVoucherParams {
+    pub p_voucher_code: String,
+    pub p_promo_tenor_id: Uuid,
+    pub p_store_id: Uuid,
+    pub p_redemption_key: String,
+    pub p_amount: i64,
+    pub p_max_usage: Option<i32>,
+    pub p_max_usage_per_store: Option<i32>,
+}
+
+/// Hasil fungsi database `redeem_voucher`
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum RedeemOutcome {
+    Redeemed { redemption: VoucherRedemption },
+    /// `redemption_key` sudah tercatat (request paralel dengan key yang sama)
+    Replayed { redemption: VoucherRedemption },
+    GlobalLimitReached,
+    StoreLimitReached,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ValidateVoucherPayload {
+    pub voucher_code: String,
+    pub store_route: String,
+    pub amount: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenor: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RedeemVoucherPayload {
+    pub voucher_code: String,
+    pub store_route: String,
+    pub amount: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenor: Option<i32>,
+    pub redemption_key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VoucherValidation {
+    pub voucher_code: String,
+    pub valid: bool,
+    pub reasons: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promo_tenor: Option<PromoTenor>,
+    pub usage_count: usize,
+    pub store_usage_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_usage: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_store_usage: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VoucherRedemptionResult {
+    pub redemption: VoucherRedemption,
+    /// `true` jika redemption_key sudah pernah dipakai dan data lama dikembalikan
+    pub replayed: bool,
+}
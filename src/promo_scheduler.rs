@@ -0,0 +1,71 @@
+use crate::model::promo_model::{PromoStatus, UpdatePromoPayload};
+use crate::repositories::cache_repository::CacheRepository;
+use crate::repositories::promo_repository::PromoRepository;
+use crate::scheduler;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Background job yang mematikan `is_active` promo yang sudah lewat `end_date_promo`
+/// dan me-refresh cache promo saat status hitung berpindah di batas window.
+pub async fn run(promo_repo: Arc<PromoRepository>, cache_repository: Arc<CacheRepository>) {
+    scheduler::run_loop("Promo scheduler", "PROMO_SCHEDULER", || tick(&promo_repo, &cache_repository)).await;
+}
+
+async fn tick(
+    promo_repo: &PromoRepository,
+    cache_repository: &CacheRepository,
+) -> Option<DateTime<Utc>> {
+    let promos = match promo_repo.rep_fetch_all().await {
+        Ok(promos) => promos,
+        Err(e) => {
+            warn!("Promo scheduler gagal memuat promo: {}", e);
+            return None;
+        }
+    };
+
+    let now = Utc::now();
+    let mut changed = false;
+
+    for promo in &promos {
+        let current = promo.status_at(now);
+        // Status hasil hitung di cache bisa basi walau `is_active` sudah benar
+        let crossed_boundary = current != promo.status;
+
+        // Hanya auto-expire: aktivasi sudah tercermin dari status hitung (`is_live`), dan
+        // `is_active = false` dari operator tidak boleh dinyalakan ulang oleh scheduler.
+        // Tanggal rusak sudah membuat promo keluar dari window; `is_active` tidak ikut diubah.
+        let target_active = match current {
+            PromoStatus::Expired if promo.is_active && !promo.has_invalid_window() => Some(false),
+            _ => None,
+        };
+
+        if let Some(is_active) = target_active {
+            let payload = UpdatePromoPayload {
+                is_active: Some(is_active),
+                ..Default::default()
+            };
+            match promo_repo.rep_update_by_id(promo.id_promo, payload).await {
+                Ok(_) => info!(
+                    "Promo {} berpindah status {:?} -> {:?} (is_active = {})",
+                    promo.id_promo, promo.status, current, is_active
+                ),
+                Err(e) => warn!("Gagal memperbarui status promo {}: {}", promo.id_promo, e),
+            }
+        }
+
+        changed |= crossed_boundary || target_active.is_some();
+    }
+
+    if changed {
+        cache_repository.clear_promo_cache_all().await;
+        if let Err(e) = promo_repo.rep_fetch_all().await {
+            warn!("Promo scheduler gagal me-warm cache promo: {}", e);
+        }
+    }
+
+    promos
+        .iter()
+        .filter_map(|promo| promo.next_boundary_after(now))
+        .min()
+}
@@ -13,7 +13,8 @@ use std::{
 };
 use tracing::{info, warn};
 
-use crate::constants::PUBLIC_ENDPOINTS;
+use crate::access_policy::AccessPolicy;
+use crate::constants::API_KEY_HEADER;
 
 #[derive(Clone)]
 pub struct RateLimiter {
@@ -70,10 +71,33 @@ impl RateLimiter {
             return next.run(req).await;
         }
 
-        let path = req.uri().path();
-        
-        // Skip rate limiting for public endpoints only
-        if PUBLIC_ENDPOINTS.iter().any(|&endpoint| path.starts_with(endpoint)) {
+        // Skip rate limiting for routes declared public only
+        if AccessPolicy::of(req.extensions()).is_public() {
+            return next.run(req).await;
+        }
+
+        // Request dengan API key valid memakai kuota per key (dicek di `auth`); key yang
+        // ditolak 401 tetap dihitung ke kuota fingerprint supaya tidak bisa dipakai menebak key
+        if req.headers().contains_key(API_KEY_HEADER) {
+            let fingerprint = self.generate_fingerprint(&req);
+            if self.is_exhausted(&fingerprint, self.max_requests) {
+                warn!(fingerprint = %fingerprint, "Rate limit exceeded (invalid API keys)");
+                return (StatusCode::TOO_MANY_REQUESTS, "Too many requests").into_response();
+            }
+            let response = next.run(req).await;
+            if response.status() == StatusCode::UNAUTHORIZED {
+                self.try_acquire(&fingerprint, self.max_requests);
+            }
+            return response;
+        }
+
+        self.enforce(req, next).await
+    }
+
+    /// Batasi semua request tanpa pengecualian path, untuk limiter khusus satu route
+    /// (mis. `/submit-lead`) yang dipasang lewat `route_layer`.
+    pub async fn enforce(&self, req: Request, next: Next) -> Response {
+        if !self.enabled {
             return next.run(req).await;
         }
 
@@ -81,23 +105,13 @@ impl RateLimiter {
         let fingerprint = self.generate_fingerprint(&req);
         let fingerprint_gen_ms = start.elapsed().as_micros() as f64 / 1000.0;
 
-        let now = Instant::now();
-        let mut entry = self.requests.entry(fingerprint.clone()).or_insert_with(Vec::new);
-
-        entry.retain(|&time| now.duration_since(time) < self.window);
-
-        if entry.len() >= self.max_requests {
+        let Some(request_count) = self.try_acquire(&fingerprint, self.max_requests) else {
             warn!(
                 fingerprint = %fingerprint,
-                requests = entry.len(),
                 "Rate limit exceeded"
             );
             return (StatusCode::TOO_MANY_REQUESTS, "Too many requests").into_response();
-        }
-
-        entry.push(now);
-        let request_count = entry.len();
-        drop(entry);
+        };
 
         info!(
             fingerprint_gen_ms = fingerprint_gen_ms,
@@ -107,4 +121,32 @@ impl RateLimiter {
 
         next.run(req).await
     }
+
+    /// Apakah `bucket` sudah mencapai `max_requests` dalam window, tanpa mencatat request
+    fn is_exhausted(&self, bucket: &str, max_requests: usize) -> bool {
+        let now = Instant::now();
+        self.requests.get(bucket).is_some_and(|entry| {
+            entry.iter().filter(|&&time| now.duration_since(time) < self.window).count() >= max_requests
+        })
+    }
+
+    /// Catat satu request pada `bucket` jika masih di bawah `max_requests` dalam window;
+    /// `None` = kuota habis. Dipakai juga untuk kuota per API key (bucket = id key).
+    pub fn try_acquire(&self, bucket: &str, max_requests: usize) -> Option<usize> {
+        if !self.enabled {
+            return Some(0);
+        }
+
+        let now = Instant::now();
+        let mut entry = self.requests.entry(bucket.to_string()).or_default();
+
+        entry.retain(|&time| now.duration_since(time) < self.window);
+
+        if entry.len() >= max_requests {
+            return None;
+        }
+
+        entry.push(now);
+        Some(entry.len())
+    }
 }
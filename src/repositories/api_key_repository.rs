@@ -0,0 +1,156 @@
+use crate::error::{ApiKeyError, AppError};
+use crate::model::api_key_model::*;
+use crate::repositories::cache_repository::CacheRepository;
+use crate::supabase::SupabaseClient;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Snapshot tabel `api_key` per `key_hash` beserta waktu muatnya
+type ApiKeysByHash = Option<(Instant, HashMap<String, ApiKey>)>;
+
+/// Seluruh tabel `api_key` di-cache per hash selama `API_KEY_CACHE_SECONDS` (default 60),
+/// jadi key tak dikenal tidak pernah memicu query. Issue/revoke di instance ini langsung
+/// membersihkan cache; instance lain mengikuti paling lambat setelah TTL.
+#[derive(Clone)]
+pub struct ApiKeyRepository {
+    pub supabase_client: Arc<SupabaseClient>,
+    pub cache_repository: Arc<CacheRepository>,
+    by_hash: Arc<RwLock<ApiKeysByHash>>,
+    cache_ttl: Duration,
+}
+
+impl ApiKeyRepository {
+    pub fn new(
+        supabase_client: Arc<SupabaseClient>,
+        cache_repository: Arc<CacheRepository>,
+    ) -> Self {
+        let cache_ttl = std::env::var("API_KEY_CACHE_SECONDS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(60));
+        Self {
+            supabase_client,
+            cache_repository,
+            by_hash: Arc::new(RwLock::new(None)),
+            cache_ttl,
+        }
+    }
+
+    /// Terbaru lebih dulu, termasuk key yang sudah expired/dicabut
+    pub async fn rep_fetch_all(&self) -> Result<Vec<ApiKey>, AppError> {
+        let keys_from_db = match self
+            .supabase_client
+            .from::<Value>("api_key")
+            .order("created_at", false)
+            .execute()
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) if e.is_not_found() => vec![],
+            Err(e) => {
+                return Err(ApiKeyError::DatabaseError(format!("Supabase error: {}", e)).into());
+            }
+        };
+
+        info!("Berhasil mendapatkan {} API key dari Supabase.", keys_from_db.len());
+
+        let keys: Vec<ApiKey> = keys_from_db
+            .into_iter()
+            .filter_map(|item| match serde_json::from_value::<ApiKey>(item) {
+                Ok(key) => Some(key),
+                Err(e) => {
+                    // Jangan log baris mentah: berisi key_hash
+                    warn!("Failed to deserialize API key: {}", e);
+                    None
+                }
+            })
+            .collect();
+
+        Ok(keys)
+    }
+
+    pub async fn rep_fetch_by_id(&self, id: Uuid) -> Result<ApiKey, AppError> {
+        let keys_from_db = self
+            .supabase_client
+            .from::<Value>("api_key")
+            .eq("id", &id.to_string())
+            .limit(1)
+            .execute()
+            .await
+            .map_err(|e| ApiKeyError::DatabaseError(format!("Supabase error: {}", e)))?;
+
+        let value = keys_from_db
+            .into_iter()
+            .next()
+            .ok_or_else(|| ApiKeyError::NotFound(format!("API key with id '{}' not found", id)))?;
+
+        serde_json::from_value(value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+
+    /// Lookup untuk autentikasi, dari cache selama masih segar
+    pub async fn rep_fetch_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, AppError> {
+        {
+            let cache = self.by_hash.read().await;
+            if let Some((loaded_at, keys)) = cache.as_ref()
+                && loaded_at.elapsed() < self.cache_ttl
+            {
+                return Ok(keys.get(key_hash).cloned());
+            }
+        }
+
+        info!("Cache API key kosong/kedaluwarsa. Menghubungi Supabase...");
+        let keys: HashMap<String, ApiKey> = self
+            .rep_fetch_all()
+            .await?
+            .into_iter()
+            .map(|k| (k.key_hash.clone(), k))
+            .collect();
+        let found = keys.get(key_hash).cloned();
+        *self.by_hash.write().await = Some((Instant::now(), keys));
+        Ok(found)
+    }
+
+    pub async fn rep_insert(&self, row: ApiKeyRow) -> Result<ApiKey, AppError> {
+        let inserted_value = self
+            .supabase_client
+            .from::<Value>("api_key")
+            .insert(&row)
+            .await
+            .map_err(|e| ApiKeyError::DatabaseError(format!("Supabase insert error: {}", e)))?;
+        self.clear_cache().await;
+
+        serde_json::from_value(inserted_value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+
+    /// Hanya key yang belum dicabut yang diperbarui; `None` = sudah dicabut lebih dulu
+    pub async fn rep_revoke(&self, id: Uuid, row: RevokeApiKeyRow) -> Result<Option<ApiKey>, AppError> {
+        let updated_vec = self
+            .supabase_client
+            .from::<Value>("api_key")
+            .eq("id", &id.to_string())
+            .is_null("revoked_at")
+            .update(&row)
+            .await
+            .map_err(|e| ApiKeyError::DatabaseError(format!("Supabase update error: {}", e)))?;
+        self.clear_cache().await;
+
+        match updated_vec.into_iter().next() {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e))),
+            None => Ok(None),
+        }
+    }
+
+    async fn clear_cache(&self) {
+        *self.by_hash.write().await = None;
+    }
+}
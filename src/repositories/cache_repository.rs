@@ -1,6 +1,7 @@
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::RwLock;
 use tracing::info;
 use uuid::Uuid;
@@ -39,6 +40,15 @@ impl AuthTokenCache {
     }
 }
 
+/// Cache entity yang terdampak satu operasi (mis. cascade delete)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CacheScope {
+    pub promo: bool,
+    pub promo_tenor: bool,
+    pub store: bool,
+    pub promo_store: bool,
+}
+
 #[derive(Clone)]
 pub struct CacheRepository {
     promo_cache_all: Arc<RwLock<Vec<Promo>>>,
@@ -50,6 +60,8 @@ pub struct CacheRepository {
     promo_store_cache_by_key: Arc<RwLock<HashMap<String, PromoStore>>>,
 
     auth_token_cache: Arc<RwLock<Option<AuthTokenCache>>>,
+    /// Versi JWKS saat claims di `auth_token_cache` diverifikasi
+    claims_key_set_version: Arc<AtomicU64>,
 }
 impl CacheRepository {
     pub fn new() -> Self {
@@ -63,6 +75,7 @@ impl CacheRepository {
             promo_store_cache_by_key: Arc::new(RwLock::new(HashMap::new())),
 
             auth_token_cache: Arc::new(RwLock::new(None)),
+            claims_key_set_version: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -79,26 +92,39 @@ impl CacheRepository {
     /// Return cached claims (JSON) if token exists and is still valid
     pub async fn get_cached_claims(&self, token: &str) -> Option<JsonValue> {
         let cache = self.auth_token_cache.read().await;
-        if let Some(auth) = cache.as_ref() {
-            if let Some(t) = auth.token.get(token) {
-                // Check if token is expired
-                if chrono::Utc::now() < t.expiry {
-                    return t.claims.clone();
-                } else {
-                    // Token expired, need to remove it
-                    drop(cache);
-                    let mut cache_mut = self.auth_token_cache.write().await;
-                    if let Some(auth_mut) = cache_mut.as_mut() {
-                        auth_mut.token.remove(token);
-                        info!("Removed expired token from cache");
-                    }
-                    return None;
+        if let Some(auth) = cache.as_ref()
+            && let Some(t) = auth.token.get(token)
+        {
+            // Check if token is expired
+            if chrono::Utc::now() < t.expiry {
+                return t.claims.clone();
+            } else {
+                // Token expired, need to remove it
+                drop(cache);
+                let mut cache_mut = self.auth_token_cache.write().await;
+                if let Some(auth_mut) = cache_mut.as_mut() {
+                    auth_mut.token.remove(token);
+                    info!("Removed expired token from cache");
                 }
+                return None;
             }
         }
         None
     }
 
+    /// Kosongkan cache claims jika JWKS berubah sejak claims diverifikasi, supaya token
+    /// dari key yang dirotasi/dicabut tidak lolos lewat cache
+    pub async fn sync_claims_key_set_version(&self, version: u64) {
+        if self.claims_key_set_version.swap(version, Ordering::SeqCst) == version {
+            return;
+        }
+        let mut cache = self.auth_token_cache.write().await;
+        if let Some(auth) = cache.as_mut() {
+            auth.token.clear();
+        }
+        info!("JWKS berubah, cache claims dikosongkan");
+    }
+
     /// Save a token with expiry and optional claims into the cache
     pub async fn save_token_claims(
         &self,
@@ -243,4 +269,51 @@ impl CacheRepository {
         cache.clear();
         info!("Menghapus cache promo_tenor (all)...");
     }
+
+    /// Bersihkan semua cache dalam `scope` sekaligus: semua write lock diambil dulu
+    /// (urutan tetap) supaya pembaca tidak melihat sebagian cache yang sudah basi.
+    pub async fn invalidate(&self, scope: CacheScope) {
+        let mut promo = if scope.promo {
+            Some(self.promo_cache_all.write().await)
+        } else {
+            None
+        };
+        let mut promo_tenor = if scope.promo_tenor {
+            Some(self.promo_tenor_cache_all.write().await)
+        } else {
+            None
+        };
+        let mut store = if scope.store {
+            Some((
+                self.store_cache_all.write().await,
+                self.store_cache_by_route.write().await,
+            ))
+        } else {
+            None
+        };
+        let mut promo_store = if scope.promo_store {
+            Some((
+                self.promo_store_cache_all.write().await,
+                self.promo_store_cache_by_key.write().await,
+            ))
+        } else {
+            None
+        };
+
+        if let Some(cache) = promo.as_mut() {
+            cache.clear();
+        }
+        if let Some(cache) = promo_tenor.as_mut() {
+            cache.clear();
+        }
+        if let Some((all, by_route)) = store.as_mut() {
+            all.clear();
+            by_route.clear();
+        }
+        if let Some((all, by_key)) = promo_store.as_mut() {
+            all.clear();
+            by_key.clear();
+        }
+        info!("Menghapus cache sekaligus: {:?}", scope);
+    }
 }
@@ -0,0 +1,144 @@
+use crate::error::{AppError, CompanyError};
+use crate::model::company_model::*;
+use crate::repositories::cache_repository::CacheRepository;
+use crate::supabase::SupabaseClient;
+use crate::supabase::error::SupabaseError;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Tabel company kecil dan hanya dibaca di jalur admin, jadi tidak di-cache.
+#[derive(Clone)]
+pub struct CompanyRepository {
+    pub supabase_client: Arc<SupabaseClient>,
+    pub cache_repository: Arc<CacheRepository>,
+}
+
+impl CompanyRepository {
+    pub fn new(
+        supabase_client: Arc<SupabaseClient>,
+        cache_repository: Arc<CacheRepository>,
+    ) -> Self {
+        Self {
+            supabase_client,
+            cache_repository,
+        }
+    }
+
+    pub async fn rep_fetch_all(&self) -> Result<Vec<Company>, AppError> {
+        let companies_from_db = self
+            .supabase_client
+            .from::<Value>("company")
+            .order("name", true)
+            .execute()
+            .await
+            .map_err(|e: SupabaseError| {
+                if e.is_not_found() {
+                    AppError::from(CompanyError::NotFound("No companies found".to_string()))
+                } else {
+                    AppError::from(CompanyError::DatabaseError(format!("Supabase error: {}", e)))
+                }
+            })?;
+
+        info!("Berhasil mendapatkan {} company dari Supabase.", companies_from_db.len());
+
+        let companies: Vec<Company> = companies_from_db
+            .into_iter()
+            .filter_map(|item| match serde_json::from_value(item.clone()) {
+                Ok(company) => Some(company),
+                Err(e) => {
+                    warn!("Failed to deserialize company: {}. Data: {:?}", e, item);
+                    None
+                }
+            })
+            .collect();
+
+        Ok(companies)
+    }
+
+    pub async fn rep_fetch_by_id(&self, id: Uuid) -> Result<Company, AppError> {
+        let companies_from_db = self
+            .supabase_client
+            .from::<Value>("company")
+            .eq("id", &id.to_string())
+            .limit(1)
+            .execute()
+            .await
+            .map_err(|e| CompanyError::DatabaseError(format!("Supabase error: {}", e)))?;
+
+        let value = companies_from_db
+            .into_iter()
+            .next()
+            .ok_or_else(|| CompanyError::NotFound(format!("Company with id '{}' not found", id)))?;
+
+        serde_json::from_value(value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+
+    pub async fn rep_fetch_by_normalized_name(
+        &self,
+        normalized_name: &str,
+    ) -> Result<Option<Company>, AppError> {
+        let companies_from_db = self
+            .supabase_client
+            .from::<Value>("company")
+            .eq("normalized_name", normalized_name)
+            .limit(1)
+            .execute()
+            .await
+            .map_err(|e| CompanyError::DatabaseError(format!("Supabase error: {}", e)))?;
+
+        match companies_from_db.into_iter().next() {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e))),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn rep_insert(&self, row: CompanyRow) -> Result<Company, AppError> {
+        let inserted_value = self
+            .supabase_client
+            .from::<Value>("company")
+            .insert(&row)
+            .await
+            .map_err(|e| CompanyError::DatabaseError(format!("Supabase insert error: {}", e)))?;
+
+        serde_json::from_value(inserted_value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+
+    pub async fn rep_update_by_id(&self, id: Uuid, row: CompanyRow) -> Result<Company, AppError> {
+        let updated_vec = self
+            .supabase_client
+            .from::<Value>("company")
+            .eq("id", &id.to_string())
+            .update(&row)
+            .await
+            .map_err(|e| CompanyError::DatabaseError(format!("Supabase update error: {}", e)))?;
+
+        let company_value = updated_vec
+            .into_iter()
+            .next()
+            .ok_or_else(|| CompanyError::NotFound(format!("Company with id '{}' not found", id)))?;
+
+        serde_json::from_value(company_value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+
+    pub async fn rep_delete_by_id(&self, id: Uuid) -> Result<(), AppError> {
+        let deleted = self
+            .supabase_client
+            .from::<Value>("company")
+            .eq("id", &id.to_string())
+            .delete()
+            .await
+            .map_err(|e| CompanyError::DatabaseError(format!("Supabase delete error: {}", e)))?;
+
+        if deleted.is_empty() {
+            return Err(CompanyError::NotFound(format!("Company with id '{}' not found", id)).into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use crate::error::{AppError, HistoryError};
+use crate::model::version_model::*;
+use crate::repositories::cache_repository::CacheRepository;
+use crate::supabase::{QueryBuilder, SupabaseClient};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Ukuran halaman saat membaca riwayat; PostgREST memotong hasil di `max-rows`
+const PAGE_SIZE: usize = 1000;
+
+/// Riwayat versi Promo, PromoTenor dan PromoStore (append-only, tidak di-cache).
+/// Dicatat oleh trigger database (migrasi 0004, nomor versi sejak 0020) dalam transaksi yang sama
+/// dengan perubahannya.
+#[derive(Clone)]
+pub struct EntityVersionRepository {
+    pub supabase_client: Arc<SupabaseClient>,
+    pub cache_repository: Arc<CacheRepository>,
+}
+
+impl EntityVersionRepository {
+    pub fn new(
+        supabase_client: Arc<SupabaseClient>,
+        cache_repository: Arc<CacheRepository>,
+    ) -> Self {
+        Self {
+            supabase_client,
+            cache_repository,
+        }
+    }
+
+    /// Semua versi satu entity, urut dari yang terlama (versi 1)
+    pub async fn rep_fetch_by_entity(
+        &self,
+        entity_type: VersionedEntity,
+        entity_id: &str,
+    ) -> Result<Vec<EntityVersion>, AppError> {
+        self.fetch_pages("version", |query| {
+            query
+                .eq("entity_type", entity_type.as_str())
+                .eq("entity_id", entity_id)
+        })
+        .await
+    }
+
+    /// Versi terakhir satu entity yang tercatat pada atau sebelum `at`
+    pub async fn rep_fetch_as_of(
+        &self,
+        entity_type: VersionedEntity,
+        entity_id: &str,
+        at: DateTime<Utc>,
+    ) -> Result<Option<EntityVersion>, AppError> {
+        let versions_from_db = self
+            .supabase_client
+            .from::<Value>("entity_version")
+            .eq("entity_type", entity_type.as_str())
+            .eq("entity_id", entity_id)
+            .lte_str("created_at", &at.to_rfc3339())
+            .order("version", false)
+            .limit(1)
+            .execute()
+            .await
+            .map_err(|e| HistoryError::DatabaseError(format!("Supabase error: {}", e)))?;
+
+        match versions_from_db.into_iter().next() {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e))),
+            None => Ok(None),
+        }
+    }
+
+    /// Versi yang tercatat pada atau sebelum `at` untuk entity bertipe `entity_type` yang kolom
+    /// `column`-nya salah satu `values` (mis. semua PromoStore milik satu store)
+    pub async fn rep_fetch_by_column_until(
+        &self,
+        entity_type: VersionedEntity,
+        column: &str,
+        values: &[Uuid],
+        at: DateTime<Utc>,
+    ) -> Result<Vec<EntityVersion>, AppError> {
+        if values.is_empty() {
+            return Ok(vec![]);
+        }
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        let values: Vec<&str> = values.iter().map(String::as_str).collect();
+        let at = at.to_rfc3339();
+        // `id` unik, jadi urutan antar halaman stabil meski banyak entity
+        self.fetch_pages("id", |query| {
+            query
+                .eq("entity_type", entity_type.as_str())
+                .r#in(column, &values)
+                .lte_str("created_at", &at)
+        })
+        .await
+    }
+
+    /// Baca semua halaman sampai halaman kosong; `order` harus unik dalam hasil query
+    async fn fetch_pages(
+        &self,
+        order: &str,
+        filter: impl Fn(QueryBuilder<'_, Value>) -> QueryBuilder<'_, Value>,
+    ) -> Result<Vec<EntityVersion>, AppError> {
+        let mut versions_from_db = Vec::new();
+        loop {
+            let query = self
+                .supabase_client
+                .from::<Value>("entity_version")
+                .order(order, true)
+                .limit(PAGE_SIZE)
+                .offset(versions_from_db.len());
+            let page = match filter(query).execute().await {
+                Ok(page) => page,
+                Err(e) if e.is_not_found() => vec![],
+                Err(e) => {
+                    return Err(HistoryError::DatabaseError(format!("Supabase error: {}", e)).into());
+                }
+            };
+            if page.is_empty() {
+                break;
+            }
+            versions_from_db.extend(page);
+        }
+
+        info!("Berhasil mendapatkan {} entity_version dari Supabase.", versions_from_db.len());
+
+        Ok(versions_from_db
+            .into_iter()
+            .filter_map(|item| match serde_json::from_value(item.clone()) {
+                Ok(version) => Some(version),
+                Err(e) => {
+                    warn!("Failed to deserialize entity_version: {}. Data: {:?}", e, item);
+                    None
+                }
+            })
+            .collect())
+    }
+}
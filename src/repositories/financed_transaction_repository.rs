@@ -0,0 +1,178 @@
+use crate::error::{AppError, TransactionError};
+use crate::model::budget_model::*;
+use crate::repositories::cache_repository::CacheRepository;
+use crate::supabase::error::SupabaseError;
+use crate::supabase::{QueryBuilder, SupabaseClient};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Ukuran halaman saat membaca ledger; PostgREST memotong hasil di `max-rows`
+const PAGE_SIZE: usize = 1000;
+
+/// Ledger transaksi tidak di-cache: pemakaian anggaran harus selalu dibaca dari database
+/// supaya batas kuota tetap akurat.
+#[derive(Clone)]
+pub struct FinancedTransactionRepository {
+    pub supabase_client: Arc<SupabaseClient>,
+    pub cache_repository: Arc<CacheRepository>,
+}
+
+impl FinancedTransactionRepository {
+    pub fn new(
+        supabase_client: Arc<SupabaseClient>,
+        cache_repository: Arc<CacheRepository>,
+    ) -> Self {
+        Self {
+            supabase_client,
+            cache_repository,
+        }
+    }
+
+    /// Filter opsional per promo dan/atau store, urut berdasarkan waktu transaksi
+    pub async fn rep_fetch_all(
+        &self,
+        promo_id: Option<Uuid>,
+        store_id: Option<Uuid>,
+    ) -> Result<Vec<FinancedTransaction>, AppError> {
+        let transactions_from_db = self
+            .fetch_pages(|mut query| {
+                if let Some(promo_id) = promo_id {
+                    query = query.eq("promo_id", &promo_id.to_string());
+                }
+                if let Some(store_id) = store_id {
+                    query = query.eq("store_id", &store_id.to_string());
+                }
+                query
+            })
+            .await?;
+
+        info!(
+            "Berhasil mendapatkan {} financed_transaction dari Supabase.",
+            transactions_from_db.len()
+        );
+
+        Ok(Self::deserialize_rows(transactions_from_db))
+    }
+
+    /// Transaksi dengan `created_at` di `[start, end)`, opsional untuk satu store
+    pub async fn rep_fetch_by_period(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        store_id: Option<Uuid>,
+    ) -> Result<Vec<FinancedTransaction>, AppError> {
+        let (start_raw, end_raw) = (start.to_rfc3339(), end.to_rfc3339());
+        let transactions_from_db = self
+            .fetch_pages(|query| {
+                let query = query.gte_str("created_at", &start_raw).lt_str("created_at", &end_raw);
+                match store_id {
+                    Some(store_id) => query.eq("store_id", &store_id.to_string()),
+                    None => query,
+                }
+            })
+            .await?;
+
+        info!(
+            "Berhasil mendapatkan {} financed_transaction untuk periode {} - {}.",
+            transactions_from_db.len(),
+            start,
+            end
+        );
+
+        Ok(Self::deserialize_rows(transactions_from_db))
+    }
+
+    pub async fn rep_fetch_by_ref(
+        &self,
+        transaction_ref: &str,
+    ) -> Result<Option<FinancedTransaction>, AppError> {
+        let transactions_from_db = self
+            .supabase_client
+            .from::<Value>("financed_transaction")
+            .eq("transaction_ref", transaction_ref)
+            .limit(1)
+            .execute()
+            .await
+            .map_err(|e| TransactionError::DatabaseError(format!("Supabase error: {}", e)))?;
+
+        match transactions_from_db.into_iter().next() {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e))),
+            None => Ok(None),
+        }
+    }
+
+    /// Pemakaian anggaran promo (total dan per store) lewat agregat SQL, bukan dari baris ledger
+    pub async fn rep_fetch_usage(&self, promo_id: Uuid) -> Result<PromoBudgetUsage, AppError> {
+        let result = self
+            .supabase_client
+            .rpc("financed_transaction_usage", &BudgetUsageParams { p_promo_id: promo_id })
+            .await
+            .map_err(|e| TransactionError::DatabaseError(format!("Supabase rpc error: {}", e)))?;
+
+        serde_json::from_value(result)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+
+    pub async fn rep_record(&self, params: RecordTransactionParams) -> Result<RecordOutcome, AppError> {
+        let result = self
+            .supabase_client
+            .rpc("record_financed_transaction", &params)
+            .await
+            .map_err(|e| match e {
+                // Unique index `transaction_ref` menangkap ref yang sama untuk promo berbeda
+                SupabaseError::InsertConflict { .. } => TransactionError::InvalidPayload(format!(
+                    "transaction_ref '{}' already used for another transaction",
+                    params.p_row.transaction_ref
+                )),
+                e => TransactionError::DatabaseError(format!("Supabase rpc error: {}", e)),
+            })?;
+
+        serde_json::from_value(result)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+
+    /// Baca semua halaman sampai halaman kosong, jadi tetap lengkap walau `max-rows` lebih kecil
+    /// dari `PAGE_SIZE`
+    async fn fetch_pages(
+        &self,
+        filter: impl Fn(QueryBuilder<'_, Value>) -> QueryBuilder<'_, Value>,
+    ) -> Result<Vec<Value>, AppError> {
+        let mut rows = Vec::new();
+        loop {
+            let query = self
+                .supabase_client
+                .from::<Value>("financed_transaction")
+                .order("created_at", true)
+                .limit(PAGE_SIZE)
+                .offset(rows.len());
+            let page = match filter(query).execute().await {
+                Ok(page) => page,
+                Err(e) if e.is_not_found() => vec![],
+                Err(e) => {
+                    return Err(TransactionError::DatabaseError(format!("Supabase error: {}", e)).into());
+                }
+            };
+            if page.is_empty() {
+                return Ok(rows);
+            }
+            rows.extend(page);
+        }
+    }
+
+    fn deserialize_rows(rows: Vec<Value>) -> Vec<FinancedTransaction> {
+        rows.into_iter()
+            .filter_map(|item| match serde_json::from_value(item.clone()) {
+                Ok(transaction) => Some(transaction),
+                Err(e) => {
+                    warn!("Failed to deserialize financed_transaction: {}. Data: {:?}", e, item);
+                    None
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,127 @@
+use crate::error::{AppError, LeadError};
+use crate::model::lead_model::*;
+use crate::repositories::cache_repository::CacheRepository;
+use crate::supabase::SupabaseClient;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Lead tidak di-cache: status dan assignment diubah bergantian oleh beberapa sales.
+#[derive(Clone)]
+pub struct LeadRepository {
+    pub supabase_client: Arc<SupabaseClient>,
+    pub cache_repository: Arc<CacheRepository>,
+}
+
+impl LeadRepository {
+    pub fn new(
+        supabase_client: Arc<SupabaseClient>,
+        cache_repository: Arc<CacheRepository>,
+    ) -> Self {
+        Self {
+            supabase_client,
+            cache_repository,
+        }
+    }
+
+    /// Terbaru lebih dulu; filter store, status dan assignee opsional
+    pub async fn rep_fetch_all(
+        &self,
+        store_id: Option<Uuid>,
+        status: Option<LeadStatus>,
+        assigned_to: Option<&str>,
+    ) -> Result<Vec<Lead>, AppError> {
+        let mut query = self
+            .supabase_client
+            .from::<Value>("lead")
+            .order("created_at", false);
+        if let Some(store_id) = store_id {
+            query = query.eq("store_id", &store_id.to_string());
+        }
+        if let Some(status) = status {
+            query = query.eq("status", status.as_str());
+        }
+        if let Some(assigned_to) = assigned_to {
+            query = query.eq("assigned_to", assigned_to);
+        }
+
+        let leads_from_db = match query.execute().await {
+            Ok(rows) => rows,
+            Err(e) if e.is_not_found() => vec![],
+            Err(e) => {
+                return Err(LeadError::DatabaseError(format!("Supabase error: {}", e)).into());
+            }
+        };
+
+        info!("Berhasil mendapatkan {} lead dari Supabase.", leads_from_db.len());
+
+        let leads: Vec<Lead> = leads_from_db
+            .into_iter()
+            .filter_map(|item| match serde_json::from_value(item.clone()) {
+                Ok(lead) => Some(lead),
+                Err(e) => {
+                    warn!("Failed to deserialize lead: {}. Data: {:?}", e, item);
+                    None
+                }
+            })
+            .collect();
+
+        Ok(leads)
+    }
+
+    pub async fn rep_fetch_by_id(&self, id: Uuid) -> Result<Lead, AppError> {
+        let leads_from_db = self
+            .supabase_client
+            .from::<Value>("lead")
+            .eq("id", &id.to_string())
+            .limit(1)
+            .execute()
+            .await
+            .map_err(|e| LeadError::DatabaseError(format!("Supabase error: {}", e)))?;
+
+        let value = leads_from_db
+            .into_iter()
+            .next()
+            .ok_or_else(|| LeadError::NotFound(format!("Lead with id '{}' not found", id)))?;
+
+        serde_json::from_value(value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+
+    pub async fn rep_insert(&self, row: CreateLeadRow) -> Result<Lead, AppError> {
+        let inserted_value = self
+            .supabase_client
+            .from::<Value>("lead")
+            .insert(&row)
+            .await
+            .map_err(|e| LeadError::DatabaseError(format!("Supabase insert error: {}", e)))?;
+
+        serde_json::from_value(inserted_value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+
+    /// Update hanya jika status masih `expected`, supaya dua perubahan bersamaan tidak saling menimpa
+    pub async fn rep_update_if_status(
+        &self,
+        id: Uuid,
+        expected: LeadStatus,
+        row: UpdateLeadRow,
+    ) -> Result<Option<Lead>, AppError> {
+        let updated_vec = self
+            .supabase_client
+            .from::<Value>("lead")
+            .eq("id", &id.to_string())
+            .eq("status", expected.as_str())
+            .update(&row)
+            .await
+            .map_err(|e| LeadError::DatabaseError(format!("Supabase update error: {}", e)))?;
+
+        match updated_vec.into_iter().next() {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e))),
+            None => Ok(None),
+        }
+    }
+}
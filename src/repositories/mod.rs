@@ -1,5 +1,14 @@
+pub mod api_key_repository;
 pub mod cache_repository;
+pub mod company_repository;
+pub mod entity_version_repository;
+pub mod financed_transaction_repository;
+pub mod lead_repository;
 pub mod promo_repository;
 pub mod promo_store_repository;
+pub mod promo_tenor_change_repository;
 pub mod promo_tenor_repository;
+pub mod promo_transition_repository;
+pub mod store_assignment_repository;
 pub mod store_repository;
+pub mod voucher_redemption_repository;
@@ -64,7 +64,7 @@ impl PromoRepository {
             .into_iter()
             .filter_map(|item| {
                 match serde_json::from_value::<Promo>(item.clone()) {
-                    Ok(promo) => Some(promo),
+                    Ok(promo) => Some(promo.with_current_status()),
                     Err(e) => {
                         warn!("Failed to deserialize promo: {}. Data: {:?}", e, item);
                         None
@@ -112,7 +112,7 @@ impl PromoRepository {
         let promo: Promo = serde_json::from_value(promos_from_db[0].clone())
             .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))?;
 
-        Ok(promo)
+        Ok(promo.with_current_status())
     }
 
     pub async fn rep_insert(&self, payload: CreatePromoPayload) -> Result<Promo, AppError> {
@@ -127,7 +127,7 @@ impl PromoRepository {
             .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))?;
 
         self.cache_repository.clear_promo_cache_all().await;
-        Ok(promo)
+        Ok(promo.with_current_status())
     }
 
     pub async fn rep_update_by_id(
@@ -149,8 +149,45 @@ impl PromoRepository {
             .into_iter()
             .next()
             .ok_or_else(|| AppError::Internal("Failed to update promo".to_string()))?;
+        Self::finish_update(promo_value)
+    }
+
+    /// Compare-and-set lifecycle: update hanya berlaku jika lifecycle di database masih `from`,
+    /// sehingga dua transisi paralel dari state yang sama tidak bisa sama-sama menang.
+    pub async fn rep_update_lifecycle(
+        &self,
+        id_promo: Uuid,
+        from: PromoLifecycle,
+        to: PromoLifecycle,
+    ) -> Result<Promo, AppError> {
+        let payload = UpdatePromoPayload {
+            lifecycle: Some(to),
+            ..Default::default()
+        };
+        let updated_vec = self
+            .supabase_client
+            .from::<Value>("promo")
+            .eq("id_promo", &id_promo.to_string())
+            .eq("lifecycle", from.as_str())
+            .update(&payload)
+            .await
+            .map_err(|e| PromoError::DatabaseError(format!("Supabase update error: {}", e)))?;
+
+        self.cache_repository.clear_promo_cache_all().await;
+
+        let promo_value = updated_vec.into_iter().next().ok_or_else(|| {
+            PromoError::Conflict(format!(
+                "promo {} is no longer in {}; it was changed concurrently",
+                id_promo,
+                from.as_str()
+            ))
+        })?;
+        Self::finish_update(promo_value)
+    }
 
-        serde_json::from_value(promo_value)
+    fn finish_update(promo_value: Value) -> Result<Promo, AppError> {
+        serde_json::from_value::<Promo>(promo_value)
+            .map(Promo::with_current_status)
             .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
     }
 
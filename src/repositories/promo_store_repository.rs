@@ -181,6 +181,60 @@ impl PromoStoreRepository {
         Ok(())
     }
 
+    /// Hapus semua relasi milik promo (cascade delete). Cache tidak dibersihkan di sini;
+    /// pemanggil meng-invalidate semua cache terdampak sekaligus lewat `CacheRepository::invalidate`.
+    pub async fn rep_delete_by_promo_id(&self, promo_id: Uuid) -> Result<Vec<PromoStore>, AppError> {
+        self.delete_where("promo_id", promo_id).await
+    }
+
+    /// Hapus semua relasi milik store (cascade delete), tanpa membersihkan cache
+    pub async fn rep_delete_by_store_id(&self, store_id: Uuid) -> Result<Vec<PromoStore>, AppError> {
+        self.delete_where("store_id", store_id).await
+    }
+
+    /// Update langsung by id tanpa membersihkan cache (dipakai cascade delete tenor)
+    pub async fn rep_update_by_id_deferred(
+        &self,
+        id: Uuid,
+        payload: &UpdatePromoStorePayload,
+    ) -> Result<PromoStore, AppError> {
+        let updated_vec = self
+            .supabase_client
+            .from::<Value>("promo_store")
+            .eq("id", &id.to_string())
+            .update(payload)
+            .await
+            .map_err(|e| PromoStoreError::DatabaseError(format!("Supabase update error: {}", e)))?;
+
+        let promo_store_value = updated_vec
+            .into_iter()
+            .next()
+            .ok_or_else(|| PromoStoreError::NotFound(format!("PromoStore with id '{}' not found", id)))?;
+
+        serde_json::from_value(promo_store_value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+
+    async fn delete_where(&self, column: &str, id: Uuid) -> Result<Vec<PromoStore>, AppError> {
+        let deleted = self
+            .supabase_client
+            .from::<Value>("promo_store")
+            .eq(column, &id.to_string())
+            .delete()
+            .await
+            .map_err(|e| PromoStoreError::DatabaseError(format!("Supabase delete error: {}", e)))?;
+
+        let mut promo_stores = Vec::with_capacity(deleted.len());
+        for snapshot in deleted {
+            match serde_json::from_value::<PromoStore>(snapshot.clone()) {
+                Ok(promo_store) => promo_stores.push(promo_store),
+                Err(e) => warn!("Failed to deserialize deleted promo_store: {}. Data: {:?}", e, snapshot),
+            }
+        }
+        info!("Berhasil menghapus {} promo_store dengan {} {}.", promo_stores.len(), column, id);
+        Ok(promo_stores)
+    }
+
     pub async fn rep_fetch_by_promo_id(&self, promo_id: Uuid) -> Result<Vec<PromoStore>, AppError> {
         let cache = self.cache_repository.get_promo_store_cache_all();
         let cache_data = cache.read().await;
@@ -0,0 +1,149 @@
+use crate::error::{AppError, PromoTenorError};
+use crate::model::tenor_change_model::*;
+use crate::repositories::cache_repository::CacheRepository;
+use crate::supabase::SupabaseClient;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Jadwal perubahan tenor tidak di-cache: job scheduler harus selalu melihat status terbaru.
+#[derive(Clone)]
+pub struct PromoTenorChangeRepository {
+    pub supabase_client: Arc<SupabaseClient>,
+    pub cache_repository: Arc<CacheRepository>,
+}
+
+impl PromoTenorChangeRepository {
+    pub fn new(
+        supabase_client: Arc<SupabaseClient>,
+        cache_repository: Arc<CacheRepository>,
+    ) -> Self {
+        Self {
+            supabase_client,
+            cache_repository,
+        }
+    }
+
+    /// Urut berdasarkan `effective_at`; filter status dan tenor opsional
+    pub async fn rep_fetch_all(
+        &self,
+        status: Option<TenorChangeStatus>,
+        promo_tenor_id: Option<Uuid>,
+    ) -> Result<Vec<PromoTenorChange>, AppError> {
+        let mut query = self
+            .supabase_client
+            .from::<Value>("promo_tenor_change")
+            .order("effective_at", true);
+        if let Some(status) = status {
+            query = query.eq("status", status.as_str());
+        }
+        if let Some(promo_tenor_id) = promo_tenor_id {
+            query = query.eq("promo_tenor_id", &promo_tenor_id.to_string());
+        }
+
+        let changes_from_db = match query.execute().await {
+            Ok(rows) => rows,
+            Err(e) if e.is_not_found() => vec![],
+            Err(e) => {
+                return Err(PromoTenorError::DatabaseError(format!("Supabase error: {}", e)).into());
+            }
+        };
+
+        info!("Berhasil mendapatkan {} promo_tenor_change dari Supabase.", changes_from_db.len());
+
+        let changes: Vec<PromoTenorChange> = changes_from_db
+            .into_iter()
+            .filter_map(|item| match serde_json::from_value(item.clone()) {
+                Ok(change) => Some(change),
+                Err(e) => {
+                    warn!("Failed to deserialize promo_tenor_change: {}. Data: {:?}", e, item);
+                    None
+                }
+            })
+            .collect();
+
+        Ok(changes)
+    }
+
+    pub async fn rep_fetch_by_id(&self, id: Uuid) -> Result<PromoTenorChange, AppError> {
+        let changes_from_db = self
+            .supabase_client
+            .from::<Value>("promo_tenor_change")
+            .eq("id", &id.to_string())
+            .limit(1)
+            .execute()
+            .await
+            .map_err(|e| PromoTenorError::DatabaseError(format!("Supabase error: {}", e)))?;
+
+        let value = changes_from_db.into_iter().next().ok_or_else(|| {
+            PromoTenorError::NotFound(format!("Scheduled change with id '{}' not found", id))
+        })?;
+
+        serde_json::from_value(value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+
+    pub async fn rep_insert(
+        &self,
+        payload: CreatePromoTenorChangePayload,
+    ) -> Result<PromoTenorChange, AppError> {
+        let inserted_value = self
+            .supabase_client
+            .from::<Value>("promo_tenor_change")
+            .insert(&payload)
+            .await
+            .map_err(|e| PromoTenorError::DatabaseError(format!("Supabase insert error: {}", e)))?;
+
+        serde_json::from_value(inserted_value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+
+    /// Update hanya jika status masih salah satu `from`, supaya approve, cancel dan apply
+    /// tidak saling menimpa
+    pub async fn rep_update_if_status(
+        &self,
+        id: Uuid,
+        from: &[TenorChangeStatus],
+        payload: UpdatePromoTenorChangePayload,
+    ) -> Result<Option<PromoTenorChange>, AppError> {
+        let from: Vec<&str> = from.iter().map(|s| s.as_str()).collect();
+        let updated_vec = self
+            .supabase_client
+            .from::<Value>("promo_tenor_change")
+            .eq("id", &id.to_string())
+            .r#in("status", &from)
+            .update(&payload)
+            .await
+            .map_err(|e| PromoTenorError::DatabaseError(format!("Supabase update error: {}", e)))?;
+
+        match updated_vec.into_iter().next() {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e))),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn rep_update_by_id(
+        &self,
+        id: Uuid,
+        payload: UpdatePromoTenorChangePayload,
+    ) -> Result<PromoTenorChange, AppError> {
+        let updated_vec = self
+            .supabase_client
+            .from::<Value>("promo_tenor_change")
+            .eq("id", &id.to_string())
+            .update(&payload)
+            .await
+            .map_err(|e| PromoTenorError::DatabaseError(format!("Supabase update error: {}", e)))?;
+
+        let value = updated_vec
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::Internal("Failed to update promo_tenor_change".to_string()))?;
+
+        serde_json::from_value(value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+}
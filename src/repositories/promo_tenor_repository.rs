@@ -176,22 +176,71 @@ impl PromoTenorRepository {
         Ok(())
     }
 
-    pub async fn rep_fetch_by_store_id(&self, store_id: Uuid) -> Result<Vec<PromoTenor>, AppError> {
+    /// Hapus semua tenor milik promo (cascade delete). Cache tidak dibersihkan di sini;
+    /// pemanggil meng-invalidate semua cache terdampak sekaligus lewat `CacheRepository::invalidate`.
+    pub async fn rep_delete_by_promo_id(&self, promo_id: Uuid) -> Result<Vec<PromoTenor>, AppError> {
+        let deleted = self
+            .supabase_client
+            .from::<Value>("promo_tenor")
+            .eq("promo_id", &promo_id.to_string())
+            .delete()
+            .await
+            .map_err(|e| PromoTenorError::DatabaseError(format!("Supabase delete error: {}", e)))?;
+
+        let mut promo_tenors = Vec::with_capacity(deleted.len());
+        for snapshot in deleted {
+            match serde_json::from_value::<PromoTenor>(snapshot.clone()) {
+                Ok(promo_tenor) => promo_tenors.push(promo_tenor),
+                Err(e) => warn!("Failed to deserialize deleted promo_tenor: {}. Data: {:?}", e, snapshot),
+            }
+        }
+        info!("Berhasil menghapus {} promo_tenor milik promo {}.", promo_tenors.len(), promo_id);
+        Ok(promo_tenors)
+    }
+
+    /// Tenor yang berlaku di store dengan nilai efektif setelah override PromoStore.
+    /// Link yang kuota anggaran store-nya habis dilewati.
+    pub async fn rep_fetch_by_store_id(&self, store_id: Uuid) -> Result<Vec<StorePromoTenor>, AppError> {
         let promo_store_cache = self.cache_repository.get_promo_store_cache_all();
         let promo_store_data = promo_store_cache.read().await;
-        let promo_stores: Vec<_> = promo_store_data.iter().filter(|ps| ps.store_id == store_id).collect();
+        let promo_stores: Vec<_> = promo_store_data
+            .iter()
+            .filter(|ps| ps.store_id == store_id && !ps.budget_exhausted)
+            .collect();
 
         let tenor_cache = self.cache_repository.get_promo_tenor_cache_all();
         let tenor_data = tenor_cache.read().await;
 
         let mut result = Vec::new();
         for ps in promo_stores {
-            let tenors: Vec<PromoTenor> = tenor_data.iter().filter(|t| t.promo_id == ps.promo_id).cloned().collect();
-            
-            // Filter by is_available instead of tenor_ids
-            result.extend(tenors.into_iter().filter(|t| t.is_available));
+            // tenor_ids None = semua tenor promo, [] = tidak ada tenor
+            result.extend(
+                tenor_data
+                    .iter()
+                    .filter(|t| t.promo_id == ps.promo_id)
+                    .filter(|t| t.is_available && ps.includes_tenor(t.id))
+                    .map(|t| ps.effective_tenor(t)),
+            );
         }
 
         Ok(result)
     }
+
+    /// Seperti `rep_fetch_by_store_id`, tetapi hanya tenor dari promo yang sedang live
+    pub async fn rep_fetch_live_by_store_id(&self, store_id: Uuid) -> Result<Vec<StorePromoTenor>, AppError> {
+        let tenors = self.rep_fetch_by_store_id(store_id).await?;
+
+        let promo_cache = self.cache_repository.get_promo_cache_all();
+        let promo_data = promo_cache.read().await;
+        let live_promo_ids: Vec<Uuid> = promo_data
+            .iter()
+            .filter(|p| p.is_live())
+            .map(|p| p.id_promo)
+            .collect();
+
+        Ok(tenors
+            .into_iter()
+            .filter(|t| live_promo_ids.contains(&t.promo_tenor.promo_id))
+            .collect())
+    }
 }
@@ -0,0 +1,81 @@
+use crate::error::{AppError, PromoError};
+use crate::model::promo_model::*;
+use crate::repositories::cache_repository::CacheRepository;
+use crate::supabase::SupabaseClient;
+use crate::supabase::error::SupabaseError;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Log transisi lifecycle bersifat append-only dan hanya dibaca di jalur admin, jadi tidak di-cache.
+#[derive(Clone)]
+pub struct PromoTransitionRepository {
+    pub supabase_client: Arc<SupabaseClient>,
+    pub cache_repository: Arc<CacheRepository>,
+}
+
+impl PromoTransitionRepository {
+    pub fn new(
+        supabase_client: Arc<SupabaseClient>,
+        cache_repository: Arc<CacheRepository>,
+    ) -> Self {
+        Self {
+            supabase_client,
+            cache_repository,
+        }
+    }
+
+    pub async fn rep_fetch_by_promo_id(&self, promo_id: Uuid) -> Result<Vec<PromoTransition>, AppError> {
+        let transitions_from_db = match self
+            .supabase_client
+            .from::<Value>("promo_transition")
+            .eq("promo_id", &promo_id.to_string())
+            .order("created_at", true)
+            .execute()
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) if e.is_not_found() => vec![],
+            Err(e) => {
+                return Err(PromoError::DatabaseError(format!("Supabase error: {}", e)).into());
+            }
+        };
+
+        info!(
+            "Berhasil mendapatkan {} promo_transition untuk promo {}.",
+            transitions_from_db.len(),
+            promo_id
+        );
+
+        let transitions: Vec<PromoTransition> = transitions_from_db
+            .into_iter()
+            .filter_map(|item| match serde_json::from_value(item.clone()) {
+                Ok(transition) => Some(transition),
+                Err(e) => {
+                    warn!("Failed to deserialize promo_transition: {}. Data: {:?}", e, item);
+                    None
+                }
+            })
+            .collect();
+
+        Ok(transitions)
+    }
+
+    pub async fn rep_insert(
+        &self,
+        payload: CreatePromoTransitionPayload,
+    ) -> Result<PromoTransition, AppError> {
+        let inserted_value = self
+            .supabase_client
+            .from::<Value>("promo_transition")
+            .insert(&payload)
+            .await
+            .map_err(|e: SupabaseError| {
+                PromoError::DatabaseError(format!("Supabase insert error: {}", e))
+            })?;
+
+        serde_json::from_value(inserted_value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+}
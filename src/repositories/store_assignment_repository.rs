@@ -0,0 +1,100 @@
+use crate::error::{AppError, AssignmentError};
+use crate::model::store_assignment_model::*;
+use crate::supabase::SupabaseClient;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Assignment tidak di-cache: pencabutan akses harus langsung berlaku.
+#[derive(Clone)]
+pub struct StoreAssignmentRepository {
+    pub supabase_client: Arc<SupabaseClient>,
+}
+
+impl StoreAssignmentRepository {
+    pub fn new(supabase_client: Arc<SupabaseClient>) -> Self {
+        Self { supabase_client }
+    }
+
+    /// Semua assignment, atau hanya milik satu user
+    pub async fn rep_fetch_all(&self, user_id: Option<&str>) -> Result<Vec<StoreAssignment>, AppError> {
+        let mut query = self
+            .supabase_client
+            .from::<Value>("store_assignment")
+            .order("created_at", true);
+        if let Some(user_id) = user_id {
+            query = query.eq("user_id", user_id);
+        }
+
+        let assignments_from_db = match query.execute().await {
+            Ok(rows) => rows,
+            Err(e) if e.is_not_found() => vec![],
+            Err(e) => {
+                return Err(AssignmentError::DatabaseError(format!("Supabase error: {}", e)).into());
+            }
+        };
+
+        info!(
+            "Berhasil mendapatkan {} store assignment dari Supabase.",
+            assignments_from_db.len()
+        );
+
+        let assignments: Vec<StoreAssignment> = assignments_from_db
+            .into_iter()
+            .filter_map(|item| match serde_json::from_value(item.clone()) {
+                Ok(assignment) => Some(assignment),
+                Err(e) => {
+                    warn!("Failed to deserialize store assignment: {}. Data: {:?}", e, item);
+                    None
+                }
+            })
+            .collect();
+
+        Ok(assignments)
+    }
+
+    pub async fn rep_insert(&self, row: StoreAssignmentRow) -> Result<StoreAssignment, AppError> {
+        let inserted_value = self
+            .supabase_client
+            .from::<Value>("store_assignment")
+            .insert(&row)
+            .await
+            .map_err(|e| AssignmentError::DatabaseError(format!("Supabase insert error: {}", e)))?;
+
+        serde_json::from_value(inserted_value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+
+    pub async fn rep_delete_by_id(&self, id: Uuid) -> Result<(), AppError> {
+        let deleted = self
+            .supabase_client
+            .from::<Value>("store_assignment")
+            .eq("id", &id.to_string())
+            .delete()
+            .await
+            .map_err(|e| AssignmentError::DatabaseError(format!("Supabase delete error: {}", e)))?;
+
+        if deleted.is_empty() {
+            return Err(AssignmentError::NotFound(format!("Store assignment with id '{}' not found", id)).into());
+        }
+        Ok(())
+    }
+
+    /// Cascade dari delete store; mengembalikan assignment yang terhapus
+    pub async fn rep_delete_by_store_id(&self, store_id: Uuid) -> Result<Vec<StoreAssignment>, AppError> {
+        let deleted = self
+            .supabase_client
+            .from::<Value>("store_assignment")
+            .eq("store_id", &store_id.to_string())
+            .delete()
+            .await
+            .map_err(|e| AssignmentError::DatabaseError(format!("Supabase delete error: {}", e)))?;
+
+        info!("Berhasil menghapus {} store assignment milik store {}.", deleted.len(), store_id);
+        Ok(deleted
+            .into_iter()
+            .filter_map(|item| serde_json::from_value(item).ok())
+            .collect())
+    }
+}
@@ -189,6 +189,36 @@ impl StoreRepository {
         Ok(store)
     }
 
+    /// Set `company_id` (dan nama company kanonik) untuk satu store berdasarkan id
+    pub async fn rep_set_company(
+        &self,
+        store_id: Uuid,
+        company_id: Uuid,
+        company: &str,
+    ) -> Result<Store, AppError> {
+        let updated = self
+            .supabase_client
+            .from::<Value>("store")
+            .eq("id", &store_id.to_string())
+            .update(&serde_json::json!({ "company_id": company_id, "company": company }))
+            .await
+            .map_err(|e: SupabaseError| {
+                AppError::from(StoreError::DatabaseError(format!("Supabase error during update: {}", e)))
+            })?;
+
+        let store_value = updated
+            .into_iter()
+            .next()
+            .ok_or_else(|| StoreError::NotFound(format!("Store with id '{}' not found", store_id)))?;
+
+        let store: Store = serde_json::from_value(store_value)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))?;
+
+        self.cache_repository.clear_store_cache_all().await;
+
+        Ok(store)
+    }
+
     pub async fn rep_delete(&self, route: &str) -> Result<(), AppError> {
         // Get id from cache or database
         let id = {
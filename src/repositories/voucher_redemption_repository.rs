@@ -0,0 +1,129 @@
+use crate::error::{AppError, VoucherError};
+use crate::model::voucher_model::*;
+use crate::repositories::cache_repository::CacheRepository;
+use crate::supabase::SupabaseClient;
+use crate::supabase::error::SupabaseError;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Redemption tidak di-cache: jumlah pemakaian harus selalu dibaca dari database
+/// supaya batas kuota tetap akurat.
+#[derive(Clone)]
+pub struct VoucherRedemptionRepository {
+    pub supabase_client: Arc<SupabaseClient>,
+    pub cache_repository: Arc<CacheRepository>,
+}
+
+impl VoucherRedemptionRepository {
+    pub fn new(
+        supabase_client: Arc<SupabaseClient>,
+        cache_repository: Arc<CacheRepository>,
+    ) -> Self {
+        Self {
+            supabase_client,
+            cache_repository,
+        }
+    }
+
+    pub async fn rep_fetch_by_voucher(&self, voucher_code: &str) -> Result<Vec<VoucherRedemption>, AppError> {
+        let redemptions_from_db = self
+            .supabase_client
+            .from::<Value>("voucher_redemption")
+            .eq("voucher_code", voucher_code)
+            .execute()
+            .await
+            .map_err(|e: SupabaseError| {
+                if e.is_not_found() {
+                    AppError::from(VoucherError::NotFound(format!(
+                        "No redemptions found for voucher '{}'",
+                        voucher_code
+                    )))
+                } else {
+                    AppError::from(VoucherError::DatabaseError(format!("Supabase error: {}", e)))
+                }
+            })?;
+
+        info!(
+            "Berhasil mendapatkan {} voucher_redemption untuk voucher {}.",
+            redemptions_from_db.len(),
+            voucher_code
+        );
+
+        let redemptions: Vec<VoucherRedemption> = redemptions_from_db
+            .into_iter()
+            .filter_map(|item| match serde_json::from_value(item.clone()) {
+                Ok(redemption) => Some(redemption),
+                Err(e) => {
+                    warn!("Failed to deserialize voucher_redemption: {}. Data: {:?}", e, item);
+                    None
+                }
+            })
+            .collect();
+
+        Ok(redemptions)
+    }
+
+    pub async fn rep_fetch_by_redemption_key(
+        &self,
+        redemption_key: &str,
+    ) -> Result<Option<VoucherRedemption>, AppError> {
+        let redemptions_from_db = self
+            .supabase_client
+            .from::<Value>("voucher_redemption")
+            .eq("redemption_key", redemption_key)
+            .limit(1)
+            .execute()
+            .await
+            .map_err(|e| VoucherError::DatabaseError(format!("Supabase error: {}", e)))?;
+
+        match redemptions_from_db.into_iter().next() {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e))),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn rep_count_by_voucher(&self, voucher_code: &str) -> Result<usize, AppError> {
+        self.supabase_client
+            .from::<Value>("voucher_redemption")
+            .eq("voucher_code", voucher_code)
+            .count()
+            .await
+            .map_err(|e| VoucherError::DatabaseError(format!("Supabase error: {}", e)).into())
+    }
+
+    pub async fn rep_count_by_voucher_and_store(
+        &self,
+        voucher_code: &str,
+        store_id: Uuid,
+    ) -> Result<usize, AppError> {
+        self.supabase_client
+            .from::<Value>("voucher_redemption")
+            .eq("voucher_code", voucher_code)
+            .eq("store_id", &store_id.to_string())
+            .count()
+            .await
+            .map_err(|e| VoucherError::DatabaseError(format!("Supabase error: {}", e)).into())
+    }
+
+    /// Cek kuota + insert atomik lewat fungsi database `redeem_voucher` (lihat `migrations/`)
+    pub async fn rep_redeem(&self, params: RedeemVoucherParams) -> Result<RedeemOutcome, AppError> {
+        let result = self
+            .supabase_client
+            .rpc("redeem_voucher", &params)
+            .await
+            .map_err(|e| match e {
+                SupabaseError::InsertConflict { .. } => VoucherError::Conflict(format!(
+                    "redemption_key '{}' already used for another voucher",
+                    params.p_redemption_key
+                )),
+                e => VoucherError::DatabaseError(format!("Supabase rpc error: {}", e)),
+            })?;
+
+        serde_json::from_value(result)
+            .map_err(|e| AppError::Internal(format!("Deserialization error: {}", e)))
+    }
+}
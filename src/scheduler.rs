@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use std::future::Future;
+use std::time::Duration;
+use tracing::info;
+
+/// Loop bersama untuk background job: membaca `{PREFIX}_ENABLED` dan
+/// `{PREFIX}_INTERVAL_SECONDS`, lalu menjalankan `tick` berulang. `tick`
+/// mengembalikan waktu jatuh tempo berikutnya (jika ada) supaya job bangun tepat waktu.
+pub async fn run_loop<F, Fut>(name: &str, env_prefix: &str, mut tick: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Option<DateTime<Utc>>>,
+{
+    let enabled = std::env::var(format!("{}_ENABLED", env_prefix))
+        .unwrap_or_else(|_| "true".to_string())
+        .parse()
+        .unwrap_or(true);
+    if !enabled {
+        info!("{} disabled", name);
+        return;
+    }
+
+    let max_interval = std::env::var(format!("{}_INTERVAL_SECONDS", env_prefix))
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(60));
+    info!("{} started (max interval {:?})", name, max_interval);
+
+    loop {
+        let next_due = tick().await;
+
+        // Tidur sampai jatuh tempo berikutnya (+1 detik), maksimal `max_interval`
+        let sleep_for = next_due
+            .and_then(|due| (due - Utc::now()).to_std().ok())
+            .map(|until| (until + Duration::from_secs(1)).min(max_interval))
+            .unwrap_or(max_interval);
+        tokio::time::sleep(sleep_for).await;
+    }
+}
@@ -0,0 +1,233 @@
+use crate::access_policy::{AccessPolicy, RoutePolicies};
+use crate::business_time::parse_business_datetime;
+use crate::constants::ANY_ROLE;
+use crate::error::{ApiKeyError, AppError};
+use crate::middleware::Actor;
+use crate::model::api_key_model::*;
+use crate::rate_limiter::RateLimiter;
+use crate::repositories::api_key_repository::ApiKeyRepository;
+use chrono::Utc;
+use dashmap::DashMap;
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Banyak karakter key mentah (termasuk `mpk_`) yang disimpan sebagai `key_prefix`
+const KEY_PREFIX_LEN: usize = 12;
+
+pub struct ApiKeyService {
+    repo: Arc<ApiKeyRepository>,
+    limiter: RateLimiter,
+    default_quota: usize,
+    usage: DashMap<Uuid, ApiKeyUsage>,
+    /// Template route yang dideklarasikan di `main.rs`, untuk memvalidasi `routes` key
+    routes: OnceLock<RoutePolicies>,
+}
+
+impl ApiKeyService {
+    pub fn new(repo: Arc<ApiKeyRepository>) -> Self {
+        let default_quota = std::env::var("API_KEY_RATE_LIMIT_REQUESTS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(600);
+        let window = std::env::var("API_KEY_RATE_LIMIT_WINDOW_SECONDS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(60);
+        info!(
+            "API key rate limiter configured: {} requests per {} seconds per key",
+            default_quota, window
+        );
+        Self {
+            repo,
+            limiter: RateLimiter::new(default_quota, Duration::from_secs(window)),
+            default_quota,
+            usage: DashMap::new(),
+            routes: OnceLock::new(),
+        }
+    }
+
+    /// Dipanggil sekali setelah semua route terdaftar
+    pub fn set_routes(&self, routes: RoutePolicies) {
+        if self.routes.set(routes).is_err() {
+            warn!("API key route registry already set");
+        }
+    }
+
+    pub async fn ser_get_api_keys(&self) -> Result<Vec<ApiKey>, AppError> {
+        self.repo.rep_fetch_all().await
+    }
+
+    /// Buat key baru; key mentah hanya ada di response ini
+    pub async fn ser_issue_api_key(
+        &self,
+        payload: CreateApiKeyPayload,
+        actor: &Actor,
+    ) -> Result<IssuedApiKey, AppError> {
+        let name = payload.name.trim().to_string();
+        if name.is_empty() {
+            return Err(ApiKeyError::InvalidPayload("name must not be empty".to_string()).into());
+        }
+        if payload.roles.is_empty() && payload.routes.is_empty() {
+            return Err(ApiKeyError::InvalidPayload("at least one role or route is required".to_string()).into());
+        }
+        if let Some(role) = payload.roles.iter().find(|r| !ANY_ROLE.contains(&r.as_str())) {
+            return Err(ApiKeyError::InvalidPayload(format!(
+                "unknown role '{}', expected one of: {}",
+                role,
+                ANY_ROLE.join(", ")
+            ))
+            .into());
+        }
+        let declared = self
+            .routes
+            .get()
+            .ok_or_else(|| AppError::Internal("API key route registry is not initialised".to_string()))?;
+        if let Some(route) = payload
+            .routes
+            .iter()
+            .find(|r| declared.get(r.as_str()).is_none_or(|policy| policy.is_public()))
+        {
+            return Err(ApiKeyError::InvalidPayload(format!(
+                "route '{}' must be a protected route template such as /record-transaction or /get-promo-tenor/{{id}}",
+                route
+            ))
+            .into());
+        }
+        if payload.rate_limit_requests.is_some_and(|n| n <= 0) {
+            return Err(ApiKeyError::InvalidPayload("rate_limit_requests must be positive".to_string()).into());
+        }
+        if let Some(raw) = &payload.expires_at {
+            let expiry = parse_business_datetime(raw, true)
+                .ok_or_else(|| ApiKeyError::InvalidPayload(format!("invalid expires_at '{}'", raw)))?;
+            if expiry <= Utc::now() {
+                return Err(ApiKeyError::InvalidPayload("expires_at must be in the future".to_string()).into());
+            }
+        }
+
+        let key = format!(
+            "{}{}{}",
+            API_KEY_PREFIX,
+            Uuid::new_v4().simple(),
+            Uuid::new_v4().simple()
+        );
+        let created = self
+            .repo
+            .rep_insert(ApiKeyRow {
+                name,
+                key_prefix: key[..KEY_PREFIX_LEN].to_string(),
+                key_hash: Self::hash_key(&key),
+                roles: payload.roles,
+                routes: payload.routes,
+                rate_limit_requests: payload.rate_limit_requests,
+                expires_at: payload.expires_at,
+                created_by: actor.user_id.clone(),
+            })
+            .await?;
+
+        info!("API key {} ({}) dibuat oleh {}.", created.id, created.name, actor.user_id);
+        Ok(IssuedApiKey { api_key: created, key })
+    }
+
+    pub async fn ser_revoke_api_key(&self, id: Uuid, actor: &Actor) -> Result<ApiKey, AppError> {
+        let existing = self.repo.rep_fetch_by_id(id).await?;
+        let revoke = RevokeApiKeyRow {
+            revoked_at: Utc::now().to_rfc3339(),
+            revoked_by: actor.user_id.clone(),
+        };
+        let revoked = self.repo.rep_revoke(id, revoke).await?.ok_or_else(|| {
+            ApiKeyError::Conflict(format!("API key '{}' was already revoked", existing.name))
+        })?;
+
+        info!("API key {} ({}) dicabut oleh {}.", id, revoked.name, actor.user_id);
+        Ok(revoked)
+    }
+
+    /// Autentikasi header `X-Api-Key` untuk route `path` dengan `policy`-nya.
+    /// Key dengan `routes` hanya boleh memanggil route itu, dan role key harus memenuhi
+    /// `AccessPolicy::Roles` seperti JWT. Keduanya berlaku bersamaan.
+    pub async fn ser_authenticate(
+        &self,
+        raw_key: &str,
+        path: Option<&str>,
+        policy: AccessPolicy,
+    ) -> Result<Actor, AppError> {
+        let key = self
+            .repo
+            .rep_fetch_by_hash(&Self::hash_key(raw_key.trim()))
+            .await?
+            .ok_or_else(|| AppError::InvalidToken("Invalid API key".to_string()))?;
+
+        if !key.is_active_at(Utc::now()) {
+            self.record(key.id, |u| u.rejected += 1);
+            return Err(AppError::InvalidToken("API key is expired or revoked".to_string()));
+        }
+
+        if !key.routes.is_empty() && !path.is_some_and(|p| key.routes.iter().any(|r| r == p)) {
+            self.record(key.id, |u| u.rejected += 1);
+            return Err(AppError::Forbidden(format!(
+                "API key '{}' is not allowed to call this route",
+                key.name
+            )));
+        }
+        // Route ber-role selalu butuh role key yang cocok; key tanpa role hanya untuk route `Authenticated`
+        if let AccessPolicy::Roles(allowed) = policy
+            && !key.roles.iter().any(|r| allowed.contains(&r.as_str()))
+        {
+            self.record(key.id, |u| u.rejected += 1);
+            return Err(AppError::Forbidden(format!(
+                "This endpoint requires one of the roles: {}",
+                allowed.join(", ")
+            )));
+        }
+
+        let quota = key
+            .rate_limit_requests
+            .map(|n| n as usize)
+            .unwrap_or(self.default_quota);
+        if self.limiter.try_acquire(&key.id.to_string(), quota).is_none() {
+            self.record(key.id, |u| u.rate_limited += 1);
+            warn!(api_key = %key.id, "API key rate limit exceeded");
+            return Err(AppError::TooManyRequests("Too many requests".to_string()));
+        }
+
+        self.record(key.id, |u| {
+            u.requests += 1;
+            u.last_used_at = Some(Utc::now().to_rfc3339());
+        });
+        Ok(Actor {
+            user_id: key.actor_id(),
+            roles: key.roles,
+        })
+    }
+
+    /// Pemakaian per key sejak proses start
+    pub fn ser_usage(&self) -> Vec<ApiKeyUsage> {
+        let mut usage: Vec<ApiKeyUsage> = self.usage.iter().map(|u| u.value().clone()).collect();
+        usage.sort_by_key(|u| u.id);
+        usage
+    }
+
+    /// Total counter semua key untuk `/metrics`
+    pub fn ser_usage_metrics(&self) -> ApiKeyMetrics {
+        let mut metrics = ApiKeyMetrics::default();
+        for usage in &self.ser_usage() {
+            metrics.add(usage);
+        }
+        metrics
+    }
+
+    fn record(&self, id: Uuid, update: impl FnOnce(&mut ApiKeyUsage)) {
+        let mut entry = self.usage.entry(id).or_insert_with(|| ApiKeyUsage {
+            id,
+            ..Default::default()
+        });
+        update(&mut entry);
+    }
+
+    fn hash_key(raw_key: &str) -> String {
+        format!("{:x}", Sha256::digest(raw_key.as_bytes()))
+    }
+}
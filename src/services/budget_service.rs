@@ -0,0 +1,322 @@
+use crate::error::{AppError, PromoError, PromoStoreError, TransactionError};
+use crate::middleware::Actor;
+use crate::model::budget_model::*;
+use crate::model::promo_model::{Promo, UpdatePromoPayload};
+use crate::model::promo_store_model::{PromoStore, UpdatePromoStorePayload};
+use crate::model::store_assignment_model::StoreScope;
+use crate::repositories::financed_transaction_repository::FinancedTransactionRepository;
+use crate::repositories::promo_repository::PromoRepository;
+use crate::repositories::promo_store_repository::PromoStoreRepository;
+use crate::repositories::promo_tenor_repository::PromoTenorRepository;
+use crate::repositories::store_repository::StoreRepository;
+use crate::services::installment_service::InstallmentService;
+use crate::services::store_assignment_service::StoreAssignmentService;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+pub struct BudgetService {
+    repo: Arc<FinancedTransactionRepository>,
+    promo_repo: Arc<PromoRepository>,
+    promo_store_repo: Arc<PromoStoreRepository>,
+    promo_tenor_repo: Arc<PromoTenorRepository>,
+    store_repo: Arc<StoreRepository>,
+}
+
+impl BudgetService {
+    pub fn new(
+        repo: Arc<FinancedTransactionRepository>,
+        promo_repo: Arc<PromoRepository>,
+        promo_store_repo: Arc<PromoStoreRepository>,
+        promo_tenor_repo: Arc<PromoTenorRepository>,
+        store_repo: Arc<StoreRepository>,
+    ) -> Self {
+        Self {
+            repo,
+            promo_repo,
+            promo_store_repo,
+            promo_tenor_repo,
+            store_repo,
+        }
+    }
+
+    /// Non-admin hanya melihat transaksi store dalam cakupannya
+    pub async fn ser_get_transactions(
+        &self,
+        promo_id: Option<Uuid>,
+        store_id: Option<Uuid>,
+        scope: &StoreScope,
+    ) -> Result<Vec<FinancedTransaction>, AppError> {
+        if let Some(store_id) = store_id {
+            StoreAssignmentService::ensure_store(scope, store_id)?;
+        }
+        let mut transactions = self.repo.rep_fetch_all(promo_id, store_id).await?;
+        transactions.retain(|t| scope.allows_store(t.store_id));
+        Ok(transactions)
+    }
+
+    /// Catat transaksi pembiayaan ke ledger memakai term efektif store saat ini.
+    /// Ditolak jika promo tidak berlaku di store atau transaksi melewati batas anggaran;
+    /// promo/link yang kuotanya habis setelah transaksi ini langsung ditandai `budget_exhausted`.
+    pub async fn ser_record_transaction(
+        &self,
+        payload: RecordTransactionPayload,
+        actor: &Actor,
+        scope: &StoreScope,
+    ) -> Result<RecordTransactionResult, AppError> {
+        if payload.transaction_ref.trim().is_empty() {
+            return Err(TransactionError::InvalidPayload("transaction_ref is required".to_string()).into());
+        }
+        if payload.amount <= 0 {
+            return Err(TransactionError::InvalidPayload(format!(
+                "Amount must be greater than 0, got: {}",
+                payload.amount
+            ))
+            .into());
+        }
+
+        let store = self.store_repo.rep_fetch_by_route(&payload.store_route).await?;
+        StoreAssignmentService::ensure_store(scope, store.id)?;
+
+        // Jalur cepat untuk retry; request paralel dengan ref yang sama ditangani fungsi database
+        if let Some(existing) = self.repo.rep_fetch_by_ref(&payload.transaction_ref).await? {
+            return Self::replay(existing, store.id, &payload);
+        }
+
+        let store_tenor = self
+            .promo_tenor_repo
+            .rep_fetch_by_store_id(store.id)
+            .await?
+            .into_iter()
+            .find(|st| st.promo_tenor.id == payload.promo_tenor_id)
+            .ok_or_else(|| {
+                TransactionError::NotApplicable(format!(
+                    "promo tenor '{}' is not available at store '{}'",
+                    payload.promo_tenor_id, payload.store_route
+                ))
+            })?;
+        let tenor = &store_tenor.promo_tenor;
+
+        let promo = self.promo_repo.rep_get_by_id(tenor.promo_id).await?;
+        if promo.budget_exhausted {
+            return Err(TransactionError::BudgetExceeded("promo budget is exhausted".to_string()).into());
+        }
+        if !promo.is_live() {
+            return Err(TransactionError::NotApplicable("promo is not live".to_string()).into());
+        }
+        if tenor.min_transaction > payload.amount {
+            return Err(TransactionError::NotApplicable(format!(
+                "min_transaction not met (minimum {})",
+                tenor.min_transaction
+            ))
+            .into());
+        }
+
+        let installment = InstallmentService::calculate_store_installment(&promo, &store_tenor, payload.amount)?;
+        let link = self.links_of(promo.id_promo).await?.into_iter().find(|ps| ps.store_id == store.id);
+
+        let row = CreateFinancedTransactionRow::from_installment(
+            payload.transaction_ref.clone(),
+            store.id,
+            tenor.subsidi,
+            &installment,
+            payload.amount,
+            Some(actor.user_id.clone()),
+        );
+        let params = RecordTransactionParams::new(row, promo.budget_caps(), link.as_ref().map(|l| l.budget_caps()));
+        let (transaction, promo_usage, store_usage) = match self.repo.rep_record(params).await? {
+            RecordOutcome::Recorded {
+                transaction,
+                promo_usage,
+                store_usage,
+            } => (transaction, promo_usage, store_usage),
+            RecordOutcome::Replayed { transaction } => return Self::replay(transaction, store.id, &payload),
+            RecordOutcome::PromoBudgetExceeded { promo_usage } => {
+                return Err(Self::budget_exceeded("promo", promo.budget_caps(), &promo_usage, installment.subsidy));
+            }
+            RecordOutcome::StoreBudgetExceeded { store_usage } => {
+                let caps = link.as_ref().map(|l| l.budget_caps()).unwrap_or_default();
+                return Err(Self::budget_exceeded("store", caps, &store_usage, installment.subsidy));
+            }
+        };
+
+        let mut changed = false;
+        if promo.budget_caps().is_reached(&promo_usage) {
+            changed |= self.set_promo_exhausted(&promo, true).await;
+        }
+        if let Some(link) = &link
+            && link.budget_caps().is_reached(&store_usage)
+        {
+            changed |= self.set_link_exhausted(link, true).await;
+        }
+        if changed {
+            self.warm_cache().await;
+        }
+
+        Ok(RecordTransactionResult {
+            transaction,
+            replayed: false,
+        })
+    }
+
+    fn replay(
+        existing: FinancedTransaction,
+        store_id: Uuid,
+        payload: &RecordTransactionPayload,
+    ) -> Result<RecordTransactionResult, AppError> {
+        if existing.promo_tenor_id != payload.promo_tenor_id
+            || existing.amount != payload.amount
+            || existing.store_id != store_id
+        {
+            return Err(TransactionError::InvalidPayload(format!(
+                "transaction_ref '{}' already used for another transaction",
+                payload.transaction_ref
+            ))
+            .into());
+        }
+        info!("Transaction ref {} sudah tercatat, mengembalikan data lama.", payload.transaction_ref);
+        Ok(RecordTransactionResult {
+            transaction: existing,
+            replayed: true,
+        })
+    }
+
+    fn budget_exceeded(scope: &str, caps: BudgetCaps, usage: &BudgetUsage, subsidy: i64) -> AppError {
+        let reason = caps
+            .check(usage, subsidy)
+            .err()
+            .unwrap_or_else(|| "budget limit reached".to_string());
+        TransactionError::BudgetExceeded(format!("{}: {}", scope, reason)).into()
+    }
+
+    /// Non-admin hanya melihat pemakaian per store untuk store dalam cakupannya, dan hanya
+    /// untuk promo yang ter-link ke minimal satu store tersebut
+    pub async fn ser_get_promo_budget(
+        &self,
+        promo_id: Uuid,
+        scope: &StoreScope,
+    ) -> Result<PromoBudgetStatus, AppError> {
+        let promo = self.promo_repo.rep_get_by_id(promo_id).await?;
+        let links = self.links_of(promo_id).await?;
+        if !scope.is_all() && !links.iter().any(|ps| scope.allows_store(ps.store_id)) {
+            return Err(AppError::Forbidden(format!(
+                "Promo '{}' is not linked to any of your assigned stores",
+                promo_id
+            )));
+        }
+        let usage = self.repo.rep_fetch_usage(promo_id).await?;
+        let mut status = Self::budget_status(&promo, &links, &usage);
+        status.stores.retain(|s| scope.allows_store(s.store_id));
+        Ok(status)
+    }
+
+    /// Sinkronkan flag `budget_exhausted` promo dan link store-nya dengan ledger,
+    /// mis. setelah batas anggaran dinaikkan atau dihapus.
+    pub async fn ser_refresh_budget(&self, promo_id: Uuid) -> Result<PromoBudgetStatus, AppError> {
+        let promo = self.promo_repo.rep_get_by_id(promo_id).await?;
+        let links = self.links_of(promo_id).await?;
+        let usage = self.repo.rep_fetch_usage(promo_id).await?;
+        let status = Self::budget_status(&promo, &links, &usage);
+
+        let mut changed = false;
+        if status.budget.exhausted != promo.budget_exhausted {
+            changed |= self.set_promo_exhausted(&promo, status.budget.exhausted).await;
+        }
+        for link in &links {
+            let exhausted = status
+                .stores
+                .iter()
+                .find(|s| s.store_id == link.store_id)
+                .is_some_and(|s| s.budget.exhausted);
+            if exhausted != link.budget_exhausted {
+                changed |= self.set_link_exhausted(link, exhausted).await;
+            }
+        }
+        if changed {
+            self.warm_cache().await;
+        }
+        Ok(status)
+    }
+
+    fn budget_status(promo: &Promo, links: &[PromoStore], usage: &PromoBudgetUsage) -> PromoBudgetStatus {
+        let stores = links
+            .iter()
+            .map(|link| StoreBudgetStatus {
+                store_id: link.store_id,
+                budget: BudgetStatus::new(link.budget_caps(), usage.of_store(link.store_id)),
+            })
+            .collect();
+
+        PromoBudgetStatus {
+            promo_id: promo.id_promo,
+            budget: BudgetStatus::new(promo.budget_caps(), usage.usage),
+            stores,
+        }
+    }
+
+    async fn links_of(&self, promo_id: Uuid) -> Result<Vec<PromoStore>, AppError> {
+        match self.promo_store_repo.rep_fetch_all().await {
+            Ok(links) => Ok(links.into_iter().filter(|ps| ps.promo_id == promo_id).collect()),
+            Err(AppError::PromoStore(PromoStoreError::NotFound(_))) => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn set_promo_exhausted(&self, promo: &Promo, exhausted: bool) -> bool {
+        let payload = UpdatePromoPayload {
+            budget_exhausted: Some(exhausted),
+            ..Default::default()
+        };
+        match self.promo_repo.rep_update_by_id(promo.id_promo, payload).await {
+            Ok(_) => {
+                info!("Anggaran promo {} budget_exhausted = {}", promo.id_promo, exhausted);
+                true
+            }
+            Err(e) => {
+                warn!("Gagal memperbarui budget_exhausted promo {}: {}", promo.id_promo, e);
+                false
+            }
+        }
+    }
+
+    async fn set_link_exhausted(&self, link: &PromoStore, exhausted: bool) -> bool {
+        let payload = UpdatePromoStorePayload {
+            budget_exhausted: Some(exhausted),
+            ..Default::default()
+        };
+        match self
+            .promo_store_repo
+            .rep_update_by_key(link.promo_id, link.store_id, payload)
+            .await
+        {
+            Ok(_) => {
+                info!(
+                    "Anggaran promo {} di store {} budget_exhausted = {}",
+                    link.promo_id, link.store_id, exhausted
+                );
+                true
+            }
+            Err(e) => {
+                warn!(
+                    "Gagal memperbarui budget_exhausted promo {} di store {}: {}",
+                    link.promo_id, link.store_id, e
+                );
+                false
+            }
+        }
+    }
+
+    async fn warm_cache(&self) {
+        if let Err(e) = self.promo_repo.rep_fetch_all().await
+            && !matches!(e, AppError::Promo(PromoError::NotFound(_)))
+        {
+            warn!("Gagal me-warm cache promo: {}", e);
+        }
+        if let Err(e) = self.promo_store_repo.rep_fetch_all().await {
+            warn!("Gagal me-warm cache promo_store: {}", e);
+        }
+        if let Err(e) = self.promo_tenor_repo.rep_fetch_all().await {
+            warn!("Gagal me-warm cache promo_tenor: {}", e);
+        }
+    }
+}
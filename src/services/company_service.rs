@@ -0,0 +1,266 @@
+use crate::error::{AppError, CompanyError, PromoError, PromoStoreError, StoreError};
+use crate::model::company_model::*;
+use crate::model::store_model::{Store, normalize_company_name};
+use crate::repositories::company_repository::CompanyRepository;
+use crate::repositories::promo_repository::PromoRepository;
+use crate::repositories::promo_store_repository::PromoStoreRepository;
+use crate::repositories::store_repository::StoreRepository;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+pub struct CompanyService {
+    repo: Arc<CompanyRepository>,
+    store_repo: Arc<StoreRepository>,
+    promo_repo: Arc<PromoRepository>,
+    promo_store_repo: Arc<PromoStoreRepository>,
+}
+
+impl CompanyService {
+    pub fn new(
+        repo: Arc<CompanyRepository>,
+        store_repo: Arc<StoreRepository>,
+        promo_repo: Arc<PromoRepository>,
+        promo_store_repo: Arc<PromoStoreRepository>,
+    ) -> Self {
+        Self {
+            repo,
+            store_repo,
+            promo_repo,
+            promo_store_repo,
+        }
+    }
+
+    pub async fn ser_get_all_companies(&self) -> Result<Vec<Company>, AppError> {
+        self.repo.rep_fetch_all().await
+    }
+
+    pub async fn ser_get_company_by_id(&self, id: Uuid) -> Result<Company, AppError> {
+        self.repo.rep_fetch_by_id(id).await
+    }
+
+    pub async fn ser_create_company(&self, payload: CreateCompanyPayload) -> Result<Company, AppError> {
+        let row = Self::validate_name(&payload.name)?;
+        self.ensure_unique(&row, None).await?;
+        self.repo.rep_insert(row).await
+    }
+
+    pub async fn ser_update_company(
+        &self,
+        id: Uuid,
+        payload: UpdateCompanyPayload,
+    ) -> Result<Company, AppError> {
+        let existing = self.repo.rep_fetch_by_id(id).await?;
+        let Some(name) = payload.name else {
+            return Ok(existing);
+        };
+
+        let row = Self::validate_name(&name)?;
+        self.ensure_unique(&row, Some(id)).await?;
+        let updated = self.repo.rep_update_by_id(id, row).await?;
+
+        // Samakan nama company pada store yang sudah ter-link
+        for store in self.stores_of(&existing).await? {
+            if store.company_id == Some(id) && store.company != updated.name {
+                self.store_repo.rep_set_company(store.id, id, &updated.name).await?;
+            }
+        }
+        Ok(updated)
+    }
+
+    pub async fn ser_delete_company(&self, id: Uuid) -> Result<(), AppError> {
+        let company = self.repo.rep_fetch_by_id(id).await?;
+        let linked = self
+            .stores_of(&company)
+            .await?
+            .into_iter()
+            .filter(|s| s.company_id == Some(id))
+            .count();
+        if linked > 0 {
+            return Err(CompanyError::InUse(format!(
+                "Company '{}' still has {} linked store(s)",
+                company.name, linked
+            ))
+            .into());
+        }
+        self.repo.rep_delete_by_id(id).await
+    }
+
+    pub async fn ser_get_company_stores(&self, id: Uuid) -> Result<Vec<Store>, AppError> {
+        let company = self.repo.rep_fetch_by_id(id).await?;
+        self.stores_of(&company).await
+    }
+
+    /// Promo yang ter-link ke minimal satu store milik company
+    pub async fn ser_get_company_promos(&self, id: Uuid) -> Result<Vec<CompanyPromo>, AppError> {
+        let company = self.repo.rep_fetch_by_id(id).await?;
+        let store_ids: Vec<Uuid> = self.stores_of(&company).await?.iter().map(|s| s.id).collect();
+
+        let promo_stores = match self.promo_store_repo.rep_fetch_all().await {
+            Ok(links) => links,
+            Err(AppError::PromoStore(PromoStoreError::NotFound(_))) => vec![],
+            Err(e) => return Err(e),
+        };
+        let mut stores_by_promo: BTreeMap<Uuid, Vec<Uuid>> = BTreeMap::new();
+        for ps in promo_stores.iter().filter(|ps| store_ids.contains(&ps.store_id)) {
+            stores_by_promo.entry(ps.promo_id).or_default().push(ps.store_id);
+        }
+
+        let promos = match self.promo_repo.rep_fetch_all().await {
+            Ok(promos) => promos,
+            Err(AppError::Promo(PromoError::NotFound(_))) => vec![],
+            Err(e) => return Err(e),
+        };
+
+        Ok(promos
+            .into_iter()
+            .filter_map(|promo| {
+                let store_ids = stores_by_promo.remove(&promo.id_promo)?;
+                Some(CompanyPromo { promo, store_ids })
+            })
+            .collect())
+    }
+
+    /// Link store lama ke company berdasarkan nama yang dinormalisasi.
+    /// Company yang belum ada dibuat dengan nama yang paling sering dipakai.
+    pub async fn ser_migrate_store_companies(
+        &self,
+        payload: MigrateStoreCompanyPayload,
+    ) -> Result<StoreCompanyMigrationResult, AppError> {
+        let stores = self.all_stores().await?;
+        let mut companies: HashMap<String, Company> = self
+            .repo
+            .rep_fetch_all()
+            .await?
+            .into_iter()
+            .map(|c| (c.normalized_name.clone(), c))
+            .collect();
+
+        let mut unlinked: BTreeMap<String, Vec<&Store>> = BTreeMap::new();
+        let mut stores_already_linked = 0;
+        let mut stores_skipped = Vec::new();
+        for store in &stores {
+            let normalized = normalize_company_name(&store.company);
+            if store.company_id.is_some() {
+                stores_already_linked += 1;
+            } else if normalized.is_empty() {
+                stores_skipped.push(store.id);
+            } else {
+                unlinked.entry(normalized).or_default().push(store);
+            }
+        }
+
+        let mut companies_created = Vec::new();
+        let mut stores_linked = Vec::new();
+        for (normalized, group) in unlinked {
+            let company = match companies.get(&normalized) {
+                Some(company) => Some(company.clone()),
+                None => {
+                    let name = Self::most_common_spelling(&group);
+                    companies_created.push(name.clone());
+                    if payload.dry_run {
+                        None
+                    } else {
+                        let created = self.repo.rep_insert(CompanyRow::from_name(&name)).await?;
+                        companies.insert(normalized.clone(), created.clone());
+                        Some(created)
+                    }
+                }
+            };
+
+            for store in group {
+                if let Some(company) = &company
+                    && !payload.dry_run
+                {
+                    self.store_repo.rep_set_company(store.id, company.id, &company.name).await?;
+                }
+                stores_linked.push(StoreCompanyLink {
+                    store_id: store.id,
+                    store_name: store.name.clone(),
+                    company: store.company.clone(),
+                    company_id: company.as_ref().map(|c| c.id),
+                    normalized_name: normalized.clone(),
+                });
+            }
+        }
+
+        if !payload.dry_run {
+            let _ = self.store_repo.rep_fetch_all().await;
+        }
+
+        info!(
+            "Migrasi company (dry_run={}): {} company baru, {} store di-link.",
+            payload.dry_run,
+            companies_created.len(),
+            stores_linked.len()
+        );
+
+        Ok(StoreCompanyMigrationResult {
+            dry_run: payload.dry_run,
+            companies_created,
+            stores_linked,
+            stores_already_linked,
+            stores_skipped,
+        })
+    }
+
+    /// Cari company untuk nama bebas di store; dipakai saat create/update store
+    pub async fn ser_find_company_by_name(&self, name: &str) -> Result<Option<Company>, AppError> {
+        let normalized = normalize_company_name(name);
+        if normalized.is_empty() {
+            return Ok(None);
+        }
+        self.repo.rep_fetch_by_normalized_name(&normalized).await
+    }
+
+    async fn stores_of(&self, company: &Company) -> Result<Vec<Store>, AppError> {
+        Ok(self
+            .all_stores()
+            .await?
+            .into_iter()
+            .filter(|s| company.owns_store(s))
+            .collect())
+    }
+
+    async fn all_stores(&self) -> Result<Vec<Store>, AppError> {
+        match self.store_repo.rep_fetch_all().await {
+            Ok(stores) => Ok(stores),
+            Err(AppError::Store(StoreError::NotFound(_))) => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn ensure_unique(&self, row: &CompanyRow, own_id: Option<Uuid>) -> Result<(), AppError> {
+        let normalized = row.normalized_name.as_deref().unwrap_or_default();
+        if let Some(existing) = self.repo.rep_fetch_by_normalized_name(normalized).await?
+            && Some(existing.id) != own_id
+        {
+            return Err(CompanyError::AlreadyExists(format!(
+                "Company '{}' already exists (id '{}')",
+                existing.name, existing.id
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    fn validate_name(name: &str) -> Result<CompanyRow, AppError> {
+        if normalize_company_name(name).is_empty() {
+            return Err(CompanyError::InvalidPayload("name must not be empty".to_string()).into());
+        }
+        Ok(CompanyRow::from_name(name))
+    }
+
+    fn most_common_spelling(group: &[&Store]) -> String {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for store in group {
+            *counts.entry(store.company.trim().to_string()).or_default() += 1;
+        }
+        counts
+            .into_iter()
+            .max_by(|(a_name, a), (b_name, b)| a.cmp(b).then(b_name.cmp(a_name)))
+            .map(|(name, _)| name)
+            .unwrap_or_default()
+    }
+}
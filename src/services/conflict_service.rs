@@ -0,0 +1,276 @@
+use crate::error::{AppError, PromoError, PromoStoreError, PromoTenorError, StoreError};
+use crate::model::conflict_model::*;
+use crate::model::promo_model::{Promo, PromoLifecycle, PromoStatus, UpdatePromoPayload};
+use crate::model::promo_store_model::{CreatePromoStorePayload, PromoStore};
+use crate::model::promo_tenor_model::PromoTenor;
+use crate::repositories::promo_repository::PromoRepository;
+use crate::repositories::promo_store_repository::PromoStoreRepository;
+use crate::repositories::promo_tenor_repository::PromoTenorRepository;
+use crate::repositories::store_repository::StoreRepository;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+pub struct ConflictService {
+    promo_repo: Arc<PromoRepository>,
+    promo_store_repo: Arc<PromoStoreRepository>,
+    promo_tenor_repo: Arc<PromoTenorRepository>,
+    store_repo: Arc<StoreRepository>,
+    mode: ConflictMode,
+}
+
+impl ConflictService {
+    pub fn new(
+        promo_repo: Arc<PromoRepository>,
+        promo_store_repo: Arc<PromoStoreRepository>,
+        promo_tenor_repo: Arc<PromoTenorRepository>,
+        store_repo: Arc<StoreRepository>,
+    ) -> Self {
+        Self {
+            promo_repo,
+            promo_store_repo,
+            promo_tenor_repo,
+            store_repo,
+            mode: ConflictMode::from_env(),
+        }
+    }
+
+    /// Laporan semua konflik yang ada saat ini, dikelompokkan per store
+    pub async fn ser_get_conflict_report(&self) -> Result<Vec<StoreConflictReport>, AppError> {
+        let (promos, promo_stores, tenors) = self.load().await?;
+        let stores = empty_if_not_found(self.store_repo.rep_fetch_all().await)?;
+
+        let mut by_store: BTreeMap<Uuid, Vec<PromoConflict>> = BTreeMap::new();
+        for conflict in detect_conflicts(&promos, &promo_stores, &tenors, None) {
+            by_store.entry(conflict.store_id).or_default().push(conflict);
+        }
+
+        Ok(by_store
+            .into_iter()
+            .map(|(store_id, conflicts)| {
+                let store = stores.iter().find(|s| s.id == store_id);
+                StoreConflictReport {
+                    store_id,
+                    store_name: store.map(|s| s.name.clone()),
+                    store_route: store.and_then(|s| s.route.clone()),
+                    conflicts,
+                }
+            })
+            .collect())
+    }
+
+    /// Cek konflik yang akan muncul jika link promo-store dibuat
+    pub async fn ser_check_promo_store_link(
+        &self,
+        payload: &CreatePromoStorePayload,
+    ) -> Result<Vec<PromoConflict>, AppError> {
+        if self.mode == ConflictMode::Off {
+            return Ok(vec![]);
+        }
+
+        let (promos, mut promo_stores, tenors) = self.load().await?;
+        promo_stores.retain(|ps| ps.store_id == payload.store_id);
+        promo_stores.retain(|ps| ps.promo_id != payload.promo_id);
+        promo_stores.push(PromoStore {
+            id: Uuid::nil(),
+            promo_id: payload.promo_id,
+            store_id: payload.store_id,
+            tenor_ids: payload.tenor_ids.clone(),
+            tenor_overrides: payload.tenor_overrides.clone(),
+            budget_max_subsidy: payload.budget_max_subsidy,
+            budget_max_transactions: payload.budget_max_transactions,
+            budget_exhausted: false,
+            created_at: None,
+            updated_at: None,
+        });
+
+        let conflicts = involving(
+            detect_conflicts(&promos, &promo_stores, &tenors, Some(payload.promo_id)),
+            payload.promo_id,
+        );
+        if self.mode == ConflictMode::Reject && !conflicts.is_empty() {
+            return Err(PromoStoreError::Conflict(describe(&conflicts)).into());
+        }
+        Ok(conflicts)
+    }
+
+    /// Cek konflik yang akan muncul jika promo di-update
+    pub async fn ser_check_promo_update(
+        &self,
+        id_promo: Uuid,
+        payload: &UpdatePromoPayload,
+    ) -> Result<Vec<PromoConflict>, AppError> {
+        if self.mode == ConflictMode::Off {
+            return Ok(vec![]);
+        }
+
+        let (mut promos, promo_stores, tenors) = self.load().await?;
+        match promos.iter_mut().find(|p| p.id_promo == id_promo) {
+            Some(promo) => *promo = promo.with_update(payload),
+            None => return Ok(vec![]),
+        }
+
+        let conflicts = involving(detect_conflicts(&promos, &promo_stores, &tenors, Some(id_promo)), id_promo);
+        if self.mode == ConflictMode::Reject && !conflicts.is_empty() {
+            return Err(PromoError::Conflict(describe(&conflicts)).into());
+        }
+        Ok(conflicts)
+    }
+
+    /// Cek konflik yang akan muncul jika promo dipublish (approved -> published)
+    pub async fn ser_check_promo_publish(&self, id_promo: Uuid) -> Result<Vec<PromoConflict>, AppError> {
+        if self.mode == ConflictMode::Off {
+            return Ok(vec![]);
+        }
+
+        let (promos, promo_stores, tenors) = self.load().await?;
+        let conflicts = involving(detect_conflicts(&promos, &promo_stores, &tenors, Some(id_promo)), id_promo);
+        if self.mode == ConflictMode::Reject && !conflicts.is_empty() {
+            return Err(PromoError::Conflict(describe(&conflicts)).into());
+        }
+        Ok(conflicts)
+    }
+
+    async fn load(&self) -> Result<(Vec<Promo>, Vec<PromoStore>, Vec<PromoTenor>), AppError> {
+        let (promos, promo_stores, tenors) = tokio::join!(
+            self.promo_repo.rep_fetch_all(),
+            self.promo_store_repo.rep_fetch_all(),
+            self.promo_tenor_repo.rep_fetch_all()
+        );
+        Ok((
+            empty_if_not_found(promos)?,
+            empty_if_not_found(promo_stores)?,
+            empty_if_not_found(tenors)?,
+        ))
+    }
+}
+
+/// Konflik dihitung untuk promo published yang aktif, belum berakhir (termasuk upcoming)
+/// dan anggarannya belum habis. `subject` adalah promo yang sedang dicek; promo ini ikut
+/// dihitung selama belum archived karena nantinya akan dipublish.
+pub fn detect_conflicts(
+    promos: &[Promo],
+    promo_stores: &[PromoStore],
+    tenors: &[PromoTenor],
+    subject: Option<Uuid>,
+) -> Vec<PromoConflict> {
+    let now = Utc::now();
+    let candidates: HashMap<Uuid, &Promo> = promos
+        .iter()
+        .filter(|p| {
+            let published = p.is_published()
+                || (subject == Some(p.id_promo) && p.lifecycle != PromoLifecycle::Archived);
+            published && p.is_active && !p.budget_exhausted && p.status_at(now) != PromoStatus::Expired
+        })
+        .map(|p| (p.id_promo, p))
+        .collect();
+
+    let mut links_by_store: BTreeMap<Uuid, Vec<&PromoStore>> = BTreeMap::new();
+    let links = promo_stores
+        .iter()
+        .filter(|ps| candidates.contains_key(&ps.promo_id) && !ps.budget_exhausted);
+    for ps in links {
+        links_by_store.entry(ps.store_id).or_default().push(ps);
+    }
+
+    let offered_tenors = |ps: &PromoStore| -> BTreeSet<i32> {
+        tenors
+            .iter()
+            .filter(|t| t.promo_id == ps.promo_id && t.is_available && ps.includes_tenor(t.id))
+            .map(|t| t.tenor)
+            .collect()
+    };
+
+    let mut conflicts = Vec::new();
+    for (store_id, links) in links_by_store {
+        for (i, a) in links.iter().enumerate() {
+            for b in links.iter().skip(i + 1) {
+                if a.promo_id == b.promo_id {
+                    continue;
+                }
+                let (promo_a, promo_b) = (candidates[&a.promo_id], candidates[&b.promo_id]);
+                let Some((overlap_start, overlap_end)) = overlap(promo_a, promo_b) else {
+                    continue;
+                };
+
+                let shared: Vec<i32> = offered_tenors(a)
+                    .intersection(&offered_tenors(b))
+                    .copied()
+                    .collect();
+                if shared.is_empty() {
+                    continue;
+                }
+
+                conflicts.push(PromoConflict {
+                    store_id,
+                    promo_id: promo_a.id_promo,
+                    title_promo: promo_a.title_promo.clone(),
+                    conflicting_promo_id: promo_b.id_promo,
+                    conflicting_title_promo: promo_b.title_promo.clone(),
+                    tenors: shared,
+                    overlap_start: overlap_start.map(|dt| dt.to_rfc3339()),
+                    overlap_end: overlap_end.map(|dt| dt.to_rfc3339()),
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+type Window = (Option<DateTime<Utc>>, Option<DateTime<Utc>>);
+
+/// Irisan window tanggal dua promo; `None` pada batas berarti tidak terbatas
+fn overlap(a: &Promo, b: &Promo) -> Option<Window> {
+    let start = match (a.start_at(), b.start_at()) {
+        (Some(x), Some(y)) => Some(x.max(y)),
+        (x, y) => x.or(y),
+    };
+    let end = match (a.end_at(), b.end_at()) {
+        (Some(x), Some(y)) => Some(x.min(y)),
+        (x, y) => x.or(y),
+    };
+    match (start, end) {
+        (Some(s), Some(e)) if s > e => None,
+        _ => Some((start, end)),
+    }
+}
+
+fn involving(conflicts: Vec<PromoConflict>, promo_id: Uuid) -> Vec<PromoConflict> {
+    conflicts
+        .into_iter()
+        .filter(|c| c.promo_id == promo_id || c.conflicting_promo_id == promo_id)
+        .collect()
+}
+
+fn describe(conflicts: &[PromoConflict]) -> String {
+    conflicts
+        .iter()
+        .map(|c| {
+            format!(
+                "store '{}': '{}' overlaps '{}' on tenor {:?}",
+                c.store_id, c.title_promo, c.conflicting_title_promo, c.tenors
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// `rep_fetch_all` mengembalikan NotFound jika tabel kosong; anggap sebagai daftar kosong
+fn empty_if_not_found<T>(result: Result<Vec<T>, AppError>) -> Result<Vec<T>, AppError> {
+    match result {
+        Err(AppError::Promo(PromoError::NotFound(_)))
+        | Err(AppError::PromoStore(PromoStoreError::NotFound(_)))
+        | Err(AppError::PromoTenor(PromoTenorError::NotFound(_)))
+        | Err(AppError::Store(StoreError::NotFound(_))) => Ok(vec![]),
+        other => other,
+    }
+}
+
+/// Log peringatan konflik (mode warn)
+pub fn log_conflicts(context: &str, conflicts: &[PromoConflict]) {
+    if !conflicts.is_empty() {
+        warn!("{}: {} promo conflict(s): {}", context, conflicts.len(), describe(conflicts));
+    }
+}
@@ -0,0 +1,407 @@
+use crate::constants::ROLE_ADMIN;
+use crate::error::{AppError, PromoError, PromoStoreError, PromoTenorError, StoreError};
+use crate::middleware::Actor;
+use crate::model::delete_model::*;
+use crate::model::promo_model::Promo;
+use crate::model::promo_store_model::PromoStore;
+use crate::model::promo_tenor_model::PromoTenor;
+use crate::model::store_assignment_model::StoreAssignment;
+use crate::model::store_model::Store;
+use crate::model::tenor_change_model::{PromoTenorChange, TenorChangeStatus, UpdatePromoTenorChangePayload};
+use crate::repositories::cache_repository::{CacheRepository, CacheScope};
+use crate::repositories::promo_repository::PromoRepository;
+use crate::repositories::promo_store_repository::PromoStoreRepository;
+use crate::repositories::promo_tenor_change_repository::PromoTenorChangeRepository;
+use crate::repositories::promo_tenor_repository::PromoTenorRepository;
+use crate::repositories::store_assignment_repository::StoreAssignmentRepository;
+use crate::repositories::store_repository::StoreRepository;
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Aturan delete per relasi:
+/// - Promo → PromoTenor, PromoStore: restrict / cascade delete
+/// - Promo/PromoTenor → PromoTenorChange `pending`: restrict / cascade cancel
+/// - PromoTenor → PromoStore (`tenor_ids`, `tenor_overrides`): restrict / cascade detach
+/// - Store → PromoStore, StoreAssignment: restrict / cascade delete
+///
+/// Ledger (financed transaction, voucher redemption, lead) menyimpan snapshot term
+/// sehingga tidak dianggap dependent dan tidak pernah ikut dihapus.
+pub struct DeleteService {
+    promo_repo: Arc<PromoRepository>,
+    promo_tenor_repo: Arc<PromoTenorRepository>,
+    promo_store_repo: Arc<PromoStoreRepository>,
+    store_repo: Arc<StoreRepository>,
+    change_repo: Arc<PromoTenorChangeRepository>,
+    assignment_repo: Arc<StoreAssignmentRepository>,
+    cache_repository: Arc<CacheRepository>,
+}
+
+impl DeleteService {
+    pub fn new(
+        promo_repo: Arc<PromoRepository>,
+        promo_tenor_repo: Arc<PromoTenorRepository>,
+        promo_store_repo: Arc<PromoStoreRepository>,
+        store_repo: Arc<StoreRepository>,
+        change_repo: Arc<PromoTenorChangeRepository>,
+        assignment_repo: Arc<StoreAssignmentRepository>,
+        cache_repository: Arc<CacheRepository>,
+    ) -> Self {
+        Self {
+            promo_repo,
+            promo_tenor_repo,
+            promo_store_repo,
+            store_repo,
+            change_repo,
+            assignment_repo,
+            cache_repository,
+        }
+    }
+
+    pub async fn ser_delete_promo(
+        &self,
+        id_promo: Uuid,
+        query: DeleteQuery,
+        actor: &Actor,
+    ) -> Result<DeleteReport, AppError> {
+        let promo = self.promo_repo.rep_get_by_id(id_promo).await?;
+        Self::ensure_draft_or_admin(&promo, actor).map_err(PromoError::Conflict)?;
+
+        let tenors: Vec<PromoTenor> = self
+            .all_promo_tenors()
+            .await?
+            .into_iter()
+            .filter(|t| t.promo_id == id_promo)
+            .collect();
+        let tenor_ids: HashSet<Uuid> = tenors.iter().map(|t| t.id).collect();
+        let links: Vec<PromoStore> = self
+            .all_promo_stores()
+            .await?
+            .into_iter()
+            .filter(|ps| ps.promo_id == id_promo)
+            .collect();
+        let changes: Vec<PromoTenorChange> = self
+            .change_repo
+            .rep_fetch_all(None, None)
+            .await?
+            .into_iter()
+            .filter(|c| c.status.is_open() && tenor_ids.contains(&c.promo_tenor_id))
+            .collect();
+
+        let store_routes = self.store_routes().await?;
+        let mut dependents: Vec<Dependent> = tenors
+            .iter()
+            .map(|t| {
+                Dependent::new(
+                    DeletableEntity::PromoTenor,
+                    t.id,
+                    format!("tenor {}", t.tenor),
+                    DependentAction::Delete,
+                )
+            })
+            .collect();
+        dependents.extend(links.iter().map(|ps| Self::link_dependent(ps, &store_routes)));
+        dependents.extend(changes.iter().map(Self::change_dependent));
+
+        if !dependents.is_empty() && !query.cascade {
+            return Err(PromoError::InUse(DependentConflict::new(
+                DeletableEntity::Promo,
+                &promo.title_promo,
+                dependents,
+            ))
+            .into());
+        }
+
+        // Anak dulu, parent terakhir: jika gagal di tengah jalan, promo masih ada dan bisa diulang
+        let result = async {
+            self.cancel_changes(&changes, actor).await?;
+            self.promo_store_repo.rep_delete_by_promo_id(id_promo).await?;
+            self.promo_tenor_repo.rep_delete_by_promo_id(id_promo).await?;
+            self.promo_repo.rep_delete_by_id(id_promo).await
+        }
+        .await;
+        self.cache_repository
+            .invalidate(CacheScope {
+                promo: true,
+                promo_tenor: true,
+                promo_store: true,
+                ..Default::default()
+            })
+            .await;
+        result?;
+
+        info!(
+            "Promo {} dihapus oleh {} ({} dependent, cascade: {}).",
+            id_promo,
+            actor.user_id,
+            dependents.len(),
+            query.cascade
+        );
+        Ok(DeleteReport {
+            entity_type: DeletableEntity::Promo,
+            id: id_promo.to_string(),
+            cascade: query.cascade,
+            dependents,
+        })
+    }
+
+    pub async fn ser_delete_promo_tenor(
+        &self,
+        id: Uuid,
+        query: DeleteQuery,
+        actor: &Actor,
+    ) -> Result<DeleteReport, AppError> {
+        let tenor = self.promo_tenor_repo.rep_fetch_by_id(id).await?;
+        let promo = self.promo_repo.rep_get_by_id(tenor.promo_id).await?;
+        Self::ensure_draft_or_admin(&promo, actor).map_err(PromoTenorError::Conflict)?;
+
+        let links: Vec<PromoStore> = self
+            .all_promo_stores()
+            .await?
+            .into_iter()
+            .filter(|ps| ps.promo_id == tenor.promo_id && ps.references_tenor(id))
+            .collect();
+        let mut changes = self
+            .change_repo
+            .rep_fetch_all(None, Some(id))
+            .await?;
+        changes.retain(|c| c.status.is_open());
+
+        let store_routes = self.store_routes().await?;
+        let mut dependents: Vec<Dependent> = links
+            .iter()
+            .map(|ps| Dependent {
+                action: DependentAction::Detach,
+                ..Self::link_dependent(ps, &store_routes)
+            })
+            .collect();
+        dependents.extend(changes.iter().map(Self::change_dependent));
+
+        if !dependents.is_empty() && !query.cascade {
+            return Err(PromoTenorError::InUse(DependentConflict::new(
+                DeletableEntity::PromoTenor,
+                &format!("tenor {} ({})", tenor.tenor, id),
+                dependents,
+            ))
+            .into());
+        }
+
+        let result = async {
+            self.cancel_changes(&changes, actor).await?;
+            for link in &links {
+                self.promo_store_repo
+                    .rep_update_by_id_deferred(link.id, &link.detach_tenor(id))
+                    .await?;
+            }
+            self.promo_tenor_repo.rep_delete_by_id(id).await
+        }
+        .await;
+        self.cache_repository
+            .invalidate(CacheScope {
+                promo_tenor: true,
+                promo_store: true,
+                ..Default::default()
+            })
+            .await;
+        result?;
+
+        info!(
+            "Promo tenor {} dihapus oleh {} ({} dependent, cascade: {}).",
+            id,
+            actor.user_id,
+            dependents.len(),
+            query.cascade
+        );
+        Ok(DeleteReport {
+            entity_type: DeletableEntity::PromoTenor,
+            id: id.to_string(),
+            cascade: query.cascade,
+            dependents,
+        })
+    }
+
+    pub async fn ser_delete_store(
+        &self,
+        route: &str,
+        query: DeleteQuery,
+        actor: &Actor,
+    ) -> Result<DeleteReport, AppError> {
+        let store = self.store_repo.rep_fetch_by_route(route).await?;
+
+        let promo_titles: HashMap<Uuid, String> = self
+            .all_promos()
+            .await?
+            .into_iter()
+            .map(|p| (p.id_promo, p.title_promo))
+            .collect();
+        let mut dependents: Vec<Dependent> = self
+            .all_promo_stores()
+            .await?
+            .into_iter()
+            .filter(|ps| ps.store_id == store.id)
+            .map(|ps| {
+                let title = promo_titles
+                    .get(&ps.promo_id)
+                    .map(String::as_str)
+                    .unwrap_or("unknown promo");
+                Dependent::new(
+                    DeletableEntity::PromoStore,
+                    format!("{}-{}", ps.promo_id, ps.store_id),
+                    format!("promo '{}'", title),
+                    DependentAction::Delete,
+                )
+            })
+            .collect();
+        let assignments: Vec<StoreAssignment> = self
+            .assignment_repo
+            .rep_fetch_all(None)
+            .await?
+            .into_iter()
+            .filter(|a| a.store_id == Some(store.id))
+            .collect();
+        dependents.extend(assignments.iter().map(|a| {
+            Dependent::new(
+                DeletableEntity::StoreAssignment,
+                a.id,
+                format!("access of user '{}'", a.user_id),
+                DependentAction::Delete,
+            )
+        }));
+
+        if !dependents.is_empty() && !query.cascade {
+            return Err(StoreError::InUse(DependentConflict::new(
+                DeletableEntity::Store,
+                route,
+                dependents,
+            ))
+            .into());
+        }
+
+        let result = async {
+            self.promo_store_repo.rep_delete_by_store_id(store.id).await?;
+            self.assignment_repo.rep_delete_by_store_id(store.id).await?;
+            self.store_repo.rep_delete(route).await
+        }
+        .await;
+        self.cache_repository
+            .invalidate(CacheScope {
+                store: true,
+                promo_store: true,
+                promo_tenor: true,
+                ..Default::default()
+            })
+            .await;
+        result?;
+
+        info!(
+            "Store {} dihapus oleh {} ({} dependent, cascade: {}).",
+            route,
+            actor.user_id,
+            dependents.len(),
+            query.cascade
+        );
+        Ok(DeleteReport {
+            entity_type: DeletableEntity::Store,
+            id: store.id.to_string(),
+            cascade: query.cascade,
+            dependents,
+        })
+    }
+
+    /// Promo yang sudah masuk review/published hanya boleh dihapus admin, sama seperti edit
+    /// yang mensyaratkan draft
+    fn ensure_draft_or_admin(promo: &Promo, actor: &Actor) -> Result<(), String> {
+        if actor.has_any_role(&[ROLE_ADMIN]) {
+            return Ok(());
+        }
+        promo
+            .ensure_draft()
+            .map_err(|_| format!("promo is {}; only admin can delete it", promo.lifecycle.as_str()))
+    }
+
+    /// Perubahan yang sudah diterapkan/dibatalkan di antara pengecekan dan cascade dilewati
+    async fn cancel_changes(&self, changes: &[PromoTenorChange], actor: &Actor) -> Result<(), AppError> {
+        for change in changes {
+            let cancel = UpdatePromoTenorChangePayload {
+                status: TenorChangeStatus::Cancelled,
+                approved_by: None,
+                approved_at: None,
+                claimed_at: None,
+                applied_at: None,
+                cancelled_by: Some(actor.user_id.clone()),
+                cancelled_at: Some(Utc::now().to_rfc3339()),
+                error: None,
+            };
+            let open = [TenorChangeStatus::PendingApproval, TenorChangeStatus::Pending];
+            if self.change_repo.rep_update_if_status(change.id, &open, cancel).await?.is_none() {
+                warn!("Perubahan terjadwal {} sudah tidak pending, dilewati.", change.id);
+            }
+        }
+        Ok(())
+    }
+
+    fn link_dependent(link: &PromoStore, store_routes: &HashMap<Uuid, String>) -> Dependent {
+        let route = store_routes
+            .get(&link.store_id)
+            .map(String::as_str)
+            .unwrap_or("unknown store");
+        Dependent::new(
+            DeletableEntity::PromoStore,
+            format!("{}-{}", link.promo_id, link.store_id),
+            format!("store '{}'", route),
+            DependentAction::Delete,
+        )
+    }
+
+    fn change_dependent(change: &PromoTenorChange) -> Dependent {
+        Dependent::new(
+            DeletableEntity::PromoTenorChange,
+            change.id,
+            format!("pending change effective {}", change.effective_at),
+            DependentAction::Cancel,
+        )
+    }
+
+    async fn store_routes(&self) -> Result<HashMap<Uuid, String>, AppError> {
+        Ok(self
+            .all_stores()
+            .await?
+            .into_iter()
+            .map(|s| (s.id, s.route.unwrap_or(s.name)))
+            .collect())
+    }
+
+    async fn all_promos(&self) -> Result<Vec<Promo>, AppError> {
+        match self.promo_repo.rep_fetch_all().await {
+            Ok(promos) => Ok(promos),
+            Err(AppError::Promo(PromoError::NotFound(_))) => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn all_promo_tenors(&self) -> Result<Vec<PromoTenor>, AppError> {
+        match self.promo_tenor_repo.rep_fetch_all().await {
+            Ok(tenors) => Ok(tenors),
+            Err(AppError::PromoTenor(PromoTenorError::NotFound(_))) => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn all_promo_stores(&self) -> Result<Vec<PromoStore>, AppError> {
+        match self.promo_store_repo.rep_fetch_all().await {
+            Ok(links) => Ok(links),
+            Err(AppError::PromoStore(PromoStoreError::NotFound(_))) => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn all_stores(&self) -> Result<Vec<Store>, AppError> {
+        match self.store_repo.rep_fetch_all().await {
+            Ok(stores) => Ok(stores),
+            Err(AppError::Store(StoreError::NotFound(_))) => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -0,0 +1,672 @@
+use crate::disclosure;
+use crate::error::AppError;
+use crate::model::installment_model::*;
+use crate::model::money_model::{Decimal, Money};
+use crate::model::promo_model::{AdminPromoType, DiscountPromoType, Promo, PromoStatus};
+use crate::model::promo_tenor_model::{PromoTenor, StorePromoTenor};
+use crate::model::store_model::Store;
+use crate::repositories::promo_repository::PromoRepository;
+use crate::repositories::promo_tenor_repository::PromoTenorRepository;
+use crate::repositories::store_repository::StoreRepository;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use tracing::info;
+use uuid::Uuid;
+
+static SCHEDULE_ROUNDING_UNIT: OnceLock<i64> = OnceLock::new();
+
+/// Batas atas satuan pembulatan jadwal; nilai lebih besar membuat pembulatan overflow
+const MAX_SCHEDULE_ROUNDING_UNIT: i64 = 1_000_000;
+
+/// Satuan pembulatan rupiah untuk jadwal cicilan, dari env `SCHEDULE_ROUNDING_UNIT` (default 1)
+fn schedule_rounding_unit() -> i64 {
+    *SCHEDULE_ROUNDING_UNIT.get_or_init(|| {
+        let unit = std::env::var("SCHEDULE_ROUNDING_UNIT")
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|unit| (1..=MAX_SCHEDULE_ROUNDING_UNIT).contains(unit))
+            .unwrap_or(1);
+        info!("Schedule rounding unit configured: {}", unit);
+        unit
+    })
+}
+
+pub struct InstallmentService {
+    store_repo: Arc<StoreRepository>,
+    promo_repo: Arc<PromoRepository>,
+    promo_tenor_repo: Arc<PromoTenorRepository>,
+}
+
+impl InstallmentService {
+    pub fn new(
+        store_repo: Arc<StoreRepository>,
+        promo_repo: Arc<PromoRepository>,
+        promo_tenor_repo: Arc<PromoTenorRepository>,
+    ) -> Self {
+        Self {
+            store_repo,
+            promo_repo,
+            promo_tenor_repo,
+        }
+    }
+
+    pub async fn ser_simulate_installment(
+        &self,
+        payload: SimulateInstallmentPayload,
+    ) -> Result<InstallmentSimulation, AppError> {
+        Self::validate_amount(payload.amount)?;
+
+        let (store, promos, tenors) = self.load_store_offers(&payload.store_route).await?;
+
+        let mut installments: Vec<TenorInstallment> = tenors
+            .iter()
+            .filter(|st| payload.tenor.is_none_or(|tenor| st.promo_tenor.tenor == tenor))
+            .filter(|st| st.promo_tenor.min_transaction <= payload.amount)
+            .filter_map(|st| {
+                promos
+                    .get(&st.promo_tenor.promo_id)
+                    .filter(|promo| promo.is_live())
+                    .map(|promo| Self::calculate_store_installment(promo, st, payload.amount))
+            })
+            .collect::<Result<_, _>>()?;
+
+        installments.sort_by(|a, b| {
+            a.tenor
+                .cmp(&b.tenor)
+                .then(a.monthly_installment.cmp(&b.monthly_installment))
+        });
+
+        Ok(InstallmentSimulation {
+            store_id: store.id,
+            store_route: payload.store_route,
+            amount: payload.amount,
+            installments,
+        })
+    }
+
+    /// Rangking semua kombinasi promo/tenor yang terhubung ke store.
+    /// Kandidat yang tidak eligible tetap dikembalikan beserta alasannya.
+    pub async fn ser_recommend_promo(
+        &self,
+        payload: RecommendPromoPayload,
+    ) -> Result<PromoRecommendationResult, AppError> {
+        Self::validate_amount(payload.amount)?;
+
+        let (store, promos, tenors) = self.load_store_offers(&payload.store_route).await?;
+        let now = Utc::now();
+
+        let mut candidates: Vec<PromoRecommendation> = tenors
+            .iter()
+            .filter_map(|st| {
+                let t = &st.promo_tenor;
+                let promo = promos.get(&t.promo_id)?;
+                let mut reasons = Self::eligibility_reasons(promo, t, payload.amount, now);
+                let eligible = reasons.is_empty();
+
+                if let Some(preferred) = payload.preferred_tenor
+                    && preferred != t.tenor
+                {
+                    reasons.push(format!(
+                        "tenor {} differs from preferred tenor {}",
+                        t.tenor, preferred
+                    ));
+                }
+
+                Some(
+                    Self::calculate_store_installment(promo, st, payload.amount).map(|installment| {
+                        PromoRecommendation {
+                            rank: None,
+                            eligible,
+                            reasons,
+                            installment,
+                        }
+                    }),
+                )
+            })
+            .collect::<Result<_, _>>()?;
+
+        let sort_key = |c: &PromoRecommendation| match payload.sort_by {
+            RecommendSortBy::TotalCost => (
+                c.installment.total_payable,
+                c.installment.monthly_installment,
+            ),
+            RecommendSortBy::MonthlyPayment => (
+                c.installment.monthly_installment,
+                c.installment.total_payable,
+            ),
+        };
+        candidates.sort_by(|a, b| {
+            b.eligible
+                .cmp(&a.eligible)
+                .then_with(|| {
+                    let a_preferred = payload.preferred_tenor.is_none_or(|t| t == a.installment.tenor);
+                    let b_preferred = payload.preferred_tenor.is_none_or(|t| t == b.installment.tenor);
+                    b_preferred.cmp(&a_preferred)
+                })
+                .then_with(|| sort_key(a).cmp(&sort_key(b)))
+                .then(a.installment.tenor.cmp(&b.installment.tenor))
+        });
+
+        for (index, candidate) in candidates.iter_mut().filter(|c| c.eligible).enumerate() {
+            candidate.rank = Some(index + 1);
+        }
+
+        let recommended = candidates.first().filter(|c| c.eligible).cloned();
+
+        Ok(PromoRecommendationResult {
+            store_id: store.id,
+            store_route: payload.store_route,
+            amount: payload.amount,
+            preferred_tenor: payload.preferred_tenor,
+            sort_by: payload.sort_by,
+            recommended,
+            candidates,
+        })
+    }
+
+    /// Alasan promo tenor (term efektif store) tidak bisa dipakai untuk `amount`; kosong = eligible
+    pub fn eligibility_reasons(promo: &Promo, tenor: &PromoTenor, amount: i64, now: DateTime<Utc>) -> Vec<String> {
+        let mut reasons = Vec::new();
+
+        if !promo.is_published() {
+            reasons.push("promo is not published".to_string());
+        }
+        if !promo.is_active {
+            reasons.push("promo is not active".to_string());
+        }
+        if promo.status_at(now) != PromoStatus::Active {
+            reasons.push("outside date window".to_string());
+        }
+        if promo.budget_exhausted {
+            reasons.push("promo budget exhausted".to_string());
+        }
+        if tenor.min_transaction > amount {
+            reasons.push(format!(
+                "min_transaction not met (minimum {})",
+                tenor.min_transaction
+            ));
+        }
+        reasons
+    }
+
+    /// Jadwal cicilan per bulan untuk satu promo tenor
+    pub async fn ser_get_installment_schedule(
+        &self,
+        promo_tenor_id: Uuid,
+        query: InstallmentScheduleQuery,
+    ) -> Result<InstallmentSchedule, AppError> {
+        Self::validate_amount(query.amount)?;
+        let rounding_unit = match query.rounding {
+            Some(unit) if !(1..=MAX_SCHEDULE_ROUNDING_UNIT).contains(&unit) => {
+                return Err(AppError::BadRequest(format!(
+                    "rounding must be between 1 and {}, got: {}",
+                    MAX_SCHEDULE_ROUNDING_UNIT, unit
+                )));
+            }
+            Some(unit) => unit,
+            None => schedule_rounding_unit(),
+        };
+
+        let promo_tenor = match query.store_id {
+            Some(store_id) => self
+                .promo_tenor_repo
+                .rep_fetch_by_store_id(store_id)
+                .await?
+                .into_iter()
+                .map(|st| st.promo_tenor)
+                .find(|t| t.id == promo_tenor_id)
+                .ok_or_else(|| {
+                    AppError::BadRequest(format!(
+                        "Promo tenor '{}' is not available at store '{}'",
+                        promo_tenor_id, store_id
+                    ))
+                })?,
+            None => self.promo_tenor_repo.rep_fetch_by_id(promo_tenor_id).await?,
+        };
+        let promo = self.promo_repo.rep_get_by_id(promo_tenor.promo_id).await?;
+        // Endpoint publik: sama seperti simulasi dan lead, hanya promo published yang berlaku
+        let mut reasons = Self::eligibility_reasons(&promo, &promo_tenor, query.amount, Utc::now());
+        if !promo_tenor.is_available {
+            reasons.push("tenor is not available".to_string());
+        }
+        if !reasons.is_empty() {
+            return Err(AppError::BadRequest(format!(
+                "Promo tenor '{}' is not eligible: {}",
+                promo_tenor_id,
+                reasons.join("; ")
+            )));
+        }
+
+        let mut summary = Self::calculate_installment(&promo, &promo_tenor, query.amount)?;
+        let (periods, totals) = Self::build_schedule(&summary, rounding_unit);
+        // Pembulatan `rounding` menggeser cicilan antar bulan; ringkasan mengikuti baris jadwal
+        summary.total_payable = totals.amount_due;
+        summary.disclosure = Some(disclosure::disclose(&summary, query.amount));
+
+        Ok(InstallmentSchedule {
+            promo_tenor_id,
+            promo_id: promo.id_promo,
+            title_promo: promo.title_promo.clone(),
+            tenor: promo_tenor.tenor,
+            amount: query.amount,
+            store_id: query.store_id,
+            rounding_unit,
+            summary,
+            periods,
+            totals,
+        })
+    }
+
+    /// Bagi cicilan flat ke tiap bulan.
+    ///
+    /// - Cicilan bulan 1..n-1 = `monthly_installment` dibulatkan ke atas ke `unit`;
+    ///   bulan terakhir menampung sisa supaya total pokok + bunga tepat.
+    /// - Bunga customer (setelah subsidi) dibagi rata, sisanya adalah pokok.
+    /// - Admin dibayar di bulan pertama; cicilan gratis adalah bulan-bulan terakhir.
+    fn build_schedule(summary: &TenorInstallment, unit: i64) -> (Vec<SchedulePeriod>, ScheduleTotals) {
+        let months = summary.tenor.max(1);
+        let customer_interest = summary.total_interest - summary.subsidy;
+        let total_owed = summary.financed_amount + customer_interest;
+
+        let round_up = |value: i64| (value + unit - 1).div_euclid(unit) * unit;
+        let round = |value: f64| ((value / unit as f64).round() as i64) * unit;
+
+        let regular_interest = round(customer_interest as f64 / months as f64);
+        let first_free_period = months - summary.free_installment + 1;
+        let installments =
+            Self::split_installments(total_owed, round_up(summary.monthly_installment), months);
+
+        let mut periods = Vec::with_capacity(months as usize);
+        let mut totals = ScheduleTotals::default();
+        let mut remaining_principal = summary.financed_amount;
+        let mut remaining_interest = customer_interest;
+
+        for (period, installment) in (1..=months).zip(installments) {
+            let is_last = period == months;
+            let interest = if is_last {
+                remaining_interest
+            } else {
+                regular_interest.min(remaining_interest.max(0))
+            };
+            let principal = installment - interest;
+            let fee = if period == 1 { summary.admin_fee } else { 0 };
+            let is_free = period >= first_free_period;
+            let amount_due = if is_free { fee } else { installment + fee };
+
+            remaining_interest -= interest;
+            remaining_principal -= principal;
+
+            totals.principal += principal;
+            totals.interest += interest;
+            totals.fees += fee;
+            if is_free {
+                totals.waived += installment;
+            }
+            totals.amount_due += amount_due;
+
+            periods.push(SchedulePeriod {
+                period,
+                principal,
+                interest,
+                fee,
+                installment,
+                is_free,
+                amount_due,
+                remaining_principal: remaining_principal.max(0),
+            });
+        }
+
+        (periods, totals)
+    }
+
+    /// Cicilan per bulan: bulan 1..n-1 = `regular` (tidak melebihi sisa utang), bulan
+    /// terakhir menampung sisanya sehingga jumlahnya tepat `total_owed`
+    pub(crate) fn split_installments(total_owed: i64, regular: i64, months: i32) -> Vec<i64> {
+        let mut remaining = total_owed;
+        (1..=months)
+            .map(|period| {
+                let installment = if period == months {
+                    remaining.max(0)
+                } else {
+                    regular.min(remaining.max(0))
+                };
+                remaining -= installment;
+                installment
+            })
+            .collect()
+    }
+
+    fn validate_amount(amount: i64) -> Result<(), AppError> {
+        if amount <= 0 {
+            return Err(AppError::BadRequest(format!(
+                "Amount must be greater than 0, got: {}",
+                amount
+            )));
+        }
+        Ok(())
+    }
+
+    async fn load_store_offers(
+        &self,
+        store_route: &str,
+    ) -> Result<(Store, HashMap<Uuid, Promo>, Vec<StorePromoTenor>), AppError> {
+        let store = self.store_repo.rep_fetch_by_route(store_route).await?;
+
+        // Jalur publik: promo yang belum published tidak boleh muncul, bahkan sebagai kandidat
+        let promos: HashMap<Uuid, Promo> = self
+            .promo_repo
+            .rep_get_by_store_id(store.id)
+            .await?
+            .into_iter()
+            .filter(|p| p.is_published())
+            .map(|p| (p.id_promo, p))
+            .collect();
+
+        let tenors = self.promo_tenor_repo.rep_fetch_by_store_id(store.id).await?;
+
+        Ok((store, promos, tenors))
+    }
+
+    /// Seperti `calculate_installment`, memakai term efektif store beserta sumbernya
+    pub fn calculate_store_installment(
+        promo: &Promo,
+        store_tenor: &StorePromoTenor,
+        amount: i64,
+    ) -> Result<TenorInstallment, AppError> {
+        let mut installment = Self::calculate_installment(promo, &store_tenor.promo_tenor, amount)?;
+        installment.term_sources = Some(store_tenor.term_sources);
+        Ok(installment)
+    }
+
+    /// Hitung cicilan untuk satu tenor.
+    ///
+    /// - `discount` mengikuti `discount_type` (FIX = rupiah, PERCENT = % dari amount),
+    ///   dibatasi `max_discount` jika lebih dari 0.
+    /// - `interest_rate` adalah bunga flat per bulan (%) dari pokok setelah diskon.
+    /// - `subsidi` adalah % pokok yang ditanggung merchant, mengurangi bunga customer.
+    /// - `admin` mengikuti `admin_promo_type` dan dibayar sekali di luar cicilan.
+    /// - `free_installment` cicilan terakhir dibebaskan dari total bayar.
+    ///
+    /// Nominal yang membuat aritmetika fixed-point overflow ditolak sebagai bad request.
+    pub fn calculate_installment(
+        promo: &Promo,
+        tenor: &PromoTenor,
+        amount: i64,
+    ) -> Result<TenorInstallment, AppError> {
+        let checked = |value: Option<Decimal>| {
+            value.ok_or_else(|| {
+                AppError::BadRequest(format!("Amount {} is too large to calculate installments", amount))
+            })
+        };
+        let months = tenor.tenor.max(1) as i64;
+        let amount_d = checked(Money::from_int(amount))?;
+
+        let raw_discount = match promo.discount_type {
+            DiscountPromoType::FIX => tenor.discount,
+            DiscountPromoType::PERCENT => checked(amount_d.percent(tenor.discount))?,
+        };
+        let mut discount = raw_discount.round_to_int();
+        if tenor.max_discount.is_positive() {
+            discount = discount.min(tenor.max_discount.round_to_int());
+        }
+        let discount = discount.clamp(0, amount);
+
+        let financed_amount = amount - discount;
+        let financed_d = checked(Money::from_int(financed_amount))?;
+
+        let admin_fee = match promo.admin_promo_type {
+            AdminPromoType::FIX => tenor.admin,
+            AdminPromoType::PERCENT => checked(financed_d.percent(tenor.admin))?,
+        }
+        .round_to_int()
+        .max(0);
+
+        let monthly_interest = checked(financed_d.percent(promo.interest_rate))?;
+        let total_interest =
+            checked(monthly_interest.checked_mul(checked(Decimal::from_int(months))?))?.round_to_int();
+        let subsidy = checked(financed_d.percent(tenor.subsidi))?
+            .round_to_int()
+            .clamp(0, total_interest.max(0));
+
+        let customer_principal = financed_amount + total_interest - subsidy;
+        let monthly_installment = checked(
+            checked(Money::from_int(customer_principal))?.checked_div(checked(Decimal::from_int(months))?),
+        )?
+        .ceil_to_int();
+
+        let free_installment = tenor.free_installment.clamp(0, tenor.tenor.max(1));
+        let paid_months = months - free_installment as i64;
+        // Dari baris jadwal (bulan terakhir menampung sisa), bukan `monthly_installment` x bulan
+        let total_payable = Self::split_installments(customer_principal, monthly_installment, months as i32)
+            .into_iter()
+            .take(paid_months as usize)
+            .sum::<i64>()
+            + admin_fee;
+
+        let mut installment = TenorInstallment {
+            promo_id: promo.id_promo,
+            title_promo: promo.title_promo.clone(),
+            promo_tenor_id: tenor.id,
+            tenor: tenor.tenor,
+            voucher_code: tenor.voucher_code.clone(),
+            interest_rate: promo.interest_rate,
+            discount,
+            financed_amount,
+            admin_fee,
+            total_interest,
+            subsidy,
+            monthly_installment,
+            free_installment,
+            total_payable,
+            term_sources: None,
+            disclosure: None,
+        };
+        installment.disclosure = Some(disclosure::disclose(&installment, amount));
+        Ok(installment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::promo_model::PromoLifecycle;
+    use AdminPromoType as Admin;
+    use DiscountPromoType as Discount;
+
+    fn promo(interest_rate: &str, admin_promo_type: AdminPromoType, discount_type: DiscountPromoType) -> Promo {
+        Promo {
+            id_promo: Uuid::nil(),
+            title_promo: "Test".to_string(),
+            admin_promo_type,
+            interest_rate: interest_rate.parse().unwrap(),
+            discount_type,
+            is_active: true,
+            start_date_promo: None,
+            end_date_promo: None,
+            created_at: None,
+            updated_at: None,
+            lifecycle: PromoLifecycle::Published,
+            budget_max_subsidy: None,
+            budget_max_transactions: None,
+            budget_exhausted: false,
+            status: PromoStatus::Active,
+        }
+    }
+
+    fn tenor(months: i32, subsidi: &str, admin: &str, discount: &str, max_discount: &str, free: i32) -> PromoTenor {
+        PromoTenor {
+            id: Uuid::nil(),
+            promo_id: Uuid::nil(),
+            tenor: months,
+            min_transaction: Decimal::ZERO,
+            subsidi: subsidi.parse().unwrap(),
+            admin: admin.parse().unwrap(),
+            discount: discount.parse().unwrap(),
+            max_discount: max_discount.parse().unwrap(),
+            voucher_code: None,
+            voucher_max_usage: None,
+            voucher_max_usage_per_store: None,
+            voucher_expires_at: None,
+            free_installment: free,
+            is_available: true,
+            created_at: None,
+            updated_at: None,
+            disclosure: None,
+        }
+    }
+
+    #[test]
+    fn zero_interest_splits_amount_evenly() {
+        let result = InstallmentService::calculate_installment(
+            &promo("0", Admin::FIX, Discount::FIX),
+            &tenor(12, "0", "0", "0", "0", 0),
+            12_000_000,
+        )
+        .unwrap();
+        assert_eq!(result.total_interest, 0);
+        assert_eq!(result.subsidy, 0);
+        assert_eq!(result.monthly_installment, 1_000_000);
+        assert_eq!(result.total_payable, 12_000_000);
+    }
+
+    #[test]
+    fn percent_discount_is_capped_by_max_discount() {
+        let capped = InstallmentService::calculate_installment(
+            &promo("0", Admin::FIX, Discount::PERCENT),
+            &tenor(10, "0", "0", "10", "500000", 0),
+            10_000_000,
+        )
+        .unwrap();
+        assert_eq!(capped.discount, 500_000);
+        assert_eq!(capped.financed_amount, 9_500_000);
+
+        // max_discount 0 = tanpa batas
+        let uncapped = InstallmentService::calculate_installment(
+            &promo("0", Admin::FIX, Discount::PERCENT),
+            &tenor(10, "0", "0", "10", "0", 0),
+            10_000_000,
+        )
+        .unwrap();
+        assert_eq!(uncapped.discount, 1_000_000);
+    }
+
+    #[test]
+    fn discount_never_exceeds_amount() {
+        let result = InstallmentService::calculate_installment(
+            &promo("0", Admin::FIX, Discount::FIX),
+            &tenor(3, "0", "0", "5000000", "0", 0),
+            1_000_000,
+        )
+        .unwrap();
+        assert_eq!(result.discount, 1_000_000);
+        assert_eq!(result.financed_amount, 0);
+    }
+
+    #[test]
+    fn free_installment_is_clamped_to_tenor() {
+        let result = InstallmentService::calculate_installment(
+            &promo("1.5", Admin::FIX, Discount::FIX),
+            &tenor(6, "0", "50000", "0", "0", 10),
+            6_000_000,
+        )
+        .unwrap();
+        assert_eq!(result.free_installment, 6);
+        // Semua cicilan gratis, customer hanya membayar admin
+        assert_eq!(result.total_payable, 50_000);
+    }
+
+    #[test]
+    fn subsidy_reduces_customer_interest() {
+        let result = InstallmentService::calculate_installment(
+            &promo("1.5", Admin::PERCENT, Discount::FIX),
+            &tenor(12, "2", "1", "0", "0", 0),
+            10_000_000,
+        )
+        .unwrap();
+        assert_eq!(result.total_interest, 1_800_000);
+        assert_eq!(result.subsidy, 200_000);
+        assert_eq!(result.admin_fee, 100_000);
+        assert_eq!(result.monthly_installment, 966_667);
+        assert_eq!(result.total_payable, 11_600_000 + 100_000);
+    }
+
+    #[test]
+    fn subsidy_above_total_interest_is_clamped() {
+        let result = InstallmentService::calculate_installment(
+            &promo("1.5", Admin::FIX, Discount::FIX),
+            &tenor(12, "50", "0", "0", "0", 0),
+            10_000_000,
+        )
+        .unwrap();
+        assert_eq!(result.subsidy, result.total_interest);
+        assert_eq!(result.monthly_installment, 833_334);
+        assert_eq!(result.total_payable, 10_000_000);
+    }
+
+    fn schedule(
+        promo: &Promo,
+        tenor: &PromoTenor,
+        amount: i64,
+        unit: i64,
+    ) -> (TenorInstallment, Vec<SchedulePeriod>, ScheduleTotals) {
+        let summary = InstallmentService::calculate_installment(promo, tenor, amount).unwrap();
+        let (periods, totals) = InstallmentService::build_schedule(&summary, unit);
+        (summary, periods, totals)
+    }
+
+    #[test]
+    fn schedule_last_period_takes_remainder() {
+        let (summary, periods, totals) =
+            schedule(&promo("1.5", Admin::FIX, Discount::FIX), &tenor(12, "0", "0", "0", "0", 0), 10_000_000, 1);
+        assert_eq!(periods.len(), 12);
+        assert!(periods[..11].iter().all(|p| p.installment == 983_334 && p.interest == 150_000));
+        assert_eq!(periods[11].installment, 983_326);
+        assert_eq!(periods[11].remaining_principal, 0);
+        assert_eq!(totals.principal, 10_000_000);
+        assert_eq!(totals.interest, 1_800_000);
+        assert_eq!(totals.amount_due, summary.total_payable);
+    }
+
+    #[test]
+    fn schedule_rounds_regular_installments_up_to_unit() {
+        let (_, periods, totals) =
+            schedule(&promo("1.5", Admin::FIX, Discount::FIX), &tenor(12, "0", "0", "0", "0", 0), 10_000_000, 1_000);
+        assert!(periods[..11].iter().all(|p| p.installment == 984_000));
+        assert_eq!(periods[11].installment, 976_000);
+        assert_eq!(periods.iter().map(|p| p.installment).sum::<i64>(), 11_800_000);
+        assert_eq!(totals.principal + totals.interest, 11_800_000);
+    }
+
+    #[test]
+    fn schedule_waives_last_installments_but_keeps_admin_fee() {
+        let (summary, periods, totals) =
+            schedule(&promo("0", Admin::FIX, Discount::FIX), &tenor(6, "0", "50000", "0", "0", 2), 6_000_000, 1);
+        assert_eq!(periods[0].fee, 50_000);
+        assert_eq!(periods[0].amount_due, 1_050_000);
+        assert!(periods[..4].iter().all(|p| !p.is_free));
+        assert!(periods[4..].iter().all(|p| p.is_free && p.amount_due == 0));
+        assert_eq!(totals.waived, 2_000_000);
+        assert_eq!(totals.amount_due, 4_050_000);
+        assert_eq!(totals.amount_due, summary.total_payable);
+    }
+
+    #[test]
+    fn schedule_with_every_installment_free_only_charges_admin() {
+        let (summary, periods, totals) =
+            schedule(&promo("1.5", Admin::FIX, Discount::FIX), &tenor(6, "0", "50000", "0", "0", 10), 6_000_000, 1);
+        assert!(periods.iter().all(|p| p.is_free));
+        assert_eq!(totals.amount_due, 50_000);
+        assert_eq!(totals.amount_due, summary.total_payable);
+    }
+
+    #[test]
+    fn overflowing_amount_is_bad_request() {
+        let result = InstallmentService::calculate_installment(
+            &promo("1.5", Admin::FIX, Discount::FIX),
+            &tenor(12, "0", "0", "0", "0", 0),
+            i64::MAX,
+        );
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+}
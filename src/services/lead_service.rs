@@ -0,0 +1,253 @@
+use crate::error::{AppError, LeadError};
+use crate::middleware::Actor;
+use crate::model::lead_model::*;
+use crate::model::store_assignment_model::StoreScope;
+use crate::repositories::lead_repository::LeadRepository;
+use crate::repositories::promo_repository::PromoRepository;
+use crate::repositories::promo_tenor_repository::PromoTenorRepository;
+use crate::repositories::store_repository::StoreRepository;
+use crate::services::installment_service::InstallmentService;
+use crate::services::store_assignment_service::StoreAssignmentService;
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+const MAX_NAME_LENGTH: usize = 100;
+const MAX_EMAIL_LENGTH: usize = 254;
+const MAX_MESSAGE_LENGTH: usize = 1000;
+
+pub struct LeadService {
+    repo: Arc<LeadRepository>,
+    store_repo: Arc<StoreRepository>,
+    promo_repo: Arc<PromoRepository>,
+    promo_tenor_repo: Arc<PromoTenorRepository>,
+}
+
+impl LeadService {
+    pub fn new(
+        repo: Arc<LeadRepository>,
+        store_repo: Arc<StoreRepository>,
+        promo_repo: Arc<PromoRepository>,
+        promo_tenor_repo: Arc<PromoTenorRepository>,
+    ) -> Self {
+        Self {
+            repo,
+            store_repo,
+            promo_repo,
+            promo_tenor_repo,
+        }
+    }
+
+    /// Lead dari halaman publik. Promo tenor harus tersedia di store dan lolos aturan
+    /// eligibility yang sama dengan simulasi/rekomendasi (term efektif store).
+    pub async fn ser_submit_lead(&self, payload: SubmitLeadPayload) -> Result<LeadReceipt, AppError> {
+        let contact = Self::validate_contact(&payload)?;
+        if payload.amount <= 0 {
+            return Err(LeadError::InvalidPayload(format!(
+                "Amount must be greater than 0, got: {}",
+                payload.amount
+            ))
+            .into());
+        }
+
+        let store = self.store_repo.rep_fetch_by_route(&payload.store_route).await?;
+        let store_tenor = self
+            .promo_tenor_repo
+            .rep_fetch_by_store_id(store.id)
+            .await?
+            .into_iter()
+            .find(|st| st.promo_tenor.id == payload.promo_tenor_id)
+            .ok_or_else(|| {
+                LeadError::NotApplicable(format!(
+                    "promo tenor '{}' is not available at store '{}'",
+                    payload.promo_tenor_id, payload.store_route
+                ))
+            })?;
+
+        let promo = self.promo_repo.rep_get_by_id(store_tenor.promo_tenor.promo_id).await?;
+        let reasons =
+            InstallmentService::eligibility_reasons(&promo, &store_tenor.promo_tenor, payload.amount, Utc::now());
+        if !reasons.is_empty() {
+            return Err(LeadError::NotApplicable(reasons.join("; ")).into());
+        }
+
+        let installment = InstallmentService::calculate_store_installment(&promo, &store_tenor, payload.amount)?;
+        let lead = self
+            .repo
+            .rep_insert(CreateLeadRow {
+                store_id: store.id,
+                promo_id: promo.id_promo,
+                promo_tenor_id: store_tenor.promo_tenor.id,
+                tenor: store_tenor.promo_tenor.tenor,
+                amount: payload.amount,
+                monthly_installment: installment.monthly_installment,
+                customer_name: contact.customer_name,
+                phone: contact.phone,
+                email: contact.email,
+                message: contact.message,
+                status: LeadStatus::New,
+            })
+            .await?;
+
+        info!("Lead {} masuk untuk store {}.", lead.id, payload.store_route);
+        Ok(LeadReceipt::from(&lead))
+    }
+
+    /// Data kontak pelanggan: non-admin hanya melihat lead store dalam cakupannya
+    pub async fn ser_get_leads(
+        &self,
+        store_id: Option<Uuid>,
+        status: Option<LeadStatus>,
+        assigned_to: Option<&str>,
+        scope: &StoreScope,
+    ) -> Result<Vec<Lead>, AppError> {
+        if let Some(store_id) = store_id {
+            StoreAssignmentService::ensure_store(scope, store_id)?;
+        }
+        let mut leads = self.repo.rep_fetch_all(store_id, status, assigned_to).await?;
+        leads.retain(|lead| scope.allows_store(lead.store_id));
+        Ok(leads)
+    }
+
+    pub async fn ser_get_lead_by_id(&self, id: Uuid, scope: &StoreScope) -> Result<Lead, AppError> {
+        let lead = self.repo.rep_fetch_by_id(id).await?;
+        StoreAssignmentService::ensure_store(scope, lead.store_id)?;
+        Ok(lead)
+    }
+
+    pub async fn ser_assign_lead(
+        &self,
+        id: Uuid,
+        payload: AssignLeadPayload,
+        actor: &Actor,
+        scope: &StoreScope,
+    ) -> Result<Lead, AppError> {
+        let lead = self.ser_get_lead_by_id(id, scope).await?;
+        if lead.status.is_final() {
+            return Err(LeadError::Conflict(format!(
+                "Lead '{}' is already {}",
+                id,
+                lead.status.as_str()
+            ))
+            .into());
+        }
+
+        let assigned_to = payload
+            .assigned_to
+            .map(|a| a.trim().to_string())
+            .filter(|a| !a.is_empty());
+        let now = Utc::now().to_rfc3339();
+        let row = UpdateLeadRow {
+            assigned_at: Some(assigned_to.as_ref().map(|_| now.clone())),
+            assigned_to: Some(assigned_to),
+            updated_by: actor.user_id.clone(),
+            updated_at: now,
+            ..Default::default()
+        };
+
+        self.update_if_unchanged(&lead, row).await
+    }
+
+    pub async fn ser_update_lead_status(
+        &self,
+        id: Uuid,
+        payload: UpdateLeadStatusPayload,
+        actor: &Actor,
+        scope: &StoreScope,
+    ) -> Result<Lead, AppError> {
+        let lead = self.ser_get_lead_by_id(id, scope).await?;
+        if !lead.status.can_transition_to(payload.status) {
+            return Err(LeadError::Conflict(format!(
+                "Cannot change lead status from {} to {}",
+                lead.status.as_str(),
+                payload.status.as_str()
+            ))
+            .into());
+        }
+
+        let row = UpdateLeadRow {
+            status: Some(payload.status),
+            status_note: payload.note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+            updated_by: actor.user_id.clone(),
+            updated_at: Utc::now().to_rfc3339(),
+            ..Default::default()
+        };
+
+        let updated = self.update_if_unchanged(&lead, row).await?;
+        info!(
+            "Lead {} {} -> {} oleh {}.",
+            id,
+            lead.status.as_str(),
+            payload.status.as_str(),
+            actor.user_id
+        );
+        Ok(updated)
+    }
+
+    async fn update_if_unchanged(&self, lead: &Lead, row: UpdateLeadRow) -> Result<Lead, AppError> {
+        self.repo
+            .rep_update_if_status(lead.id, lead.status, row)
+            .await?
+            .ok_or_else(|| {
+                LeadError::Conflict(format!("Lead '{}' was changed concurrently, please retry", lead.id)).into()
+            })
+    }
+
+    /// Rapikan dan validasi data kontak; nomor telepon disimpan tanpa spasi/tanda hubung
+    fn validate_contact(payload: &SubmitLeadPayload) -> Result<SubmitLeadPayload, AppError> {
+        let invalid = |msg: String| -> AppError { LeadError::InvalidPayload(msg).into() };
+
+        let customer_name = payload.customer_name.trim().to_string();
+        if customer_name.is_empty() || customer_name.chars().count() > MAX_NAME_LENGTH {
+            return Err(invalid(format!(
+                "customer_name is required (max {} characters)",
+                MAX_NAME_LENGTH
+            )));
+        }
+
+        let phone: String = payload
+            .phone
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '(' | ')'))
+            .collect();
+        let digits = phone.strip_prefix('+').unwrap_or(&phone);
+        if !(8..=15).contains(&digits.len()) || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid(format!("phone must contain 8-15 digits, got: {}", payload.phone)));
+        }
+
+        let email = payload
+            .email
+            .as_deref()
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .map(str::to_string);
+        if let Some(email) = &email {
+            let valid = email.len() <= MAX_EMAIL_LENGTH
+                && email
+                    .split_once('@')
+                    .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.') && !domain.contains('@'));
+            if !valid {
+                return Err(invalid(format!("email is not valid: {}", email)));
+            }
+        }
+
+        let message = payload
+            .message
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .map(str::to_string);
+        if message.as_ref().is_some_and(|m| m.chars().count() > MAX_MESSAGE_LENGTH) {
+            return Err(invalid(format!("message must be at most {} characters", MAX_MESSAGE_LENGTH)));
+        }
+
+        Ok(SubmitLeadPayload {
+            customer_name,
+            phone,
+            email,
+            message,
+            ..payload.clone()
+        })
+    }
+}
@@ -1,4 +1,15 @@
+pub mod company_service;
+pub mod api_key_service;
+pub mod budget_service;
+pub mod conflict_service;
+pub mod delete_service;
+pub mod installment_service;
+pub mod lead_service;
 pub mod promo_service;
 pub mod promo_store_service;
 pub mod promo_tenor_service;
+pub mod settlement_service;
+pub mod store_assignment_service;
 pub mod store_service;
+pub mod version_service;
+pub mod voucher_service;
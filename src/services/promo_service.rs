@@ -1,16 +1,43 @@
-use crate::error::AppError;
+use crate::business_time::parse_business_datetime;
+use crate::error::{AppError, PromoError, PromoStoreError, PromoTenorError};
+use crate::middleware::Actor;
 use crate::model::promo_model::*;
+use crate::model::promo_store_model::{CreatePromoStorePayload, PromoStore, TenorOverride};
+use crate::model::budget_model::BudgetCaps;
+use crate::model::money_model::validate_percent;
+use crate::model::promo_tenor_model::{CreatePromoTenorPayload, PromoTenor, TenorTerms};
 use crate::repositories::promo_repository::PromoRepository;
+use crate::repositories::promo_store_repository::PromoStoreRepository;
+use crate::repositories::promo_tenor_repository::PromoTenorRepository;
+use crate::repositories::promo_transition_repository::PromoTransitionRepository;
+use chrono::Utc;
+use rand::Rng;
+use rand::distributions::Alphanumeric;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use tracing::{info, warn};
 use uuid::Uuid;
 
 pub struct PromoService {
     repo: Arc<PromoRepository>,
+    promo_tenor_repo: Arc<PromoTenorRepository>,
+    promo_store_repo: Arc<PromoStoreRepository>,
+    transition_repo: Arc<PromoTransitionRepository>,
 }
 
 impl PromoService {
-    pub fn new(repo: Arc<PromoRepository>) -> Self {
-        Self { repo }
+    pub fn new(
+        repo: Arc<PromoRepository>,
+        promo_tenor_repo: Arc<PromoTenorRepository>,
+        promo_store_repo: Arc<PromoStoreRepository>,
+        transition_repo: Arc<PromoTransitionRepository>,
+    ) -> Self {
+        Self {
+            repo,
+            promo_tenor_repo,
+            promo_store_repo,
+            transition_repo,
+        }
     }
 
     pub async fn ser_get_all_promos(&self) -> Result<Vec<Promo>, AppError> {
@@ -21,23 +48,386 @@ impl PromoService {
         self.repo.rep_get_by_id(id_promo).await
     }
 
+    /// Listing publik per store: hanya promo aktif di dalam window tanggal
     pub async fn ser_get_promos_by_store_id(&self, store_id: Uuid) -> Result<Vec<Promo>, AppError> {
-        self.repo.rep_get_by_store_id(store_id).await
+        let promos = self.repo.rep_get_by_store_id(store_id).await?;
+        Ok(promos.into_iter().filter(|p| p.is_live()).collect())
     }
 
-    pub async fn ser_create_promo(&self, payload: CreatePromoPayload) -> Result<Promo, AppError> {
-        self.repo.rep_insert(payload).await
+    /// Listing admin per tahap lifecycle (semua tahap jika `lifecycle` kosong)
+    pub async fn ser_get_promos_by_lifecycle(
+        &self,
+        lifecycle: Option<PromoLifecycle>,
+    ) -> Result<Vec<Promo>, AppError> {
+        let promos = match self.repo.rep_fetch_all().await {
+            Ok(promos) => promos,
+            Err(AppError::Promo(PromoError::NotFound(_))) => vec![],
+            Err(e) => return Err(e),
+        };
+        Ok(promos
+            .into_iter()
+            .filter(|p| lifecycle.is_none_or(|l| p.lifecycle == l))
+            .collect())
+    }
+
+    /// Promo baru selalu masuk sebagai draft; pembuatannya dicatat di log transisi
+    pub async fn ser_create_promo(&self, payload: CreatePromoPayload, actor: &Actor) -> Result<Promo, AppError> {
+        validate_percent("interest_rate", payload.interest_rate).map_err(PromoError::InvalidPayload)?;
+        BudgetCaps {
+            max_subsidy: payload.budget_max_subsidy,
+            max_transactions: payload.budget_max_transactions,
+        }
+        .validate()
+        .map_err(PromoError::InvalidPayload)?;
+        Self::validate_window(payload.start_date_promo.as_deref(), payload.end_date_promo.as_deref())?;
+        let payload = CreatePromoPayload {
+            lifecycle: PromoLifecycle::Draft,
+            ..payload
+        };
+        let created = self.repo.rep_insert(payload).await?;
+        self.record_creation(&created, actor, None).await;
+        Ok(created)
     }
 
+    /// Hanya promo draft yang boleh diedit; promo lain harus dikembalikan ke draft dulu,
+    /// kecuali update yang hanya mengubah batas anggaran
     pub async fn ser_update_promo(
         &self,
         id_promo: Uuid,
         payload: UpdatePromoPayload,
     ) -> Result<Promo, AppError> {
+        let current = self.repo.rep_get_by_id(id_promo).await?;
+        if !payload.is_budget_only() {
+            current.ensure_draft().map_err(PromoError::Conflict)?;
+        }
+        if let Some(rate) = payload.interest_rate {
+            validate_percent("interest_rate", rate).map_err(PromoError::InvalidPayload)?;
+        }
+        BudgetCaps {
+            max_subsidy: payload.budget_max_subsidy.flatten(),
+            max_transactions: payload.budget_max_transactions.flatten(),
+        }
+        .validate()
+        .map_err(PromoError::InvalidPayload)?;
+        let merged = current.with_update(&payload);
+        Self::validate_window(merged.start_date_promo.as_deref(), merged.end_date_promo.as_deref())?;
+        if payload.admin_promo_type.is_some() || payload.discount_type.is_some() {
+            self.validate_tenors_for_types(&current, &payload).await?;
+        }
         self.repo.rep_update_by_id(id_promo, payload).await
     }
 
-    pub async fn ser_delete_promo(&self, id_promo: Uuid) -> Result<(), AppError> {
-        self.repo.rep_delete_by_id(id_promo).await
+    /// Pindahkan promo ke tahap lifecycle lain sesuai aturan role di `PromoLifecycle::transition_roles`.
+    /// Jika log transisi gagal disimpan, lifecycle dikembalikan ke state semula.
+    pub async fn ser_transition_promo(
+        &self,
+        id_promo: Uuid,
+        payload: TransitionPromoPayload,
+        actor: &Actor,
+    ) -> Result<PromoTransitionResult, AppError> {
+        let promo = self.repo.rep_get_by_id(id_promo).await?;
+        let from = promo.lifecycle;
+        let to = payload.to;
+
+        let roles = from.transition_roles(to).ok_or_else(|| {
+            PromoError::Conflict(format!("cannot move promo from {} to {}", from.as_str(), to.as_str()))
+        })?;
+        if !actor.has_any_role(roles) {
+            return Err(AppError::Forbidden(format!(
+                "moving promo from {} to {} requires role {}",
+                from.as_str(),
+                to.as_str(),
+                roles.join(" or ")
+            )));
+        }
+
+        let updated = self.repo.rep_update_lifecycle(id_promo, from, to).await?;
+
+        let transition = CreatePromoTransitionPayload {
+            promo_id: id_promo,
+            from_state: Some(from),
+            to_state: to,
+            actor: actor.user_id.clone(),
+            note: payload.note.filter(|n| !n.trim().is_empty()),
+        };
+        let transition = match self.transition_repo.rep_insert(transition).await {
+            Ok(transition) => transition,
+            Err(e) => {
+                if let Err(rollback) = self.repo.rep_update_lifecycle(id_promo, to, from).await {
+                    warn!("Rollback lifecycle promo {} gagal: {}", id_promo, rollback);
+                }
+                return Err(e);
+            }
+        };
+
+        info!(
+            "Promo {} dipindah {} -> {} oleh {}",
+            id_promo,
+            from.as_str(),
+            to.as_str(),
+            actor.user_id
+        );
+        Ok(PromoTransitionResult {
+            promo: updated,
+            transition,
+        })
+    }
+
+    pub async fn ser_get_promo_transitions(&self, id_promo: Uuid) -> Result<Vec<PromoTransition>, AppError> {
+        self.repo.rep_get_by_id(id_promo).await?;
+        self.transition_repo.rep_fetch_by_promo_id(id_promo).await
+    }
+
+    /// Log pembuatan bersifat best-effort: promo draft tetap tersimpan walau log gagal
+    async fn record_creation(&self, promo: &Promo, actor: &Actor, note: Option<String>) {
+        let payload = CreatePromoTransitionPayload {
+            promo_id: promo.id_promo,
+            from_state: None,
+            to_state: promo.lifecycle,
+            actor: actor.user_id.clone(),
+            note,
+        };
+        if let Err(e) = self.transition_repo.rep_insert(payload).await {
+            warn!("Gagal mencatat pembuatan promo {}: {}", promo.id_promo, e);
+        }
+    }
+
+    /// Ganti tipe admin/discount (FIX <-> PERCENT) tidak boleh membuat tenor yang ada jadi invalid
+    async fn validate_tenors_for_types(
+        &self,
+        promo: &Promo,
+        payload: &UpdatePromoPayload,
+    ) -> Result<(), AppError> {
+        let id_promo = promo.id_promo;
+        let admin_type = payload.admin_promo_type.as_ref().unwrap_or(&promo.admin_promo_type);
+        let discount_type = payload.discount_type.as_ref().unwrap_or(&promo.discount_type);
+
+        let tenors = match self.promo_tenor_repo.rep_fetch_by_promo_id(id_promo).await {
+            Ok(tenors) => tenors,
+            Err(AppError::PromoTenor(PromoTenorError::NotFound(_))) => vec![],
+            Err(e) => return Err(e),
+        };
+        for tenor in &tenors {
+            TenorTerms::from(tenor)
+                .validate(admin_type, discount_type)
+                .map_err(|e| PromoError::InvalidPayload(format!("tenor {} ({} months): {}", tenor.id, tenor.tenor, e)))?;
+        }
+        Ok(())
+    }
+
+    /// Salin promo beserta tenor (dan opsional link store) menjadi promo baru.
+    /// Jika salah satu insert gagal, baris yang sudah dibuat dihapus lagi.
+    pub async fn ser_clone_promo(
+        &self,
+        id_promo: Uuid,
+        payload: ClonePromoPayload,
+        actor: &Actor,
+    ) -> Result<PromoGraph, AppError> {
+        let source = self.repo.rep_get_by_id(id_promo).await?;
+
+        let all_tenors = match self.promo_tenor_repo.rep_fetch_all().await {
+            Ok(tenors) => tenors,
+            Err(AppError::PromoTenor(PromoTenorError::NotFound(_))) => vec![],
+            Err(e) => return Err(e),
+        };
+        let source_tenors: Vec<&PromoTenor> =
+            all_tenors.iter().filter(|t| t.promo_id == id_promo).collect();
+
+        let source_links = if payload.include_store_links {
+            match self.promo_store_repo.rep_fetch_all().await {
+                Ok(links) => links.into_iter().filter(|ps| ps.promo_id == id_promo).collect(),
+                Err(AppError::PromoStore(PromoStoreError::NotFound(_))) => vec![],
+                Err(e) => return Err(e),
+            }
+        } else {
+            vec![]
+        };
+
+        let create = CreatePromoPayload {
+            title_promo: payload
+                .title_promo
+                .clone()
+                .unwrap_or_else(|| format!("{} (copy)", source.title_promo)),
+            admin_promo_type: source.admin_promo_type.clone(),
+            interest_rate: payload.interest_rate.unwrap_or(source.interest_rate),
+            discount_type: source.discount_type.clone(),
+            is_active: payload.is_active.unwrap_or(source.is_active),
+            start_date_promo: payload.start_date_promo.clone().or(source.start_date_promo.clone()),
+            end_date_promo: payload.end_date_promo.clone().or(source.end_date_promo.clone()),
+            // Batas anggaran ikut disalin, ledger promo baru mulai dari nol
+            budget_max_subsidy: source.budget_max_subsidy,
+            budget_max_transactions: source.budget_max_transactions,
+            // Hasil clone harus melewati review lagi sebelum tampil publik
+            lifecycle: PromoLifecycle::Draft,
+        };
+        Self::validate_clone(&create)?;
+
+        let promo = self.repo.rep_insert(create).await?;
+
+        let mut used_codes: HashSet<String> = all_tenors
+            .iter()
+            .filter_map(|t| t.voucher_code.clone())
+            .collect();
+        let mut tenors = Vec::with_capacity(source_tenors.len());
+        let mut tenor_id_map: HashMap<Uuid, Uuid> = HashMap::new();
+        let mut store_links = Vec::with_capacity(source_links.len());
+
+        let result: Result<(), AppError> = async {
+            for source_tenor in &source_tenors {
+                let voucher_code = source_tenor.voucher_code.as_deref().and_then(|code| {
+                    match payload.voucher_strategy {
+                        VoucherCloneStrategy::Clear => None,
+                        VoucherCloneStrategy::Regenerate => Some(Self::regenerate_voucher(code, &mut used_codes)),
+                    }
+                });
+                // Expiry yang sudah lewat tidak ikut disalin
+                let now = Utc::now();
+                let voucher_expires_at = source_tenor
+                    .voucher_expires_at
+                    .clone()
+                    .filter(|_| {
+                        voucher_code.is_some() && source_tenor.voucher_expires_at().is_none_or(|exp| exp > now)
+                    });
+
+                let created = self
+                    .promo_tenor_repo
+                    .rep_insert(CreatePromoTenorPayload {
+                        promo_id: promo.id_promo,
+                        tenor: source_tenor.tenor,
+                        min_transaction: source_tenor.min_transaction,
+                        subsidi: source_tenor.subsidi,
+                        admin: source_tenor.admin,
+                        discount: source_tenor.discount,
+                        max_discount: source_tenor.max_discount,
+                        voucher_max_usage: voucher_code.as_ref().and(source_tenor.voucher_max_usage),
+                        voucher_max_usage_per_store: voucher_code
+                            .as_ref()
+                            .and(source_tenor.voucher_max_usage_per_store),
+                        voucher_code,
+                        voucher_expires_at,
+                        free_installment: source_tenor.free_installment,
+                        is_available: source_tenor.is_available,
+                    })
+                    .await?;
+                tenor_id_map.insert(source_tenor.id, created.id);
+                tenors.push(created);
+            }
+
+            for link in &source_links {
+                let created = self
+                    .promo_store_repo
+                    .rep_insert(CreatePromoStorePayload {
+                        promo_id: promo.id_promo,
+                        store_id: link.store_id,
+                        tenor_ids: link.tenor_ids.as_ref().map(|ids| {
+                            ids.iter().filter_map(|id| tenor_id_map.get(id).copied()).collect()
+                        }),
+                        tenor_overrides: link.tenor_overrides.as_ref().map(|overrides| {
+                            overrides
+                                .iter()
+                                .filter_map(|o| {
+                                    tenor_id_map.get(&o.tenor_id).map(|new_id| TenorOverride {
+                                        tenor_id: *new_id,
+                                        ..o.clone()
+                                    })
+                                })
+                                .collect()
+                        }),
+                        budget_max_subsidy: link.budget_max_subsidy,
+                        budget_max_transactions: link.budget_max_transactions,
+                    })
+                    .await?;
+                store_links.push(created);
+            }
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            warn!("Clone promo {} gagal, rollback promo {}: {}", id_promo, promo.id_promo, e);
+            self.rollback_clone(&promo, &tenors, &store_links).await;
+            return Err(e);
+        }
+
+        // Isi ulang cache supaya lookup per store langsung melihat promo baru
+        let _ = self.repo.rep_fetch_all().await;
+        let _ = self.promo_tenor_repo.rep_fetch_all().await;
+        let _ = self.promo_store_repo.rep_fetch_all().await;
+
+        self.record_creation(&promo, actor, Some(format!("cloned from {}", id_promo))).await;
+
+        info!(
+            "Promo {} di-clone menjadi {} ({} tenor, {} link store).",
+            id_promo,
+            promo.id_promo,
+            tenors.len(),
+            store_links.len()
+        );
+
+        Ok(PromoGraph {
+            promo,
+            tenors,
+            store_links,
+        })
+    }
+
+    fn validate_clone(create: &CreatePromoPayload) -> Result<(), AppError> {
+        if create.title_promo.trim().is_empty() {
+            return Err(PromoError::InvalidPayload("title_promo must not be empty".to_string()).into());
+        }
+        validate_percent("interest_rate", create.interest_rate).map_err(PromoError::InvalidPayload)?;
+        Self::validate_window(create.start_date_promo.as_deref(), create.end_date_promo.as_deref())
+    }
+
+    /// Tanggal yang gagal di-parse akan membuat window tanpa batas, jadi ditolak saat ditulis
+    fn validate_window(start: Option<&str>, end: Option<&str>) -> Result<(), AppError> {
+        let start = start.map(|raw| (raw, parse_business_datetime(raw, false)));
+        let end = end.map(|raw| (raw, parse_business_datetime(raw, true)));
+        for (raw, parsed) in start.iter().chain(end.iter()) {
+            if parsed.is_none() {
+                return Err(PromoError::InvalidPayload(format!("invalid date '{}'", raw)).into());
+            }
+        }
+        if let (Some((_, Some(s))), Some((_, Some(e)))) = (start, end)
+            && s > e
+        {
+            return Err(PromoError::InvalidPayload(
+                "start_date_promo must not be after end_date_promo".to_string(),
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Prefix kode lama (tanpa suffix clone sebelumnya) + 6 karakter acak, dijamin unik
+    fn regenerate_voucher(code: &str, used_codes: &mut HashSet<String>) -> String {
+        let prefix = code.split('-').next().unwrap_or(code).to_uppercase();
+        loop {
+            let suffix: String = rand::thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(6)
+                .map(|c| (c as char).to_ascii_uppercase())
+                .collect();
+            let candidate = format!("{}-{}", prefix, suffix);
+            if used_codes.insert(candidate.clone()) {
+                return candidate;
+            }
+        }
+    }
+
+    async fn rollback_clone(&self, promo: &Promo, tenors: &[PromoTenor], store_links: &[PromoStore]) {
+        for link in store_links {
+            if let Err(e) = self.promo_store_repo.rep_delete_by_key(link.promo_id, link.store_id).await {
+                warn!("Rollback promo_store {} gagal: {}", link.id, e);
+            }
+        }
+        for tenor in tenors {
+            if let Err(e) = self.promo_tenor_repo.rep_delete_by_id(tenor.id).await {
+                warn!("Rollback promo_tenor {} gagal: {}", tenor.id, e);
+            }
+        }
+        if let Err(e) = self.repo.rep_delete_by_id(promo.id_promo).await {
+            warn!("Rollback promo {} gagal: {}", promo.id_promo, e);
+        }
     }
 }
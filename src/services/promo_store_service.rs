@@ -1,18 +1,39 @@
-use crate::error::AppError;
-use crate::model::promo_store_model::{
-    CreatePromoStorePayload, PromoStore, UpdatePromoStorePayload,
-};
+use crate::error::{AppError, PromoStoreError, StoreError};
+use crate::model::budget_model::BudgetCaps;
+use crate::model::promo_store_model::*;
+use crate::model::promo_tenor_model::TenorTerms;
+use crate::model::store_assignment_model::StoreScope;
+use crate::model::store_model::{Store, normalize_company_name};
+use crate::repositories::promo_repository::PromoRepository;
 use crate::repositories::promo_store_repository::PromoStoreRepository;
+use crate::repositories::promo_tenor_repository::PromoTenorRepository;
+use crate::repositories::store_repository::StoreRepository;
+use crate::services::conflict_service::ConflictService;
+use std::collections::HashSet;
 use std::sync::Arc;
+use tracing::{info, warn};
 use uuid::Uuid;
 
 pub struct PromoStoreService {
     repo: Arc<PromoStoreRepository>,
+    promo_tenor_repo: Arc<PromoTenorRepository>,
+    promo_repo: Arc<PromoRepository>,
+    store_repo: Arc<StoreRepository>,
 }
 
 impl PromoStoreService {
-    pub fn new(repo: Arc<PromoStoreRepository>) -> Self {
-        Self { repo }
+    pub fn new(
+        repo: Arc<PromoStoreRepository>,
+        promo_tenor_repo: Arc<PromoTenorRepository>,
+        promo_repo: Arc<PromoRepository>,
+        store_repo: Arc<StoreRepository>,
+    ) -> Self {
+        Self {
+            repo,
+            promo_tenor_repo,
+            promo_repo,
+            store_repo,
+        }
     }
 
     pub async fn ser_get_all_promo_stores(&self) -> Result<Vec<PromoStore>, AppError> {
@@ -41,7 +62,24 @@ impl PromoStoreService {
         &self,
         payload: CreatePromoStorePayload,
     ) -> Result<PromoStore, AppError> {
-        self.repo.rep_insert(payload).await
+        if let Some(tenor_ids) = &payload.tenor_ids {
+            self.validate_tenor_ids(payload.promo_id, tenor_ids).await?;
+        }
+        if let Some(overrides) = &payload.tenor_overrides {
+            self.validate_tenor_overrides(payload.promo_id, overrides).await?;
+            if !overrides.is_empty() {
+                self.ensure_promo_draft(payload.promo_id).await?;
+            }
+        }
+        BudgetCaps {
+            max_subsidy: payload.budget_max_subsidy,
+            max_transactions: payload.budget_max_transactions,
+        }
+        .validate()
+        .map_err(PromoStoreError::InvalidPayload)?;
+        let created = self.repo.rep_insert(payload).await?;
+        self.warm_cache().await;
+        Ok(created)
     }
 
     pub async fn ser_update_promo_store(
@@ -50,10 +88,298 @@ impl PromoStoreService {
         store_id: Uuid,
         payload: UpdatePromoStorePayload,
     ) -> Result<PromoStore, AppError> {
-        self.repo.rep_update_by_key(promo_id, store_id, payload).await
+        if let Some(Some(tenor_ids)) = &payload.tenor_ids {
+            self.validate_tenor_ids(promo_id, tenor_ids).await?;
+        }
+        if let Some(Some(overrides)) = &payload.tenor_overrides {
+            self.validate_tenor_overrides(promo_id, overrides).await?;
+        }
+        // Mengubah atau menghapus override mengubah term di store, jadi ikut aturan draft
+        if payload.tenor_overrides.is_some() {
+            self.ensure_promo_draft(promo_id).await?;
+        }
+        BudgetCaps {
+            max_subsidy: payload.budget_max_subsidy.flatten(),
+            max_transactions: payload.budget_max_transactions.flatten(),
+        }
+        .validate()
+        .map_err(PromoStoreError::InvalidPayload)?;
+        let updated = self.repo.rep_update_by_key(promo_id, store_id, payload).await?;
+        self.warm_cache().await;
+        Ok(updated)
     }
 
     pub async fn ser_delete_promo_store(&self, promo_id: Uuid, store_id: Uuid) -> Result<(), AppError> {
-        self.repo.rep_delete_by_key(promo_id, store_id).await
+        self.repo.rep_delete_by_key(promo_id, store_id).await?;
+        self.warm_cache().await;
+        Ok(())
+    }
+
+    /// Buat / hapus link promo ke banyak store sekaligus.
+    /// Kegagalan satu store tidak menghentikan store lain; semua dilaporkan per store.
+    pub async fn ser_bulk_promo_stores(
+        &self,
+        payload: BulkPromoStorePayload,
+        conflict_service: &ConflictService,
+        scope: &StoreScope,
+    ) -> Result<BulkPromoStoreResult, AppError> {
+        // Pastikan promo ada sebelum menyentuh store manapun
+        self.promo_repo.rep_get_by_id(payload.promo_id).await?;
+
+        if payload.action == BulkAction::Assign {
+            if let Some(tenor_ids) = &payload.tenor_ids {
+                self.validate_tenor_ids(payload.promo_id, tenor_ids).await?;
+            }
+            if let Some(overrides) = &payload.tenor_overrides {
+                self.validate_tenor_overrides(payload.promo_id, overrides).await?;
+                if !overrides.is_empty() {
+                    self.ensure_promo_draft(payload.promo_id).await?;
+                }
+            }
+        }
+
+        let stores = match self.store_repo.rep_fetch_all().await {
+            Ok(stores) => stores,
+            Err(AppError::Store(StoreError::NotFound(_))) => vec![],
+            Err(e) => return Err(e),
+        };
+        let linked_store_ids: HashSet<Uuid> = match self.repo.rep_fetch_all().await {
+            Ok(links) => links
+                .into_iter()
+                .filter(|ps| ps.promo_id == payload.promo_id)
+                .map(|ps| ps.store_id)
+                .collect(),
+            Err(AppError::PromoStore(PromoStoreError::NotFound(_))) => HashSet::new(),
+            Err(e) => return Err(e),
+        };
+
+        let mut targets = Self::select_stores(&payload.selector, &stores)?;
+        // Selector company/store_type hanya menjangkau store dalam cakupan pemanggil;
+        // store eksplisit di luar cakupan dilaporkan gagal di bawah
+        if !matches!(payload.selector, StoreSelector::Stores { .. }) {
+            targets.retain(|(store_id, _)| scope.allows_store(*store_id));
+        }
+        let mut results = Vec::with_capacity(targets.len());
+
+        for (store_id, store) in targets {
+            let mut result = BulkStoreResult {
+                store_id,
+                store_name: store.map(|s| s.name.clone()),
+                outcome: BulkStoreOutcome::Failed,
+                error: None,
+                conflicts: vec![],
+            };
+
+            if store.is_none() {
+                result.error = Some(format!("Store '{}' not found", store_id));
+                results.push(result);
+                continue;
+            }
+            if !scope.allows_store(store_id) {
+                result.store_name = None;
+                result.error = Some(format!("Store '{}' is outside your assigned stores", store_id));
+                results.push(result);
+                continue;
+            }
+
+            let linked = linked_store_ids.contains(&store_id);
+            match (payload.action, linked) {
+                (BulkAction::Assign, true) => result.outcome = BulkStoreOutcome::AlreadyLinked,
+                (BulkAction::Remove, false) => result.outcome = BulkStoreOutcome::NotLinked,
+                (BulkAction::Assign, false) => {
+                    let create = CreatePromoStorePayload {
+                        promo_id: payload.promo_id,
+                        store_id,
+                        tenor_ids: payload.tenor_ids.clone(),
+                        tenor_overrides: payload.tenor_overrides.clone(),
+                        budget_max_subsidy: None,
+                        budget_max_transactions: None,
+                    };
+                    match conflict_service.ser_check_promo_store_link(&create).await {
+                        Err(e) => result.error = Some(e.to_string()),
+                        Ok(conflicts) => {
+                            result.conflicts = conflicts;
+                            if payload.dry_run {
+                                result.outcome = BulkStoreOutcome::WouldCreate;
+                            } else {
+                                match self.repo.rep_insert(create).await {
+                                    Ok(_) => result.outcome = BulkStoreOutcome::Created,
+                                    Err(e) => result.error = Some(e.to_string()),
+                                }
+                            }
+                        }
+                    }
+                }
+                (BulkAction::Remove, true) => {
+                    if payload.dry_run {
+                        result.outcome = BulkStoreOutcome::WouldRemove;
+                    } else {
+                        match self.repo.rep_delete_by_key(payload.promo_id, store_id).await {
+                            Ok(_) => result.outcome = BulkStoreOutcome::Removed,
+                            Err(e) => result.error = Some(e.to_string()),
+                        }
+                    }
+                }
+            }
+            results.push(result);
+        }
+
+        if !payload.dry_run {
+            self.warm_cache().await;
+        }
+
+        let count = |outcomes: &[BulkStoreOutcome]| {
+            results.iter().filter(|r| outcomes.contains(&r.outcome)).count()
+        };
+        let summary = BulkPromoStoreResult {
+            promo_id: payload.promo_id,
+            action: payload.action,
+            dry_run: payload.dry_run,
+            matched_stores: results.len(),
+            changed: count(&[
+                BulkStoreOutcome::Created,
+                BulkStoreOutcome::Removed,
+                BulkStoreOutcome::WouldCreate,
+                BulkStoreOutcome::WouldRemove,
+            ]),
+            unchanged: count(&[BulkStoreOutcome::AlreadyLinked, BulkStoreOutcome::NotLinked]),
+            failed: count(&[BulkStoreOutcome::Failed]),
+            results,
+        };
+
+        info!(
+            "Bulk {:?} promo {} (dry_run={}): {} matched, {} changed, {} failed.",
+            summary.action,
+            summary.promo_id,
+            summary.dry_run,
+            summary.matched_stores,
+            summary.changed,
+            summary.failed
+        );
+        Ok(summary)
+    }
+
+    /// Resolve selector ke daftar store; store eksplisit yang tidak ada tetap ikut (sebagai `None`)
+    fn select_stores<'a>(
+        selector: &StoreSelector,
+        stores: &'a [Store],
+    ) -> Result<Vec<(Uuid, Option<&'a Store>)>, AppError> {
+        let selected = match selector {
+            StoreSelector::Company { company } => {
+                let wanted = normalize_company_name(company);
+                if wanted.is_empty() {
+                    return Err(PromoStoreError::InvalidPayload("company must not be empty".to_string()).into());
+                }
+                stores
+                    .iter()
+                    .filter(|s| normalize_company_name(&s.company) == wanted)
+                    .map(|s| (s.id, Some(s)))
+                    .collect()
+            }
+            StoreSelector::CompanyId { company_id } => stores
+                .iter()
+                .filter(|s| s.company_id == Some(*company_id))
+                .map(|s| (s.id, Some(s)))
+                .collect(),
+            StoreSelector::StoreType { store_type } => stores
+                .iter()
+                .filter(|s| s.store_type == Some(*store_type))
+                .map(|s| (s.id, Some(s)))
+                .collect(),
+            StoreSelector::Tag { tag } => {
+                let wanted = tag.trim().to_lowercase();
+                if wanted.is_empty() {
+                    return Err(PromoStoreError::InvalidPayload("tag must not be empty".to_string()).into());
+                }
+                stores
+                    .iter()
+                    .filter(|s| s.tags.iter().any(|t| t.trim().to_lowercase() == wanted))
+                    .map(|s| (s.id, Some(s)))
+                    .collect()
+            }
+            StoreSelector::Stores { store_ids } => {
+                if store_ids.is_empty() {
+                    return Err(PromoStoreError::InvalidPayload("store_ids must not be empty".to_string()).into());
+                }
+                let mut seen = HashSet::new();
+                store_ids
+                    .iter()
+                    .filter(|id| seen.insert(**id))
+                    .map(|id| (*id, stores.iter().find(|s| s.id == *id)))
+                    .collect()
+            }
+        };
+        Ok(selected)
+    }
+
+    /// Pastikan semua `tenor_ids` adalah tenor milik promo yang di-link
+    async fn validate_tenor_ids(&self, promo_id: Uuid, tenor_ids: &[Uuid]) -> Result<(), AppError> {
+        if tenor_ids.is_empty() {
+            return Ok(());
+        }
+
+        let promo_tenors = self.promo_tenor_repo.rep_fetch_all().await?;
+        let invalid: Vec<String> = tenor_ids
+            .iter()
+            .filter(|id| !promo_tenors.iter().any(|t| t.id == **id && t.promo_id == promo_id))
+            .map(|id| id.to_string())
+            .collect();
+
+        if !invalid.is_empty() {
+            return Err(PromoStoreError::InvalidPayload(format!(
+                "tenor_ids do not belong to promo '{}': {}",
+                promo_id,
+                invalid.join(", ")
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Override harus untuk tenor milik promo, satu entri per tenor, dan valid untuk tipe promo
+    async fn validate_tenor_overrides(
+        &self,
+        promo_id: Uuid,
+        overrides: &[TenorOverride],
+    ) -> Result<(), AppError> {
+        let promo = self.promo_repo.rep_get_by_id(promo_id).await?;
+        let mut seen = HashSet::new();
+        for o in overrides {
+            if !seen.insert(o.tenor_id) {
+                return Err(PromoStoreError::InvalidPayload(format!(
+                    "duplicate override for tenor '{}'",
+                    o.tenor_id
+                ))
+                .into());
+            }
+
+            TenorTerms::from(o)
+                .validate(&promo.admin_promo_type, &promo.discount_type)
+                .map_err(|e| {
+                    PromoStoreError::InvalidPayload(format!("override for tenor '{}': {}", o.tenor_id, e))
+                })?;
+        }
+
+        let tenor_ids: Vec<Uuid> = overrides.iter().map(|o| o.tenor_id).collect();
+        self.validate_tenor_ids(promo_id, &tenor_ids).await
+    }
+
+    /// Override term store hanya boleh diubah saat promo masih draft (lihat `Promo::ensure_draft`)
+    async fn ensure_promo_draft(&self, promo_id: Uuid) -> Result<(), AppError> {
+        let promo = self.promo_repo.rep_get_by_id(promo_id).await?;
+        promo
+            .ensure_draft()
+            .map_err(|e| PromoStoreError::Conflict(e).into())
+    }
+
+    /// Mutasi promo_store mengosongkan cache promo_store & promo_tenor;
+    /// isi ulang supaya lookup tenor per store langsung melihat link terbaru.
+    async fn warm_cache(&self) {
+        if let Err(e) = self.repo.rep_fetch_all().await {
+            warn!("Gagal me-warm cache promo_store: {}", e);
+        }
+        if let Err(e) = self.promo_tenor_repo.rep_fetch_all().await {
+            warn!("Gagal me-warm cache promo_tenor: {}", e);
+        }
     }
 }
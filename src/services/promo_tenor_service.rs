@@ -1,36 +1,64 @@
-use crate::error::AppError;
+use crate::business_time::parse_business_datetime;
+use crate::disclosure;
+use crate::error::{AppError, PromoError, PromoTenorError};
+use crate::middleware::Actor;
+use crate::model::promo_model::Promo;
 use crate::model::promo_tenor_model::*;
+use crate::model::tenor_change_model::*;
+use crate::repositories::promo_repository::PromoRepository;
+use crate::repositories::promo_tenor_change_repository::PromoTenorChangeRepository;
 use crate::repositories::promo_tenor_repository::PromoTenorRepository;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
 use std::sync::Arc;
+use tracing::{info, warn};
 use uuid::Uuid;
 
+/// Klaim `applying` lebih lama dari ini dikembalikan ke `pending`
+const STALE_CLAIM_MINUTES: i64 = 10;
+
 pub struct PromoTenorService {
     repo: Arc<PromoTenorRepository>,
+    promo_repo: Arc<PromoRepository>,
+    change_repo: Arc<PromoTenorChangeRepository>,
 }
 
 impl PromoTenorService {
-    pub fn new(repo: Arc<PromoTenorRepository>) -> Self {
-        Self { repo }
+    pub fn new(
+        repo: Arc<PromoTenorRepository>,
+        promo_repo: Arc<PromoRepository>,
+        change_repo: Arc<PromoTenorChangeRepository>,
+    ) -> Self {
+        Self {
+            repo,
+            promo_repo,
+            change_repo,
+        }
     }
 
     pub async fn ser_get_all_promo_tenors(&self) -> Result<Vec<PromoTenor>, AppError> {
-        self.repo.rep_fetch_all().await
+        let tenors = self.repo.rep_fetch_all().await?;
+        self.with_disclosures(tenors).await
     }
 
     pub async fn ser_get_promo_tenor_by_id(&self, id: Uuid) -> Result<PromoTenor, AppError> {
-        self.repo.rep_fetch_by_id(id).await
+        let tenor = self.repo.rep_fetch_by_id(id).await?;
+        self.with_disclosure(tenor).await
     }
 
     pub async fn ser_get_promo_tenors_by_promo_id(&self, promo_id: Uuid) -> Result<Vec<PromoTenor>, AppError> {
-        self.repo.rep_fetch_by_promo_id(promo_id).await
+        let tenors = self.repo.rep_fetch_by_promo_id(promo_id).await?;
+        self.with_disclosures(tenors).await
     }
 
     pub async fn ser_get_promo_tenors_by_tenor(&self, tenor: i32) -> Result<Vec<PromoTenor>, AppError> {
-        self.repo.rep_fetch_by_tenor(tenor).await
+        let tenors = self.repo.rep_fetch_by_tenor(tenor).await?;
+        self.with_disclosures(tenors).await
     }
 
     pub async fn ser_get_promo_tenors_by_voucher(&self, voucher: &str) -> Result<Vec<PromoTenor>, AppError> {
-        self.repo.rep_fetch_by_voucher(voucher).await
+        let tenors = self.repo.rep_fetch_by_voucher(voucher).await?;
+        self.with_disclosures(tenors).await
     }
 
     pub async fn ser_create_promo_tenor(&self, payload: CreatePromoTenorPayload) -> Result<PromoTenor, AppError> {
@@ -40,8 +68,12 @@ impl PromoTenorService {
                 format!("Tenor must be between 1-60 months, got: {}", payload.tenor)
             )));
         }
-        
-        self.repo.rep_insert(payload).await
+        Self::validate_voucher_expiry(payload.voucher_expires_at.as_deref())?;
+        self.ensure_promo_draft(payload.promo_id).await?;
+        self.validate_terms(payload.promo_id, TenorTerms::from(&payload)).await?;
+
+        let created = self.repo.rep_insert(payload).await?;
+        self.with_disclosure(created).await
     }
 
     pub async fn ser_update_promo_tenor(
@@ -49,14 +81,356 @@ impl PromoTenorService {
         id: Uuid,
         payload: UpdatePromoTenorPayload,
     ) -> Result<PromoTenor, AppError> {
-        self.repo.rep_update_by_id(id, payload).await
+        let current = self.repo.rep_fetch_by_id(id).await?;
+        self.ensure_promo_draft(current.promo_id).await?;
+        if let Some(promo_id) = payload.promo_id.filter(|p| *p != current.promo_id) {
+            self.ensure_promo_draft(promo_id).await?;
+        }
+        self.validate_update(id, &payload).await?;
+        let updated = self.repo.rep_update_by_id(id, payload).await?;
+        self.with_disclosure(updated).await
+    }
+
+    /// Term hasil update (termasuk pindah promo) harus valid untuk tipe promo tujuan
+    async fn validate_update(&self, id: Uuid, payload: &UpdatePromoTenorPayload) -> Result<(), AppError> {
+        let current = self.repo.rep_fetch_by_id(id).await?;
+        if let Some(tenor) = payload.tenor
+            && (tenor <= 0 || tenor > 60)
+        {
+            return Err(PromoTenorError::InvalidTenor(format!(
+                "Tenor must be between 1-60 months, got: {}",
+                tenor
+            ))
+            .into());
+        }
+        Self::validate_voucher_expiry(payload.voucher_expires_at.as_ref().and_then(|v| v.as_deref()))?;
+        let promo_id = payload.promo_id.unwrap_or(current.promo_id);
+        let terms = TenorTerms::from(payload).or(TenorTerms::from(&current));
+        self.validate_terms(promo_id, terms).await
+    }
+
+    /// Simpan perubahan tenor sebagai `pending`; diterapkan job scheduler saat `effective_at` tercapai.
+    /// Perubahan pada promo yang bukan draft masuk `pending_approval` dan menunggu approval admin.
+    pub async fn ser_schedule_promo_tenor_change(
+        &self,
+        payload: SchedulePromoTenorChangePayload,
+        actor: &Actor,
+    ) -> Result<PromoTenorChange, AppError> {
+        let effective_at = parse_business_datetime(&payload.effective_at, false).ok_or_else(|| {
+            PromoTenorError::InvalidPayload(format!("invalid effective_at '{}'", payload.effective_at))
+        })?;
+        if effective_at <= Utc::now() {
+            return Err(PromoTenorError::InvalidPayload(
+                "effective_at must be in the future; use /update-promo-tenor for immediate changes".to_string(),
+            )
+            .into());
+        }
+        let is_empty = serde_json::to_value(&payload.changes)
+            .ok()
+            .and_then(|v| v.as_object().map(|o| o.is_empty()))
+            .unwrap_or(true);
+        if is_empty {
+            return Err(PromoTenorError::InvalidPayload("changes must not be empty".to_string()).into());
+        }
+        Self::ensure_same_promo(&payload.changes)?;
+        self.validate_update(payload.promo_tenor_id, &payload.changes).await?;
+        let status = if self.needs_approval(payload.promo_tenor_id).await? {
+            TenorChangeStatus::PendingApproval
+        } else {
+            TenorChangeStatus::Pending
+        };
+
+        let change = self
+            .change_repo
+            .rep_insert(CreatePromoTenorChangePayload {
+                promo_tenor_id: payload.promo_tenor_id,
+                changes: payload.changes,
+                effective_at: effective_at.to_rfc3339(),
+                status,
+                created_by: Some(actor.user_id.clone()),
+            })
+            .await?;
+        info!(
+            "Perubahan tenor {} dijadwalkan untuk {} oleh {} ({})",
+            change.promo_tenor_id,
+            change.effective_at,
+            actor.user_id,
+            change.status.as_str()
+        );
+        Ok(change)
     }
 
-    pub async fn ser_delete_promo_tenor(&self, id: Uuid) -> Result<(), AppError> {
-        self.repo.rep_delete_by_id(id).await
+    /// Admin menyetujui perubahan `pending_approval` supaya boleh diterapkan scheduler
+    pub async fn ser_approve_promo_tenor_change(&self, id: Uuid, actor: &Actor) -> Result<PromoTenorChange, AppError> {
+        let mut approve = Self::change_status(TenorChangeStatus::Pending, None);
+        approve.approved_by = Some(actor.user_id.clone());
+        approve.approved_at = Some(Utc::now().to_rfc3339());
+        match self
+            .change_repo
+            .rep_update_if_status(id, &[TenorChangeStatus::PendingApproval], approve)
+            .await?
+        {
+            Some(approved) => {
+                info!("Perubahan terjadwal {} disetujui oleh {}", id, actor.user_id);
+                Ok(approved)
+            }
+            None => Err(self.not_in_status(id).await),
+        }
     }
 
-    pub async fn ser_get_promo_tenors_by_store_id(&self, store_id: Uuid) -> Result<Vec<PromoTenor>, AppError> {
-        self.repo.rep_fetch_by_store_id(store_id).await
+    pub async fn ser_get_promo_tenor_changes(
+        &self,
+        status: Option<TenorChangeStatus>,
+        promo_tenor_id: Option<Uuid>,
+    ) -> Result<Vec<PromoTenorChange>, AppError> {
+        self.change_repo.rep_fetch_all(status, promo_tenor_id).await
+    }
+
+    pub async fn ser_get_promo_tenor_change_by_id(&self, id: Uuid) -> Result<PromoTenorChange, AppError> {
+        self.change_repo.rep_fetch_by_id(id).await
+    }
+
+    pub async fn ser_cancel_promo_tenor_change(&self, id: Uuid, actor: &Actor) -> Result<PromoTenorChange, AppError> {
+        let mut cancel = Self::change_status(TenorChangeStatus::Cancelled, None);
+        cancel.cancelled_by = Some(actor.user_id.clone());
+        cancel.cancelled_at = Some(Utc::now().to_rfc3339());
+        let open = [TenorChangeStatus::PendingApproval, TenorChangeStatus::Pending];
+        match self.change_repo.rep_update_if_status(id, &open, cancel).await? {
+            Some(cancelled) => Ok(cancelled),
+            None => Err(self.not_in_status(id).await),
+        }
+    }
+
+    async fn not_in_status(&self, id: Uuid) -> AppError {
+        match self.change_repo.rep_fetch_by_id(id).await {
+            Ok(existing) => {
+                PromoTenorError::Conflict(format!("scheduled change is already {}", existing.status.as_str())).into()
+            }
+            Err(e) => e,
+        }
+    }
+
+    /// Terapkan semua perubahan `pending` yang sudah jatuh tempo, lalu warm ulang cache tenor.
+    /// Mengembalikan `effective_at` perubahan pending berikutnya.
+    pub async fn ser_apply_due_changes(&self) -> Result<Option<DateTime<Utc>>, AppError> {
+        self.requeue_stale_claims().await?;
+        let pending = self
+            .change_repo
+            .rep_fetch_all(Some(TenorChangeStatus::Pending), None)
+            .await?;
+        let now = Utc::now();
+        let mut applied = 0;
+
+        for change in pending.iter().filter(|c| c.is_due(now)) {
+            // Promo yang dijadwalkan saat draft bisa sudah published; perubahan tanpa approval
+            // dikembalikan ke antrian approval, bukan diterapkan
+            if change.approved_by.is_none() && self.needs_approval(change.promo_tenor_id).await? {
+                let back = Self::change_status(TenorChangeStatus::PendingApproval, None);
+                if self
+                    .change_repo
+                    .rep_update_if_status(change.id, &[TenorChangeStatus::Pending], back)
+                    .await?
+                    .is_some()
+                {
+                    warn!("Perubahan terjadwal {} butuh approval admin, tidak diterapkan.", change.id);
+                }
+                continue;
+            }
+
+            // Klaim dulu (pending -> applying) supaya instance lain / cancel tidak menyentuh perubahan yang sama
+            let mut claim = Self::change_status(TenorChangeStatus::Applying, None);
+            claim.claimed_at = Some(Utc::now().to_rfc3339());
+            if self
+                .change_repo
+                .rep_update_if_status(change.id, &[TenorChangeStatus::Pending], claim)
+                .await?
+                .is_none()
+            {
+                continue;
+            }
+
+            // Status `applied` baru ditulis setelah tenor benar-benar ter-update
+            match self.apply_change(change).await {
+                Ok(()) => {
+                    applied += 1;
+                    info!("Perubahan terjadwal {} diterapkan ke tenor {}", change.id, change.promo_tenor_id);
+                }
+                Err(e) => {
+                    warn!("Perubahan terjadwal {} gagal diterapkan: {}", change.id, e);
+                    let failed = Self::change_status(TenorChangeStatus::Failed, Some(e.to_string()));
+                    if let Err(e) = self.change_repo.rep_update_by_id(change.id, failed).await {
+                        warn!("Gagal menandai perubahan {} sebagai failed: {}", change.id, e);
+                    }
+                }
+            }
+        }
+
+        if applied > 0 {
+            // rep_update_by_id sudah clear cache tenor; warm ulang supaya listing langsung memakai term baru
+            if let Err(e) = self.repo.rep_fetch_all().await {
+                warn!("Gagal me-warm cache promo_tenor setelah perubahan terjadwal: {}", e);
+            }
+        }
+
+        Ok(pending
+            .iter()
+            .filter(|c| !c.is_due(now))
+            .filter_map(|c| c.effective_at())
+            .min())
+    }
+
+    /// Instance yang mati di tengah apply meninggalkan baris `applying`; setelah timeout dikembalikan
+    /// ke `pending` supaya diterapkan ulang (nilai `changes` absolut, jadi aman diterapkan dua kali)
+    async fn requeue_stale_claims(&self) -> Result<(), AppError> {
+        let cutoff = Utc::now() - Duration::minutes(STALE_CLAIM_MINUTES);
+        let applying = self
+            .change_repo
+            .rep_fetch_all(Some(TenorChangeStatus::Applying), None)
+            .await?;
+        for change in applying.iter().filter(|c| c.is_stale_claim(cutoff)) {
+            let requeue = Self::change_status(TenorChangeStatus::Pending, None);
+            if self
+                .change_repo
+                .rep_update_if_status(change.id, &[TenorChangeStatus::Applying], requeue)
+                .await?
+                .is_some()
+            {
+                warn!(
+                    "Perubahan terjadwal {} tertahan di applying sejak {:?}, dikembalikan ke pending.",
+                    change.id, change.claimed_at
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Terapkan perubahan ke tenor lalu tandai `applied`; jika penandaan gagal,
+    /// tenor dikembalikan ke nilai sebelumnya supaya status dan data tidak berbeda.
+    async fn apply_change(&self, change: &PromoTenorChange) -> Result<(), AppError> {
+        Self::ensure_same_promo(&change.changes)?;
+        self.validate_update(change.promo_tenor_id, &change.changes).await?;
+        let before = self.repo.rep_fetch_by_id(change.promo_tenor_id).await?;
+        self.repo.rep_update_by_id(change.promo_tenor_id, change.changes.clone()).await?;
+
+        let mut done = Self::change_status(TenorChangeStatus::Applied, None);
+        done.applied_at = Some(Utc::now().to_rfc3339());
+        if let Err(e) = self.change_repo.rep_update_by_id(change.id, done).await {
+            if let Err(rollback) = self
+                .repo
+                .rep_update_by_id(change.promo_tenor_id, before.revert_of(&change.changes))
+                .await
+            {
+                warn!("Rollback tenor {} gagal: {}", change.promo_tenor_id, rollback);
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn change_status(status: TenorChangeStatus, error: Option<String>) -> UpdatePromoTenorChangePayload {
+        UpdatePromoTenorChangePayload {
+            status,
+            approved_by: None,
+            approved_at: None,
+            claimed_at: None,
+            applied_at: None,
+            cancelled_by: None,
+            cancelled_at: None,
+            error,
+        }
+    }
+
+    /// Perubahan terjadwal tidak boleh memindahkan tenor ke promo lain
+    fn ensure_same_promo(changes: &UpdatePromoTenorPayload) -> Result<(), AppError> {
+        if changes.promo_id.is_some() {
+            return Err(PromoTenorError::InvalidPayload(
+                "scheduled changes cannot move a tenor to another promo".to_string(),
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Perubahan pada tenor milik promo yang bukan draft harus di-approve admin
+    async fn needs_approval(&self, promo_tenor_id: Uuid) -> Result<bool, AppError> {
+        let tenor = self.repo.rep_fetch_by_id(promo_tenor_id).await?;
+        let promo = self.promo_repo.rep_get_by_id(tenor.promo_id).await?;
+        Ok(promo.ensure_draft().is_err())
+    }
+
+    /// Expiry yang tidak bisa di-parse dibaca sebagai sudah lewat, jadi ditolak saat ditulis
+    fn validate_voucher_expiry(raw: Option<&str>) -> Result<(), AppError> {
+        match raw {
+            Some(raw) if parse_business_datetime(raw, true).is_none() => Err(PromoTenorError::InvalidPayload(
+                format!("invalid voucher_expires_at '{}'", raw),
+            )
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Edit langsung hanya untuk promo draft; promo published diubah lewat perubahan terjadwal
+    /// atau dikembalikan ke draft supaya melewati review lagi
+    async fn ensure_promo_draft(&self, promo_id: Uuid) -> Result<(), AppError> {
+        let promo = self.promo_repo.rep_get_by_id(promo_id).await?;
+        promo
+            .ensure_draft()
+            .map_err(|e| PromoTenorError::Conflict(e).into())
+    }
+
+    /// admin/discount divalidasi sesuai tipe promo (PERCENT 0–100, FIX tidak negatif)
+    async fn validate_terms(&self, promo_id: Uuid, terms: TenorTerms) -> Result<(), AppError> {
+        let promo = self.promo_repo.rep_get_by_id(promo_id).await?;
+        terms
+            .validate(&promo.admin_promo_type, &promo.discount_type)
+            .map_err(|e| PromoTenorError::InvalidPayload(e).into())
+    }
+
+    /// Katalog store: disclosure dihitung dari term efektif (termasuk override store)
+    pub async fn ser_get_promo_tenors_by_store_id(&self, store_id: Uuid) -> Result<Vec<StorePromoTenor>, AppError> {
+        let mut tenors = self.repo.rep_fetch_live_by_store_id(store_id).await?;
+        let promos = self.promos_by_id().await?;
+        for st in tenors.iter_mut() {
+            if let Some(promo) = promos.get(&st.promo_tenor.promo_id) {
+                st.promo_tenor.disclosure = disclosure::disclose_tenor(promo, &st.promo_tenor);
+            }
+        }
+        Ok(tenors)
+    }
+
+    /// Listing publik: buang tenor milik promo yang belum published
+    pub async fn ser_retain_published(&self, mut tenors: Vec<PromoTenor>) -> Result<Vec<PromoTenor>, AppError> {
+        if tenors.is_empty() {
+            return Ok(tenors);
+        }
+        let promos = self.promos_by_id().await?;
+        tenors.retain(|t| promos.get(&t.promo_id).is_some_and(|p| p.is_published()));
+        Ok(tenors)
+    }
+
+    async fn with_disclosure(&self, tenor: PromoTenor) -> Result<PromoTenor, AppError> {
+        let mut tenors = self.with_disclosures(vec![tenor]).await?;
+        Ok(tenors.remove(0))
+    }
+
+    async fn with_disclosures(&self, mut tenors: Vec<PromoTenor>) -> Result<Vec<PromoTenor>, AppError> {
+        if tenors.is_empty() {
+            return Ok(tenors);
+        }
+        let promos = self.promos_by_id().await?;
+        for tenor in tenors.iter_mut() {
+            if let Some(promo) = promos.get(&tenor.promo_id) {
+                tenor.disclosure = disclosure::disclose_tenor(promo, tenor);
+            }
+        }
+        Ok(tenors)
+    }
+
+    async fn promos_by_id(&self) -> Result<HashMap<Uuid, Promo>, AppError> {
+        match self.promo_repo.rep_fetch_all().await {
+            Ok(promos) => Ok(promos.into_iter().map(|p| (p.id_promo, p)).collect()),
+            Err(AppError::Promo(PromoError::NotFound(_))) => Ok(HashMap::new()),
+            Err(e) => Err(e),
+        }
     }
 }
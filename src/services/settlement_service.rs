@@ -0,0 +1,165 @@
+use crate::business_time::business_day_start;
+use crate::error::{AppError, CompanyError, PromoError, StoreError};
+use crate::model::company_model::Company;
+use crate::model::settlement_model::*;
+use crate::model::store_assignment_model::StoreScope;
+use crate::model::store_model::Store;
+use crate::repositories::company_repository::CompanyRepository;
+use crate::repositories::financed_transaction_repository::FinancedTransactionRepository;
+use crate::repositories::promo_repository::PromoRepository;
+use crate::repositories::store_repository::StoreRepository;
+use chrono::{Days, NaiveDate};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// (nama company, id company) dan (nama store, id store) sebagai kunci urutan laporan
+type CompanyKey = (String, Option<Uuid>);
+type StoreKey = (String, Uuid);
+
+pub struct SettlementService {
+    repo: Arc<FinancedTransactionRepository>,
+    store_repo: Arc<StoreRepository>,
+    company_repo: Arc<CompanyRepository>,
+    promo_repo: Arc<PromoRepository>,
+}
+
+impl SettlementService {
+    pub fn new(
+        repo: Arc<FinancedTransactionRepository>,
+        store_repo: Arc<StoreRepository>,
+        company_repo: Arc<CompanyRepository>,
+        promo_repo: Arc<PromoRepository>,
+    ) -> Self {
+        Self {
+            repo,
+            store_repo,
+            company_repo,
+            promo_repo,
+        }
+    }
+
+    /// Laporan settlement merchant untuk periode `from`..=`to` (tanggal bisnis),
+    /// dikelompokkan per company lalu per store dari ledger `financed_transaction`.
+    pub async fn ser_get_settlement_report(
+        &self,
+        query: SettlementQuery,
+        scope: &StoreScope,
+    ) -> Result<SettlementReport, AppError> {
+        let from = Self::parse_date("from", &query.from)?;
+        let to = Self::parse_date("to", &query.to)?;
+        if from > to {
+            return Err(AppError::BadRequest(format!(
+                "from ({}) must not be after to ({})",
+                from, to
+            )));
+        }
+        let start = business_day_start(from)
+            .ok_or_else(|| AppError::BadRequest(format!("Invalid from date: {}", from)))?;
+        let end = to
+            .checked_add_days(Days::new(1))
+            .and_then(business_day_start)
+            .ok_or_else(|| AppError::BadRequest(format!("Invalid to date: {}", to)))?;
+
+        if let Some(company_id) = query.company_id {
+            // 404 jika company tidak terdaftar
+            self.company_repo.rep_fetch_by_id(company_id).await?;
+        }
+
+        let stores: HashMap<Uuid, Store> = self.all_stores().await?.into_iter().map(|s| (s.id, s)).collect();
+        let companies = match self.company_repo.rep_fetch_all().await {
+            Ok(companies) => companies,
+            Err(AppError::Company(CompanyError::NotFound(_))) => vec![],
+            Err(e) => return Err(e),
+        };
+        let promo_titles: HashMap<Uuid, String> = match self.promo_repo.rep_fetch_all().await {
+            Ok(promos) => promos.into_iter().map(|p| (p.id_promo, p.title_promo)).collect(),
+            Err(AppError::Promo(PromoError::NotFound(_))) => HashMap::new(),
+            Err(e) => return Err(e),
+        };
+
+        let transactions = self.repo.rep_fetch_by_period(start, end, query.store_id).await?;
+
+        // Urut berdasarkan nama company lalu nama store supaya laporan stabil
+        let mut grouped: BTreeMap<CompanyKey, BTreeMap<StoreKey, StoreSettlement>> = BTreeMap::new();
+        for transaction in transactions.iter().filter(|t| scope.allows_store(t.store_id)) {
+            let store = stores.get(&transaction.store_id);
+            let (company_id, company_name) = Self::company_of(store, &companies);
+            if query.company_id.is_some() && company_id != query.company_id {
+                continue;
+            }
+
+            let line = SettlementLine::from_transaction(
+                transaction,
+                promo_titles.get(&transaction.promo_id).cloned(),
+            );
+            let store_name = store.map(|s| s.name.clone());
+            let store_settlement = grouped
+                .entry((company_name, company_id))
+                .or_default()
+                .entry((store_name.clone().unwrap_or_default(), transaction.store_id))
+                .or_insert_with(|| StoreSettlement {
+                    store_id: transaction.store_id,
+                    store_name,
+                    store_route: store.and_then(|s| s.route.clone()),
+                    totals: SettlementTotals::default(),
+                    lines: Vec::new(),
+                });
+            store_settlement.totals.add_line(&line);
+            store_settlement.lines.push(line);
+        }
+
+        let mut totals = SettlementTotals::default();
+        let companies = grouped
+            .into_iter()
+            .map(|((company_name, company_id), stores)| {
+                let stores: Vec<StoreSettlement> = stores.into_values().collect();
+                let mut company_totals = SettlementTotals::default();
+                for store in &stores {
+                    company_totals.add_totals(&store.totals);
+                }
+                totals.add_totals(&company_totals);
+                CompanySettlement {
+                    company_id,
+                    company_name,
+                    totals: company_totals,
+                    stores,
+                }
+            })
+            .collect();
+
+        Ok(SettlementReport {
+            from: from.to_string(),
+            to: to.to_string(),
+            company_id: query.company_id,
+            store_id: query.store_id,
+            totals,
+            companies,
+        })
+    }
+
+    fn parse_date(field: &str, raw: &str) -> Result<NaiveDate, AppError> {
+        NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d").map_err(|_| {
+            AppError::BadRequest(format!("{} must be a date in YYYY-MM-DD format, got: {}", field, raw))
+        })
+    }
+
+    /// Company terdaftar pemilik store, atau teks `company` lama jika belum dimigrasi
+    fn company_of(store: Option<&Store>, companies: &[Company]) -> (Option<Uuid>, String) {
+        let Some(store) = store else {
+            return (None, "(unknown store)".to_string());
+        };
+        match companies.iter().find(|c| c.owns_store(store)) {
+            Some(company) => (Some(company.id), company.name.clone()),
+            None => (store.company_id, store.company.clone()),
+        }
+    }
+
+    async fn all_stores(&self) -> Result<Vec<Store>, AppError> {
+        match self.store_repo.rep_fetch_all().await {
+            Ok(stores) => Ok(stores),
+            Err(AppError::Store(StoreError::NotFound(_))) => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+}
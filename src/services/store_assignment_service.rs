@@ -0,0 +1,169 @@
+use crate::constants::ROLE_ADMIN;
+use crate::error::{AppError, AssignmentError, CompanyError, StoreError};
+use crate::middleware::Actor;
+use crate::model::store_assignment_model::*;
+use crate::model::store_model::Store;
+use crate::repositories::company_repository::CompanyRepository;
+use crate::repositories::store_assignment_repository::StoreAssignmentRepository;
+use crate::repositories::store_repository::StoreRepository;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+pub struct StoreAssignmentService {
+    repo: Arc<StoreAssignmentRepository>,
+    store_repo: Arc<StoreRepository>,
+    company_repo: Arc<CompanyRepository>,
+}
+
+impl StoreAssignmentService {
+    pub fn new(
+        repo: Arc<StoreAssignmentRepository>,
+        store_repo: Arc<StoreRepository>,
+        company_repo: Arc<CompanyRepository>,
+    ) -> Self {
+        Self {
+            repo,
+            store_repo,
+            company_repo,
+        }
+    }
+
+    pub async fn ser_get_assignments(&self, query: StoreAssignmentQuery) -> Result<Vec<StoreAssignment>, AppError> {
+        self.repo.rep_fetch_all(query.user_id.as_deref()).await
+    }
+
+    pub async fn ser_create_assignment(
+        &self,
+        payload: CreateStoreAssignmentPayload,
+        actor: &Actor,
+    ) -> Result<StoreAssignment, AppError> {
+        let user_id = payload.user_id.trim().to_string();
+        if user_id.is_empty() {
+            return Err(AssignmentError::InvalidPayload("user_id must not be empty".to_string()).into());
+        }
+        match (payload.store_id, payload.company_id) {
+            (Some(store_id), None) => {
+                if !self.all_stores().await?.iter().any(|s| s.id == store_id) {
+                    return Err(StoreError::NotFound(format!("Store with id '{}' not found", store_id)).into());
+                }
+            }
+            (None, Some(company_id)) => {
+                self.company_repo.rep_fetch_by_id(company_id).await?;
+            }
+            _ => {
+                return Err(AssignmentError::InvalidPayload(
+                    "exactly one of store_id or company_id is required".to_string(),
+                )
+                .into());
+            }
+        }
+
+        let existing = self.repo.rep_fetch_all(Some(&user_id)).await?;
+        if let Some(duplicate) = existing
+            .iter()
+            .find(|a| a.store_id == payload.store_id && a.company_id == payload.company_id)
+        {
+            return Err(AssignmentError::AlreadyExists(format!(
+                "User '{}' already has this assignment (id '{}')",
+                user_id, duplicate.id
+            ))
+            .into());
+        }
+
+        let created = self
+            .repo
+            .rep_insert(StoreAssignmentRow {
+                user_id,
+                store_id: payload.store_id,
+                company_id: payload.company_id,
+                created_by: actor.user_id.clone(),
+            })
+            .await?;
+        info!(
+            "Store assignment {} untuk user {} dibuat oleh {}.",
+            created.id, created.user_id, actor.user_id
+        );
+        Ok(created)
+    }
+
+    pub async fn ser_delete_assignment(&self, id: Uuid, actor: &Actor) -> Result<(), AppError> {
+        self.repo.rep_delete_by_id(id).await?;
+        info!("Store assignment {} dihapus oleh {}.", id, actor.user_id);
+        Ok(())
+    }
+
+    /// Cakupan store pemanggil; admin tidak dibatasi. Store company dicocokkan lewat
+    /// `Company::owns_store`, jadi store lama yang belum dimigrasi ikut terhitung.
+    pub async fn ser_scope(&self, actor: &Actor) -> Result<StoreScope, AppError> {
+        if actor.has_any_role(&[ROLE_ADMIN]) {
+            return Ok(StoreScope::All);
+        }
+
+        let assignments = self.repo.rep_fetch_all(Some(&actor.user_id)).await?;
+        let mut store_ids: HashSet<Uuid> = assignments.iter().filter_map(|a| a.store_id).collect();
+        let company_ids: HashSet<Uuid> = assignments.iter().filter_map(|a| a.company_id).collect();
+
+        if !company_ids.is_empty() {
+            let mut companies = Vec::with_capacity(company_ids.len());
+            for company_id in &company_ids {
+                match self.company_repo.rep_fetch_by_id(*company_id).await {
+                    Ok(company) => companies.push(company),
+                    // Company sudah dihapus: assignment-nya tidak memberi akses apa pun
+                    Err(AppError::Company(CompanyError::NotFound(_))) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            store_ids.extend(
+                self.all_stores()
+                    .await?
+                    .iter()
+                    .filter(|s| companies.iter().any(|c| c.owns_store(s)))
+                    .map(|s| s.id),
+            );
+        }
+
+        Ok(StoreScope::Assigned { store_ids, company_ids })
+    }
+
+    /// Store di luar cakupan = 403
+    pub fn ensure_store(scope: &StoreScope, store_id: Uuid) -> Result<(), AppError> {
+        if scope.allows_store(store_id) {
+            return Ok(());
+        }
+        Err(AppError::Forbidden(format!(
+            "Store '{}' is outside your assigned stores",
+            store_id
+        )))
+    }
+
+    pub fn ensure_company(scope: &StoreScope, company_id: Uuid) -> Result<(), AppError> {
+        if scope.allows_company(company_id) {
+            return Ok(());
+        }
+        Err(AppError::Forbidden(format!(
+            "Company '{}' is outside your assigned companies",
+            company_id
+        )))
+    }
+
+    /// Store yang boleh dikelola pemanggil
+    pub async fn ser_get_my_stores(&self, actor: &Actor) -> Result<Vec<Store>, AppError> {
+        let scope = self.ser_scope(actor).await?;
+        Ok(self
+            .all_stores()
+            .await?
+            .into_iter()
+            .filter(|s| scope.allows_store(s.id))
+            .collect())
+    }
+
+    async fn all_stores(&self) -> Result<Vec<Store>, AppError> {
+        match self.store_repo.rep_fetch_all().await {
+            Ok(stores) => Ok(stores),
+            Err(AppError::Store(StoreError::NotFound(_))) => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -1,15 +1,19 @@
-use crate::error::AppError;
-use crate::model::store_model::{CreateStorePayload, Store, UpdateStorePayload};
+use crate::error::{AppError, CompanyError, StoreError};
+use crate::model::store_model::{
+    CreateStorePayload, Store, UpdateStorePayload, normalize_company_name, normalize_store_tags,
+};
+use crate::repositories::company_repository::CompanyRepository;
 use crate::repositories::store_repository::StoreRepository;
 use std::sync::Arc;
 
 pub struct StoreService {
     repo: Arc<StoreRepository>,
+    company_repo: Arc<CompanyRepository>,
 }
 
 impl StoreService {
-    pub fn new(repo: Arc<StoreRepository>) -> Self {
-        Self { repo }
+    pub fn new(repo: Arc<StoreRepository>, company_repo: Arc<CompanyRepository>) -> Self {
+        Self { repo, company_repo }
     }
 
     pub async fn ser_get_all_stores(&self) -> Result<Vec<Store>, AppError> {
@@ -20,19 +24,55 @@ impl StoreService {
         self.repo.rep_fetch_by_route(route).await.map(Some)
     }
 
-    pub async fn ser_create_store(&self, new_store: CreateStorePayload) -> Result<Store, AppError> {
+    pub async fn ser_create_store(&self, mut new_store: CreateStorePayload) -> Result<Store, AppError> {
+        match new_store.company_id {
+            Some(company_id) => {
+                new_store.company = self.company_repo.rep_fetch_by_id(company_id).await?.name;
+            }
+            None => {
+                if normalize_company_name(&new_store.company).is_empty() {
+                    return Err(StoreError::InvalidPayload("company or company_id is required".to_string()).into());
+                }
+                new_store.company_id = self.resolve_company_id(&new_store.company).await?;
+            }
+        }
+        new_store.tags = new_store.tags.as_deref().map(normalize_store_tags);
         self.repo.rep_create(new_store).await
     }
 
     pub async fn ser_update_store(
         &self,
         route: &str,
-        updated_store: UpdateStorePayload,
+        mut updated_store: UpdateStorePayload,
     ) -> Result<Store, AppError> {
+        match (updated_store.company_id, &updated_store.company) {
+            (Some(Some(company_id)), _) => {
+                let company = self.company_repo.rep_fetch_by_id(company_id).await?;
+                updated_store.company = Some(company.name);
+            }
+            // Nama berubah tanpa company_id: link ulang ke company yang cocok. Nama yang tidak
+            // dikenal ditolak supaya store tidak terlepas diam-diam; lepas link lewat `company_id: null`
+            (None, Some(company)) => {
+                let company_id = self.resolve_company_id(company).await?.ok_or_else(|| {
+                    CompanyError::NotFound(format!(
+                        "Company '{}' not found; create it first or pass company_id",
+                        company
+                    ))
+                })?;
+                updated_store.company_id = Some(Some(company_id));
+            }
+            _ => {}
+        }
+        updated_store.tags = updated_store.tags.as_deref().map(normalize_store_tags);
         self.repo.rep_update(route, updated_store).await
     }
 
-    pub async fn ser_delete_store(&self, route: &str) -> Result<(), AppError> {
-        self.repo.rep_delete(route).await
+    async fn resolve_company_id(&self, company: &str) -> Result<Option<uuid::Uuid>, AppError> {
+        let normalized = normalize_company_name(company);
+        Ok(self
+            .company_repo
+            .rep_fetch_by_normalized_name(&normalized)
+            .await?
+            .map(|c| c.id))
     }
 }
@@ -0,0 +1,227 @@
+use crate::business_time::parse_business_datetime;
+use crate::error::{AppError, HistoryError};
+use crate::model::promo_model::Promo;
+use crate::model::promo_store_model::PromoStore;
+use crate::model::promo_tenor_model::PromoTenor;
+use crate::model::version_model::*;
+use crate::repositories::entity_version_repository::EntityVersionRepository;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+pub struct VersionService {
+    repo: Arc<EntityVersionRepository>,
+}
+
+impl VersionService {
+    pub fn new(repo: Arc<EntityVersionRepository>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn ser_get_versions(
+        &self,
+        entity_type: VersionedEntity,
+        entity_id: &str,
+    ) -> Result<Vec<EntityVersion>, AppError> {
+        Self::validate_entity_id(entity_type, entity_id)?;
+        let versions = self.repo.rep_fetch_by_entity(entity_type, entity_id).await?;
+        if versions.is_empty() {
+            return Err(HistoryError::NotFound(format!(
+                "No versions recorded for {} '{}'",
+                entity_type.as_str(),
+                entity_id
+            ))
+            .into());
+        }
+        Ok(versions)
+    }
+
+    pub async fn ser_diff_versions(
+        &self,
+        entity_type: VersionedEntity,
+        entity_id: &str,
+        query: VersionDiffQuery,
+    ) -> Result<VersionDiff, AppError> {
+        let versions = self.ser_get_versions(entity_type, entity_id).await?;
+        let pick = |number: usize| {
+            versions
+                .iter()
+                .find(|v| v.version == number)
+                .cloned()
+                .ok_or_else(|| {
+                    HistoryError::NotFound(format!(
+                        "Version {} of {} '{}' not found (latest is {})",
+                        number,
+                        entity_type.as_str(),
+                        entity_id,
+                        versions.last().map_or(0, |v| v.version)
+                    ))
+                })
+        };
+        Ok(VersionDiff::between(pick(query.from)?, pick(query.to)?))
+    }
+
+    /// Versi terakhir yang tercatat pada atau sebelum `at`; 404 jika entity belum ada atau sudah dihapus
+    pub async fn ser_get_as_of(
+        &self,
+        entity_type: VersionedEntity,
+        entity_id: &str,
+        at: &str,
+    ) -> Result<EntityVersion, AppError> {
+        Self::validate_entity_id(entity_type, entity_id)?;
+        let at_time = Self::parse_at(at)?;
+        let version = self.repo.rep_fetch_as_of(entity_type, entity_id, at_time).await?.ok_or_else(|| {
+            HistoryError::NotFound(format!(
+                "{} '{}' did not exist at {}",
+                entity_type.as_str(),
+                entity_id,
+                at
+            ))
+        })?;
+        if version.is_delete() {
+            return Err(HistoryError::NotFound(format!(
+                "{} '{}' was deleted at {}",
+                entity_type.as_str(),
+                entity_id,
+                version.created_at
+            ))
+            .into());
+        }
+        Ok(version)
+    }
+
+    /// Term efektif tenor di store pada waktu `at`, disusun ulang dari versi PromoStore,
+    /// PromoTenor dan Promo yang berlaku saat itu (termasuk override per store).
+    pub async fn ser_get_store_terms_as_of(
+        &self,
+        store_id: Uuid,
+        query: AsOfQuery,
+    ) -> Result<StoreTermsAsOf, AppError> {
+        let at_time = Self::parse_at(&query.at)?;
+
+        let link_versions = self
+            .repo
+            .rep_fetch_by_column_until(VersionedEntity::PromoStore, "store_id", &[store_id], at_time)
+            .await?;
+        let links: Vec<(usize, PromoStore)> = Self::live(&link_versions);
+
+        let promo_ids: Vec<Uuid> = links.iter().map(|(_, link)| link.promo_id).collect();
+        let promo_versions = self
+            .repo
+            .rep_fetch_by_column_until(VersionedEntity::Promo, "promo_id", &promo_ids, at_time)
+            .await?;
+        let promos: HashMap<Uuid, (usize, Promo)> = Self::live::<Promo>(&promo_versions)
+            .into_iter()
+            .map(|(version, promo)| (promo.id_promo, (version, promo.with_status_at(at_time))))
+            .collect();
+        let tenor_versions = self
+            .repo
+            .rep_fetch_by_column_until(VersionedEntity::PromoTenor, "promo_id", &promo_ids, at_time)
+            .await?;
+        let tenors: Vec<(usize, PromoTenor)> = Self::live(&tenor_versions);
+
+        let mut result = Vec::new();
+        for (link_version, link) in &links {
+            let Some((promo_version, promo)) = promos.get(&link.promo_id) else {
+                continue;
+            };
+            for (tenor_version, tenor) in tenors.iter().filter(|(_, t)| t.promo_id == link.promo_id) {
+                if !tenor.is_available
+                    || !link.includes_tenor(tenor.id)
+                    || query.tenor.is_some_and(|t| t != tenor.tenor)
+                {
+                    continue;
+                }
+                result.push(HistoricalStoreTenor {
+                    promo: promo.clone(),
+                    tenor: link.effective_tenor(tenor),
+                    promo_version: *promo_version,
+                    promo_tenor_version: *tenor_version,
+                    promo_store_version: *link_version,
+                });
+            }
+        }
+        result.sort_by(|a, b| {
+            a.tenor
+                .promo_tenor
+                .tenor
+                .cmp(&b.tenor.promo_tenor.tenor)
+                .then(a.promo.title_promo.cmp(&b.promo.title_promo))
+        });
+
+        Ok(StoreTermsAsOf {
+            store_id,
+            at: at_time.to_rfc3339(),
+            tenors: result,
+        })
+    }
+
+    fn parse_at(at: &str) -> Result<DateTime<Utc>, AppError> {
+        parse_business_datetime(at, true).ok_or_else(|| {
+            HistoryError::InvalidQuery(format!(
+                "at must be RFC3339, 'YYYY-MM-DD HH:MM:SS' or 'YYYY-MM-DD', got: {}",
+                at
+            ))
+            .into()
+        })
+    }
+
+    /// Snapshot versi tertinggi per entity, tanpa entity yang sudah dihapus. `versions` sudah
+    /// dibatasi ke waktu as-of oleh query.
+    fn live<T: DeserializeOwned>(versions: &[EntityVersion]) -> Vec<(usize, T)> {
+        let mut by_entity: HashMap<&str, Vec<&EntityVersion>> = HashMap::new();
+        for version in versions {
+            by_entity.entry(version.entity_id.as_str()).or_default().push(version);
+        }
+
+        by_entity
+            .into_values()
+            .filter_map(|history| history.into_iter().max_by_key(|v| v.version))
+            .filter(|v| !v.is_delete())
+            .filter_map(|v| match serde_json::from_value(v.snapshot.clone()) {
+                Ok(entity) => Some((v.version, entity)),
+                Err(e) => {
+                    warn!(
+                        "Snapshot {} {} versi {} tidak bisa dibaca: {}",
+                        v.entity_type.as_str(),
+                        v.entity_id,
+                        v.version,
+                        e
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn validate_entity_id(entity_type: VersionedEntity, entity_id: &str) -> Result<(), AppError> {
+        let valid = match entity_type {
+            VersionedEntity::Promo | VersionedEntity::PromoTenor => Uuid::parse_str(entity_id).is_ok(),
+            VersionedEntity::PromoStore => {
+                // UUID berformat tetap 36 karakter, dan UUID sendiri mengandung '-'
+                entity_id
+                    .split_at_checked(36)
+                    .and_then(|(promo_id, rest)| Some((promo_id, rest.strip_prefix('-')?)))
+                    .is_some_and(|(promo_id, store_id)| {
+                        Uuid::parse_str(promo_id).is_ok() && Uuid::parse_str(store_id).is_ok()
+                    })
+            }
+        };
+        if !valid {
+            return Err(HistoryError::InvalidQuery(format!(
+                "Invalid {} id '{}' (expected {})",
+                entity_type.as_str(),
+                entity_id,
+                match entity_type {
+                    VersionedEntity::PromoStore => "{promo_id}-{store_id}",
+                    _ => "UUID",
+                }
+            ))
+            .into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,305 @@
+use crate::error::{AppError, PromoError, VoucherError};
+use crate::model::promo_model::PromoStatus;
+use crate::model::promo_tenor_model::PromoTenor;
+use crate::model::store_assignment_model::StoreScope;
+use crate::model::store_model::Store;
+use crate::model::voucher_model::*;
+use crate::repositories::promo_repository::PromoRepository;
+use crate::repositories::promo_tenor_repository::PromoTenorRepository;
+use crate::repositories::store_repository::StoreRepository;
+use crate::repositories::voucher_redemption_repository::VoucherRedemptionRepository;
+use crate::services::store_assignment_service::StoreAssignmentService;
+use chrono::Utc;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+pub struct VoucherService {
+    repo: Arc<VoucherRedemptionRepository>,
+    store_repo: Arc<StoreRepository>,
+    promo_repo: Arc<PromoRepository>,
+    promo_tenor_repo: Arc<PromoTenorRepository>,
+}
+
+struct VoucherEvaluation {
+    store: Store,
+    validation: VoucherValidation,
+    failures: Vec<VoucherError>,
+}
+
+impl VoucherService {
+    pub fn new(
+        repo: Arc<VoucherRedemptionRepository>,
+        store_repo: Arc<StoreRepository>,
+        promo_repo: Arc<PromoRepository>,
+        promo_tenor_repo: Arc<PromoTenorRepository>,
+    ) -> Self {
+        Self {
+            repo,
+            store_repo,
+            promo_repo,
+            promo_tenor_repo,
+        }
+    }
+
+    pub async fn ser_get_redemptions_by_voucher(
+        &self,
+        voucher_code: &str,
+    ) -> Result<Vec<VoucherRedemption>, AppError> {
+        self.repo.rep_fetch_by_voucher(voucher_code).await
+    }
+
+    pub async fn ser_validate_voucher(
+        &self,
+        payload: ValidateVoucherPayload,
+    ) -> Result<VoucherValidation, AppError> {
+        let evaluation = self
+            .evaluate(&payload.voucher_code, &payload.store_route, payload.amount, payload.tenor)
+            .await?;
+        Ok(evaluation.validation)
+    }
+
+    pub async fn ser_redeem_voucher(
+        &self,
+        payload: RedeemVoucherPayload,
+        scope: &StoreScope,
+    ) -> Result<VoucherRedemptionResult, AppError> {
+        if payload.redemption_key.trim().is_empty() {
+            return Err(VoucherError::InvalidPayload("redemption_key is required".to_string()).into());
+        }
+        let store = self.store_repo.rep_fetch_by_route(&payload.store_route).await?;
+        StoreAssignmentService::ensure_store(scope, store.id)?;
+
+        if let Some(existing) = self.repo.rep_fetch_by_redemption_key(&payload.redemption_key).await? {
+            return self.replay(existing, &payload).await;
+        }
+
+        let evaluation = self
+            .evaluate(&payload.voucher_code, &payload.store_route, payload.amount, payload.tenor)
+            .await?;
+
+        if let Some(failure) = evaluation.failures.into_iter().next() {
+            return Err(failure.into());
+        }
+
+        let promo_tenor = evaluation
+            .validation
+            .promo_tenor
+            .ok_or_else(|| AppError::Internal("Validated voucher has no promo tenor".to_string()))?;
+
+        // Kuota dicek ulang di database bersamaan dengan insert; evaluasi di atas hanya
+        // untuk pesan error yang lengkap
+        let outcome = self
+            .repo
+            .rep_redeem(RedeemVoucherParams {
+                p_voucher_code: payload.voucher_code.clone(),
+                p_promo_tenor_id: promo_tenor.id,
+                p_store_id: evaluation.store.id,
+                p_redemption_key: payload.redemption_key.clone(),
+                p_amount: payload.amount,
+                p_max_usage: promo_tenor.voucher_max_usage,
+                p_max_usage_per_store: promo_tenor.voucher_max_usage_per_store,
+            })
+            .await?;
+
+        match outcome {
+            RedeemOutcome::Redeemed { redemption } => Ok(VoucherRedemptionResult {
+                redemption,
+                replayed: false,
+            }),
+            RedeemOutcome::Replayed { redemption } => self.replay(redemption, &payload).await,
+            RedeemOutcome::GlobalLimitReached => Err(VoucherError::UsageLimitReached(format!(
+                "global limit of {} redemptions reached",
+                promo_tenor.voucher_max_usage.unwrap_or_default()
+            ))
+            .into()),
+            RedeemOutcome::StoreLimitReached => Err(VoucherError::UsageLimitReached(format!(
+                "store limit of {} redemptions reached",
+                promo_tenor.voucher_max_usage_per_store.unwrap_or_default()
+            ))
+            .into()),
+        }
+    }
+
+    /// Kembalikan redemption lama untuk `redemption_key` yang sama, asal payload-nya identik;
+    /// key yang dipakai ulang untuk voucher/store/amount/tenor lain ditolak 409
+    async fn replay(
+        &self,
+        existing: VoucherRedemption,
+        payload: &RedeemVoucherPayload,
+    ) -> Result<VoucherRedemptionResult, AppError> {
+        let mut mismatches = Vec::new();
+        if existing.voucher_code != payload.voucher_code {
+            mismatches.push("voucher_code");
+        }
+        if existing.amount != payload.amount {
+            mismatches.push("amount");
+        }
+        let store = self.store_repo.rep_fetch_by_route(&payload.store_route).await?;
+        if existing.store_id != store.id {
+            mismatches.push("store_route");
+        }
+        if let Some(tenor) = payload.tenor {
+            let original_tenor = self
+                .promo_tenor_repo
+                .rep_fetch_by_voucher(&existing.voucher_code)
+                .await?
+                .into_iter()
+                .find(|pt| pt.id == existing.promo_tenor_id)
+                .map(|pt| pt.tenor);
+            if original_tenor != Some(tenor) {
+                mismatches.push("tenor");
+            }
+        }
+
+        if !mismatches.is_empty() {
+            return Err(VoucherError::Conflict(format!(
+                "redemption_key '{}' was already used with a different {}",
+                payload.redemption_key,
+                mismatches.join(", ")
+            ))
+            .into());
+        }
+
+        info!("Redemption key {} sudah dipakai, mengembalikan data lama.", payload.redemption_key);
+        Ok(VoucherRedemptionResult {
+            redemption: existing,
+            replayed: true,
+        })
+    }
+
+    async fn evaluate(
+        &self,
+        voucher_code: &str,
+        store_route: &str,
+        amount: i64,
+        tenor: Option<i32>,
+    ) -> Result<VoucherEvaluation, AppError> {
+        if voucher_code.trim().is_empty() {
+            return Err(VoucherError::InvalidPayload("voucher_code is required".to_string()).into());
+        }
+        if amount <= 0 {
+            return Err(VoucherError::InvalidPayload(format!(
+                "Amount must be greater than 0, got: {}",
+                amount
+            ))
+            .into());
+        }
+
+        let store = self.store_repo.rep_fetch_by_route(store_route).await?;
+
+        // Term tenor promo yang belum published tidak boleh terlihat dari endpoint publik
+        let published: HashSet<Uuid> = match self.promo_repo.rep_fetch_all().await {
+            Ok(promos) => promos
+                .into_iter()
+                .filter(|p| p.is_published())
+                .map(|p| p.id_promo)
+                .collect(),
+            Err(AppError::Promo(PromoError::NotFound(_))) => HashSet::new(),
+            Err(e) => return Err(e),
+        };
+        let mut voucher_tenors = self.promo_tenor_repo.rep_fetch_by_voucher(voucher_code).await?;
+        voucher_tenors.retain(|vt| published.contains(&vt.promo_id));
+        if voucher_tenors.is_empty() {
+            return Err(VoucherError::NotFound(format!("Voucher '{}' not found", voucher_code)).into());
+        }
+
+        let store_tenors = self.promo_tenor_repo.rep_fetch_by_store_id(store.id).await?;
+        // Pakai term efektif store (termasuk override min_transaction)
+        let promo_tenor: Option<PromoTenor> = store_tenors
+            .into_iter()
+            .map(|st| st.promo_tenor)
+            .filter(|st| voucher_tenors.iter().any(|vt| vt.id == st.id))
+            .find(|st| tenor.is_none_or(|t| st.tenor == t));
+
+        let mut failures = Vec::new();
+        let usage_count = self.repo.rep_count_by_voucher(voucher_code).await?;
+        let store_usage_count = self
+            .repo
+            .rep_count_by_voucher_and_store(voucher_code, store.id)
+            .await?;
+
+        let (remaining_usage, remaining_store_usage) = match &promo_tenor {
+            None => {
+                failures.push(VoucherError::NotApplicable(format!(
+                    "voucher '{}' is not available at store '{}'",
+                    voucher_code, store_route
+                )));
+                (None, None)
+            }
+            Some(pt) => {
+                let now = Utc::now();
+
+                if let (Some(raw), Some(expires_at)) = (pt.voucher_expires_at.as_deref(), pt.voucher_expires_at())
+                    && now > expires_at
+                {
+                    failures.push(VoucherError::Expired(format!("voucher expired at {}", raw)));
+                }
+
+                let promos = self.promo_repo.rep_get_by_store_id(store.id).await?;
+                match promos.iter().find(|p| p.id_promo == pt.promo_id) {
+                    Some(promo) if !promo.is_active => failures.push(VoucherError::NotApplicable(
+                        "promo is not active".to_string(),
+                    )),
+                    Some(promo) if promo.status_at(now) != PromoStatus::Active => failures.push(
+                        VoucherError::NotApplicable("outside date window".to_string()),
+                    ),
+                    Some(promo) if promo.budget_exhausted => failures.push(VoucherError::NotApplicable(
+                        "promo budget exhausted".to_string(),
+                    )),
+                    Some(_) => {}
+                    None => failures.push(VoucherError::NotApplicable(
+                        "promo not found for store".to_string(),
+                    )),
+                }
+
+                if pt.min_transaction > amount {
+                    failures.push(VoucherError::NotApplicable(format!(
+                        "min_transaction not met (minimum {})",
+                        pt.min_transaction
+                    )));
+                }
+
+                let remaining_usage = pt
+                    .voucher_max_usage
+                    .map(|max| (max.max(0) as usize).saturating_sub(usage_count));
+                let remaining_store_usage = pt
+                    .voucher_max_usage_per_store
+                    .map(|max| (max.max(0) as usize).saturating_sub(store_usage_count));
+
+                if remaining_usage == Some(0) {
+                    failures.push(VoucherError::UsageLimitReached(format!(
+                        "global limit of {} redemptions reached",
+                        pt.voucher_max_usage.unwrap_or_default()
+                    )));
+                }
+                if remaining_store_usage == Some(0) {
+                    failures.push(VoucherError::UsageLimitReached(format!(
+                        "store limit of {} redemptions reached",
+                        pt.voucher_max_usage_per_store.unwrap_or_default()
+                    )));
+                }
+
+                (remaining_usage, remaining_store_usage)
+            }
+        };
+
+        let validation = VoucherValidation {
+            voucher_code: voucher_code.to_string(),
+            valid: failures.is_empty(),
+            reasons: failures.iter().map(|f| f.to_string()).collect(),
+            promo_tenor,
+            usage_count,
+            store_usage_count,
+            remaining_usage,
+            remaining_store_usage,
+        };
+
+        Ok(VoucherEvaluation {
+            store,
+            validation,
+            failures,
+        })
+    }
+}
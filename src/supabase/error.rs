@@ -105,7 +105,7 @@ pub(crate) fn extract_table_name(message: &str) -> Option<String> {
     message
         .split('"')
         .nth(1)
-        .and_then(|s| s.split('.').last())
+        .and_then(|s| s.split('.').next_back())
         .map(|s| s.to_string())
 }
 
@@ -113,7 +113,7 @@ pub(crate) fn extract_table_and_column(message: &str) -> Option<(String, String)
     let parts: Vec<&str> = message.split('"').collect();
     if parts.len() >= 4 {
         let column = parts[1].to_string();
-        let table = parts[3].split('.').last().unwrap_or("").to_string();
+        let table = parts[3].split('.').next_back().unwrap_or("").to_string();
         if !table.is_empty() && !column.is_empty() {
             return Some((table, column));
         }
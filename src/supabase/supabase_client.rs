@@ -114,6 +114,15 @@ impl SupabaseClient {
         }
     }
 
+    /// Panggil fungsi Postgres lewat `/rpc/{function}`; seluruh isi fungsi berjalan dalam satu transaksi
+    pub async fn rpc(&self, function: &str, params: &impl Serialize) -> SupabaseResult<Value> {
+        let json = serde_json::to_value(params).map_err(|e| SupabaseError::SerializationError {
+            message: format!("Failed to serialize rpc params: {}", e),
+        })?;
+
+        self.post(&format!("rpc/{}", function), &json).await
+    }
+
     // Method HTTP dasar dengan better error handling
     async fn get(&self, path: &str) -> SupabaseResult<Value> {
         let url = self.build_url(path);
@@ -174,6 +183,37 @@ impl SupabaseClient {
         self.handle_response_json("DELETE", path, response).await
     }
 
+    /// HEAD dengan `Prefer: count=exact`; jumlah baris dibaca dari header `Content-Range` (`*/N`)
+    async fn head_count(&self, path: &str) -> SupabaseResult<usize> {
+        let url = self.build_url(path);
+        debug!("HEAD request to: {}", url);
+
+        let response = self
+            .client
+            .request(reqwest::Method::HEAD, &url)
+            .header("apikey", &self.api_key)
+            .header("Authorization", format!("Bearer {}", &self.api_key))
+            .header("Prefer", "count=exact")
+            .send()
+            .await
+            .map_err(|e| SupabaseError::NetworkError { source: e })?;
+
+        if !response.status().is_success() {
+            return self.handle_response_json("HEAD", path, response).await.map(|_| 0);
+        }
+
+        response
+            .headers()
+            .get("content-range")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|range| range.rsplit('/').next())
+            .and_then(|total| total.parse().ok())
+            .ok_or_else(|| SupabaseError::QueryError {
+                message: "Missing row count in Content-Range header".to_string(),
+                details: None,
+            })
+    }
+
     // Helper methods
     fn build_url(&self, path: &str) -> String {
         format!(
@@ -248,24 +288,22 @@ impl SupabaseClient {
 
     fn handle_bad_request(&self, body: &str, details: Option<Value>) -> SupabaseResult<Value> {
         // Parse Supabase-specific error messages
-        if body.contains("relation") && body.contains("does not exist") {
-            if let Some(details) = &details {
-                if let Some(message) = details.get("message").and_then(|m| m.as_str()) {
-                    if let Some(table) = extract_table_name(message) {
-                        return Err(SupabaseError::TableNotFound { table });
-                    }
-                }
-            }
+        if body.contains("relation")
+            && body.contains("does not exist")
+            && let Some(details) = &details
+            && let Some(message) = details.get("message").and_then(|m| m.as_str())
+            && let Some(table) = extract_table_name(message)
+        {
+            return Err(SupabaseError::TableNotFound { table });
         }
 
-        if body.contains("column") && body.contains("does not exist") {
-            if let Some(details) = &details {
-                if let Some(message) = details.get("message").and_then(|m| m.as_str()) {
-                    if let Some((table, column)) = extract_table_and_column(message) {
-                        return Err(SupabaseError::ColumnNotFound { table, column });
-                    }
-                }
-            }
+        if body.contains("column")
+            && body.contains("does not exist")
+            && let Some(details) = &details
+            && let Some(message) = details.get("message").and_then(|m| m.as_str())
+            && let Some((table, column)) = extract_table_and_column(message)
+        {
+            return Err(SupabaseError::ColumnNotFound { table, column });
         }
 
         if body.contains("failed to parse filter") {
@@ -293,15 +331,14 @@ impl SupabaseClient {
         details: Option<Value>,
     ) -> SupabaseResult<Value> {
         // Handle validation errors
-        if let Some(details) = &details {
-            if let Some(message) = details.get("message").and_then(|m| m.as_str()) {
-                if message.contains("failed to parse filter") {
-                    return Err(SupabaseError::QueryError {
-                        message: "Invalid filter syntax".to_string(),
-                        details: Some(details.clone()),
-                    });
-                }
-            }
+        if let Some(details) = &details
+            && let Some(message) = details.get("message").and_then(|m| m.as_str())
+            && message.contains("failed to parse filter")
+        {
+            return Err(SupabaseError::QueryError {
+                message: "Invalid filter syntax".to_string(),
+                details: Some(details.clone()),
+            });
         }
 
         Err(SupabaseError::ValidationError {
@@ -372,6 +409,30 @@ impl<'a, T: DeserializeOwned> QueryBuilder<'a, T> {
         self
     }
 
+    /// Filter: greater than or equal (gte) - untuk string (mis. timestamp ISO 8601)
+    pub fn gte_str(mut self, column: &str, value: &str) -> Self {
+        let encoded_value = encode(value).to_string();
+        self.filters
+            .push(format!("{}={}.{}", column, "gte", encoded_value));
+        self
+    }
+
+    /// Filter: less than (lt) - untuk string (mis. timestamp ISO 8601)
+    pub fn lt_str(mut self, column: &str, value: &str) -> Self {
+        let encoded_value = encode(value).to_string();
+        self.filters
+            .push(format!("{}={}.{}", column, "lt", encoded_value));
+        self
+    }
+
+    /// Filter: less than or equal (lte) - untuk string (mis. timestamp ISO 8601)
+    pub fn lte_str(mut self, column: &str, value: &str) -> Self {
+        let encoded_value = encode(value).to_string();
+        self.filters
+            .push(format!("{}={}.{}", column, "lte", encoded_value));
+        self
+    }
+
     /// Filter: like (pattern matching)
     pub fn like(mut self, column: &str, pattern: &str) -> Self {
         let encoded_pattern = encode(pattern).to_string();
@@ -392,22 +453,20 @@ impl<'a, T: DeserializeOwned> QueryBuilder<'a, T> {
     pub fn r#in(mut self, column: &str, values: &[&str]) -> Self {
         let encoded_values: Vec<String> = values.iter().map(|v| encode(v).to_string()).collect();
         let values_str = encoded_values.join(",");
-        self.filters.push(format!(
-            "{}={}.{}",
-            column,
-            "in",
-            format!("({})", values_str)
-        ));
+        self.filters
+            .push(format!("{}={}.({})", column, "in", values_str));
         self
     }
 
     /// Filter: is null
+    #[allow(clippy::wrong_self_convention)]
     pub fn is_null(mut self, column: &str) -> Self {
         self.filters.push(format!("{}={}", column, "is.null"));
         self
     }
 
     /// Filter: is not null
+    #[allow(clippy::wrong_self_convention)]
     pub fn is_not_null(mut self, column: &str) -> Self {
         self.filters.push(format!("{}={}", column, "not.is.null"));
         self
@@ -556,31 +615,15 @@ impl<'a, T: DeserializeOwned> QueryBuilder<'a, T> {
         Ok(results)
     }
 
-    /// Count records
+    /// Count records di database tanpa mengambil barisnya
     pub async fn count(self) -> SupabaseResult<usize> {
-        let mut query_params = vec!["count=exact".to_string()];
-
-        // Tambahkan filter
-        if !self.filters.is_empty() {
-            query_params.extend(self.filters);
-        }
-
-        let path = format!("{}?{}", self.table, query_params.join("&"));
-        let response = self.client.get(&path).await?;
-
-        // Extract count from response
-        if let Some(count) = response
-            .get(0)
-            .and_then(|v| v.as_array())
-            .map(|arr| arr.len())
-        {
-            Ok(count)
+        let path = if self.filters.is_empty() {
+            self.table
         } else {
-            // Fallback: count the results manually
-            let results: Vec<T> = serde_json::from_value(response)
-                .map_err(|e| SupabaseError::JsonError { source: e })?;
-            Ok(results.len())
-        }
+            format!("{}?{}", self.table, self.filters.join("&"))
+        };
+
+        self.client.head_count(&path).await
     }
 }
 
@@ -597,6 +640,7 @@ impl<'a, T: DeserializeOwned> QueryBuilder<'a, T> {
     }
 
     /// Filter by boolean column
+    #[allow(clippy::wrong_self_convention)]
     pub fn is_verified(self, verified: bool) -> Self {
         self.eq_bool("verified", verified)
     }
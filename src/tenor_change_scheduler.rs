@@ -0,0 +1,18 @@
+use crate::app_state::AppState;
+use crate::scheduler;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Background job yang menerapkan perubahan PromoTenor terjadwal saat `effective_at` tercapai.
+pub async fn run(state: Arc<AppState>) {
+    scheduler::run_loop("Tenor change scheduler", "TENOR_CHANGE_SCHEDULER", || async {
+        match state.promo_tenor_service.ser_apply_due_changes().await {
+            Ok(next_due) => next_due,
+            Err(e) => {
+                warn!("Tenor change scheduler gagal: {}", e);
+                None
+            }
+        }
+    })
+    .await;
+}